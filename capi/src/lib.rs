@@ -0,0 +1,5 @@
+//! Packages the C ABI of [`libfin::ffi`] as a shared and a static C library.
+//!
+//! See the README of this crate for building and linking the library.
+
+pub use libfin::ffi::*;
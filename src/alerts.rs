@@ -0,0 +1,576 @@
+//! Alerts fired by conditions on streaming indicator values.
+//!
+//! An [`AlertMonitor`] holds rules, each watching a named input such as `"rsi"` or
+//! `"macd_histogram"`. Every new value of an input is checked against the rules watching it, and
+//! the callback of every rule whose condition holds is called with the resulting [`Alert`].
+//! Callbacks can forward alerts anywhere, for example to a channel with
+//! `move |alert| sender.send(alert.clone()).unwrap()`. Crossovers between two lines are watched
+//! by streaming their difference and using a crossing of zero.
+//!
+//! An [`AlertPolicy`] limits how often a rule fires, with a cooldown between alerts, a number of
+//! confirmation values, and a re-arming condition that implements hysteresis. Fired alerts can
+//! be kept for later audit in an append-only [`AlertLog`]. Alerts are stamped with the time
+//! passed to [`AlertMonitor::update`], or with the time of a [`Clock`] through
+//! [`AlertMonitor::update_with_clock`].
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+
+use crate::clock::Clock;
+use crate::json::{escape_json, json_number};
+
+/// Detector of a pattern in a window of values, oldest first.
+pub type PatternDetector = Box<dyn Fn(&[f64]) -> bool>;
+
+/// Condition checked by an alert rule against the values of its input.
+pub enum Condition {
+    /// The value is above the threshold
+    Above(f64),
+    /// The value is below the threshold
+    Below(f64),
+    /// The value moved from at or below the threshold to above it
+    CrossesAbove(f64),
+    /// The value moved from at or above the threshold to below it
+    CrossesBelow(f64),
+    /// A pattern detected in the latest `lookback` values, oldest first. The detector is only
+    /// called once `lookback` values were received.
+    Pattern {
+        /// Number of values passed to the detector
+        lookback: usize,
+        /// Returns `true` if the pattern is present
+        detect: PatternDetector,
+    },
+}
+
+impl Condition {
+    /// Returns `true` if the condition holds for the latest value of `history`, oldest first.
+    fn holds(&self, history: &[f64]) -> bool {
+        let current = history.last().copied();
+        let previous = history.len().checked_sub(2).map(|i| history[i]);
+        match self {
+            Condition::Above(threshold) => current.is_some_and(|c| c > *threshold),
+            Condition::Below(threshold) => current.is_some_and(|c| c < *threshold),
+            Condition::CrossesAbove(threshold) => previous
+                .zip(current)
+                .is_some_and(|(p, c)| p <= *threshold && c > *threshold),
+            Condition::CrossesBelow(threshold) => previous
+                .zip(current)
+                .is_some_and(|(p, c)| p >= *threshold && c < *threshold),
+            Condition::Pattern { lookback, detect } => {
+                history.len() >= *lookback && detect(&history[history.len() - lookback..])
+            }
+        }
+    }
+
+    /// Number of latest values the condition needs to be checked.
+    fn lookback(&self) -> usize {
+        match self {
+            Condition::Above(_) | Condition::Below(_) => 1,
+            Condition::CrossesAbove(_) | Condition::CrossesBelow(_) => 2,
+            Condition::Pattern { lookback, .. } => *lookback,
+        }
+    }
+}
+
+/// Limits on how often an alert rule fires.
+///
+/// The default policy fires on every value for which the condition holds.
+#[derive(Default)]
+pub struct AlertPolicy {
+    /// Minimum number of values between two alerts of the rule. With a cooldown of 2, a rule that
+    /// fired on one value can fire again on the third value after it.
+    pub cooldown: usize,
+    /// Number of consecutive values the condition must hold for before the rule fires. Zero and
+    /// one both fire on the first value for which the condition holds.
+    pub confirmation: usize,
+    /// Condition that must hold after an alert before the rule can fire again. For example, a
+    /// rule on `Condition::Above(70.0)` re-armed by `Condition::Below(65.0)` fires once when RSI
+    /// enters overbought territory and again only after it has clearly left it.
+    pub rearm: Option<Condition>,
+}
+
+/// An alert fired by a rule of an [`AlertMonitor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// Name of the rule that fired
+    pub rule: String,
+    /// Name of the input the rule watches
+    pub input: String,
+    /// Timestamp of the value that fired the rule
+    pub time: i64,
+    /// Value that fired the rule
+    pub value: f64,
+}
+
+/// A condition on an input and the callback called when it holds.
+struct Rule {
+    name: String,
+    input: String,
+    condition: Condition,
+    policy: AlertPolicy,
+    callback: Box<dyn FnMut(&Alert)>,
+    history: VecDeque<f64>,
+    streak: usize,
+    since_alert: Option<usize>,
+    armed: bool,
+}
+
+impl Rule {
+    /// Records a new value and returns `true` if the rule fires on it.
+    fn fires(&mut self, value: f64) -> bool {
+        let lookback = self
+            .policy
+            .rearm
+            .as_ref()
+            .map_or(0, Condition::lookback)
+            .max(self.condition.lookback());
+        self.history.push_back(value);
+        if self.history.len() > lookback {
+            self.history.pop_front();
+        }
+        let history = self.history.make_contiguous();
+
+        if let Some(since_alert) = self.since_alert.as_mut() {
+            *since_alert += 1;
+        }
+        if !self.armed && self.policy.rearm.as_ref().is_some_and(|c| c.holds(history)) {
+            self.armed = true;
+        }
+        self.streak = if self.condition.holds(history) {
+            self.streak + 1
+        } else {
+            0
+        };
+
+        let fires = self.armed
+            && self.streak >= self.policy.confirmation.max(1)
+            && self.since_alert.is_none_or(|n| n > self.policy.cooldown);
+        if fires {
+            self.since_alert = Some(0);
+            self.armed = self.policy.rearm.is_none();
+        }
+        fires
+    }
+}
+
+/// Checks streaming values against registered rules and fires alerts.
+#[derive(Default)]
+pub struct AlertMonitor {
+    rules: Vec<Rule>,
+}
+
+impl AlertMonitor {
+    /// Creates a monitor without any rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a rule named `name` that calls `callback` whenever `condition` holds for a new
+    /// value of `input`.
+    pub fn add_rule<F>(&mut self, name: &str, input: &str, condition: Condition, callback: F)
+    where
+        F: FnMut(&Alert) + 'static,
+    {
+        self.add_rule_with_policy(name, input, condition, AlertPolicy::default(), callback);
+    }
+
+    /// Registers a rule named `name` that calls `callback` when `condition` holds for a new value
+    /// of `input` and `policy` allows it to fire.
+    pub fn add_rule_with_policy<F>(
+        &mut self,
+        name: &str,
+        input: &str,
+        condition: Condition,
+        policy: AlertPolicy,
+        callback: F,
+    ) where
+        F: FnMut(&Alert) + 'static,
+    {
+        self.rules.push(Rule {
+            name: name.to_string(),
+            input: input.to_string(),
+            condition,
+            policy,
+            callback: Box::new(callback),
+            history: VecDeque::new(),
+            streak: 0,
+            since_alert: None,
+            armed: true,
+        });
+    }
+
+    /// Returns the number of registered rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns `true` if no rule is registered.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Records a new value of `input`, calls the callbacks of the rules it fires, and returns the
+    /// fired alerts in registration order.
+    pub fn update(&mut self, input: &str, time: i64, value: f64) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for rule in self.rules.iter_mut().filter(|rule| rule.input == input) {
+            if rule.fires(value) {
+                let alert = Alert {
+                    rule: rule.name.clone(),
+                    input: rule.input.clone(),
+                    time,
+                    value,
+                };
+                (rule.callback)(&alert);
+                alerts.push(alert);
+            }
+        }
+        alerts
+    }
+
+    /// Records a new value of `input` at the current time of `clock`, calls the callbacks of the
+    /// rules it fires, and returns the fired alerts in registration order.
+    pub fn update_with_clock<C: Clock>(
+        &mut self,
+        clock: &C,
+        input: &str,
+        value: f64,
+    ) -> Vec<Alert> {
+        self.update(input, clock.now(), value)
+    }
+}
+
+/// An append-only log of alerts in the JSON Lines format, one alert per line.
+///
+/// Every line is a JSON object with the `rule`, `input`, `time`, and `value` of an alert, e.g.
+/// `{"rule":"overbought","input":"rsi","time":1700000000,"value":72.5}`. Values that are not
+/// finite are written as `null`. Logs can be read back with [`read_alert_log`].
+#[derive(Debug)]
+pub struct AlertLog<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> AlertLog<W> {
+    /// Creates a log appending to `writer`, for example a file opened in append mode.
+    pub fn new(writer: W) -> Self {
+        AlertLog { writer }
+    }
+
+    /// Appends an alert to the log and flushes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of the underlying writer.
+    pub fn record(&mut self, alert: &Alert) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            "{{\"rule\":\"{}\",\"input\":\"{}\",\"time\":{},\"value\":{}}}",
+            escape_json(&alert.rule),
+            escape_json(&alert.input),
+            alert.time,
+            json_number(alert.value)
+        )?;
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// A value of a flat JSON object. Numbers are kept as written so integers keep their precision.
+enum JsonValue {
+    String(String),
+    Number(String),
+    Null,
+}
+
+/// Parses a flat JSON object of string, number, and null values.
+fn parse_flat_object(line: &str) -> Option<Vec<(String, JsonValue)>> {
+    let mut chars = line.trim().chars().peekable();
+    let mut fields = Vec::new();
+    let skip_whitespace = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    };
+    let parse_string = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        let mut s = String::new();
+        loop {
+            match chars.next()? {
+                '"' => return Some(s),
+                '\\' => match chars.next()? {
+                    'n' => s.push('\n'),
+                    'r' => s.push('\r'),
+                    't' => s.push('\t'),
+                    'u' => {
+                        let code = chars.by_ref().take(4).collect::<String>();
+                        s.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                    }
+                    c => s.push(c),
+                },
+                c => s.push(c),
+            }
+        }
+    };
+
+    if chars.next()? != '{' {
+        return None;
+    }
+    skip_whitespace(&mut chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return chars.next().is_none().then_some(fields);
+    }
+    loop {
+        skip_whitespace(&mut chars);
+        if chars.next()? != '"' {
+            return None;
+        }
+        let key = parse_string(&mut chars)?;
+        skip_whitespace(&mut chars);
+        if chars.next()? != ':' {
+            return None;
+        }
+        skip_whitespace(&mut chars);
+        let value = match chars.peek()? {
+            '"' => {
+                chars.next();
+                JsonValue::String(parse_string(&mut chars)?)
+            }
+            _ => {
+                let mut token = String::new();
+                while chars
+                    .peek()
+                    .is_some_and(|c| !c.is_whitespace() && *c != ',' && *c != '}')
+                {
+                    token.extend(chars.next());
+                }
+                match token.as_str() {
+                    "null" => JsonValue::Null,
+                    "" => return None,
+                    _ => JsonValue::Number(token),
+                }
+            }
+        };
+        fields.push((key, value));
+        skip_whitespace(&mut chars);
+        match chars.next()? {
+            ',' => continue,
+            '}' => return chars.next().is_none().then_some(fields),
+            _ => return None,
+        }
+    }
+}
+
+/// Reads the alerts recorded by an [`AlertLog`], in the order they were recorded.
+///
+/// Empty lines are skipped and `null` values are read as `NaN`.
+///
+/// # Arguments
+///
+/// * `reader` - A reader over the log, for example a `BufReader` over its file.
+///
+/// # Returns
+///
+/// A Result containing the recorded alerts or an I/O error.
+///
+/// # Errors
+///
+/// Returns the errors of the underlying reader, and an `io::ErrorKind::InvalidData` error if a
+/// line is not a valid alert record.
+pub fn read_alert_log<R: BufRead>(reader: R) -> io::Result<Vec<Alert>> {
+    let mut alerts = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Line {} is not a valid alert record", number + 1),
+            )
+        };
+
+        let mut rule = None;
+        let mut input = None;
+        let mut time = None;
+        let mut value = None;
+        for (key, field) in parse_flat_object(&line).ok_or_else(invalid)? {
+            match (key.as_str(), field) {
+                ("rule", JsonValue::String(s)) => rule = Some(s),
+                ("input", JsonValue::String(s)) => input = Some(s),
+                ("time", JsonValue::Number(t)) => time = Some(t.parse().map_err(|_| invalid())?),
+                ("value", JsonValue::Number(v)) => value = Some(v.parse().map_err(|_| invalid())?),
+                ("value", JsonValue::Null) => value = Some(f64::NAN),
+                _ => (),
+            }
+        }
+        alerts.push(Alert {
+            rule: rule.ok_or_else(invalid)?,
+            input: input.ok_or_else(invalid)?,
+            time: time.ok_or_else(invalid)?,
+            value: value.ok_or_else(invalid)?,
+        });
+    }
+    Ok(alerts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SimulatedClock;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_alert_monitor() {
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let (sender, receiver) = mpsc::channel();
+
+        let mut monitor = AlertMonitor::new();
+        let log = Rc::clone(&fired);
+        monitor.add_rule("overbought", "rsi", Condition::Above(70.0), move |alert| {
+            log.borrow_mut().push(alert.time)
+        });
+        monitor.add_rule(
+            "bullish cross",
+            "macd_histogram",
+            Condition::CrossesAbove(0.0),
+            move |alert| sender.send(alert.clone()).unwrap(),
+        );
+        assert_eq!(monitor.len(), 2);
+
+        // Thresholds fire on every matching value
+        for (time, rsi) in [(1, 65.0), (2, 68.0), (3, 72.0), (4, 71.0)] {
+            monitor.update("rsi", time, rsi);
+        }
+        assert_eq!(*fired.borrow(), vec![3, 4]);
+
+        // Crossings only fire on the value that crosses
+        let mut alerts = Vec::new();
+        for (time, histogram) in [(1, -0.5), (2, 0.3), (3, 0.4), (4, -0.1), (5, 0.2)] {
+            alerts.extend(monitor.update("macd_histogram", time, histogram));
+        }
+        let times = receiver.try_iter().map(|a| a.time).collect::<Vec<i64>>();
+        assert_eq!(times, vec![2, 5]);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].rule, "bullish cross");
+        assert_eq!(alerts[0].input, "macd_histogram");
+        assert_eq!(alerts[0].value, 0.3);
+
+        // Values of unknown inputs are ignored
+        assert!(monitor.update("volume", 6, 1e6).is_empty());
+
+        // Alerts are stamped with the time of the clock
+        let clock = SimulatedClock::new(100);
+        clock.advance(5);
+        let alerts = monitor.update_with_clock(&clock, "rsi", 75.0);
+        assert_eq!(alerts[0].time, 105);
+    }
+
+    #[test]
+    fn test_pattern_alert() {
+        let mut monitor = AlertMonitor::new();
+        monitor.add_rule(
+            "three rising",
+            "rsi",
+            Condition::Pattern {
+                lookback: 3,
+                detect: Box::new(|v| v[0] < v[1] && v[1] < v[2]),
+            },
+            |_| (),
+        );
+        let fired = [30.0, 35.0, 40.0, 38.0, 39.0, 41.0]
+            .iter()
+            .enumerate()
+            .filter(|(time, rsi)| !monitor.update("rsi", *time as i64, **rsi).is_empty())
+            .map(|(time, _)| time)
+            .collect::<Vec<usize>>();
+        assert_eq!(fired, vec![2, 5]);
+    }
+
+    /// Feeds `values` to a monitor holding a single rule and returns the indices that fired.
+    fn fired(monitor: &mut AlertMonitor, values: &[f64]) -> Vec<usize> {
+        values
+            .iter()
+            .enumerate()
+            .filter(|(i, value)| !monitor.update("rsi", *i as i64, **value).is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[test]
+    fn test_alert_policy() {
+        let values = [72.0, 75.0, 71.0, 68.0, 66.0, 71.0, 64.0, 73.0, 74.0];
+
+        // Test case with a cooldown
+        let mut monitor = AlertMonitor::new();
+        let policy = AlertPolicy {
+            cooldown: 2,
+            ..Default::default()
+        };
+        monitor.add_rule_with_policy("overbought", "rsi", Condition::Above(70.0), policy, |_| ());
+        assert_eq!(fired(&mut monitor, &values), vec![0, 5, 8]);
+
+        // Test case with confirmation values
+        let mut monitor = AlertMonitor::new();
+        let policy = AlertPolicy {
+            confirmation: 2,
+            ..Default::default()
+        };
+        monitor.add_rule_with_policy("overbought", "rsi", Condition::Above(70.0), policy, |_| ());
+        assert_eq!(fired(&mut monitor, &values), vec![1, 2, 8]);
+
+        // Test case with hysteresis
+        let mut monitor = AlertMonitor::new();
+        let policy = AlertPolicy {
+            rearm: Some(Condition::Below(65.0)),
+            ..Default::default()
+        };
+        monitor.add_rule_with_policy("overbought", "rsi", Condition::Above(70.0), policy, |_| ());
+        assert_eq!(fired(&mut monitor, &values), vec![0, 7]);
+    }
+
+    #[test]
+    fn test_alert_log() {
+        let alerts = [
+            Alert {
+                rule: "overbought".to_string(),
+                input: "rsi".to_string(),
+                time: 1_700_000_000,
+                value: 72.5,
+            },
+            Alert {
+                rule: "say \"hi\"\n".to_string(),
+                input: "c:\\feed".to_string(),
+                time: -5,
+                value: f64::INFINITY,
+            },
+        ];
+        let mut log = AlertLog::new(Vec::new());
+        for alert in &alerts {
+            log.record(alert).unwrap();
+        }
+        let written = String::from_utf8(log.into_inner()).unwrap();
+        assert_eq!(
+            written.lines().next(),
+            Some(r#"{"rule":"overbought","input":"rsi","time":1700000000,"value":72.5}"#)
+        );
+
+        // Records are read back in order, with non-finite values as `NaN`
+        let read = read_alert_log(written.as_bytes()).unwrap();
+        assert_eq!(read[0], alerts[0]);
+        assert_eq!(read[1].rule, alerts[1].rule);
+        assert_eq!(read[1].input, alerts[1].input);
+        assert_eq!(read[1].time, -5);
+        assert!(read[1].value.is_nan());
+
+        // Test case with an invalid record
+        let result = read_alert_log(&b"{\"rule\":\"a\",\"time\":1}\n"[..]);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,240 @@
+//! Fixed coupon bonds on a schedule of whole periods, and the dedication of a bond portfolio to a
+//! schedule of liabilities.
+//!
+//! Periods are numbered from 1, the first coupon date, so a bond maturing in period `m` pays its
+//! coupon in every period up to `m` and its face value together with the last coupon.
+
+use crate::IndicatorError;
+
+/// A fixed coupon bond candidate for [`match_cash_flows`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bond {
+    /// Price of one bond
+    pub price: f64,
+    /// Coupon paid per bond in every period
+    pub coupon: f64,
+    /// Face value repaid per bond at maturity
+    pub face: f64,
+    /// Period of the last coupon and the repayment
+    pub maturity: usize,
+}
+
+impl Bond {
+    /// Returns the cash paid per bond in `period`.
+    pub fn cash_flow(&self, period: usize) -> f64 {
+        match period {
+            0 => 0.0,
+            p if p < self.maturity => self.coupon,
+            p if p == self.maturity => self.coupon + self.face,
+            _ => 0.0,
+        }
+    }
+
+    /// Calculates the price of the bond at a yield per period, discounting every cash flow at
+    /// that yield.
+    pub fn price_at_yield(&self, yield_per_period: f64) -> f64 {
+        (1..=self.maturity)
+            .map(|p| self.cash_flow(p) / (1.0 + yield_per_period).powi(p as i32))
+            .sum()
+    }
+}
+
+/// A bond portfolio dedicated to a liability schedule, produced by [`match_cash_flows`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CashFlowMatch {
+    /// Number of bonds bought of every candidate, which may be fractional
+    pub quantities: Vec<f64>,
+    /// Price of the whole portfolio
+    pub cost: f64,
+    /// Cash received in every period beyond what its liability and the later liabilities it
+    /// funds need
+    pub surplus: Vec<f64>,
+}
+
+/// Selects bonds from the candidates whose cash flows meet a schedule of liabilities.
+///
+/// Liabilities are funded from the last to the first. Each is funded by the candidate maturing at
+/// or before it that delivers cash at the lowest price per unit, with early repayments carried to
+/// the liability at `reinvestment_rate` per period, and the coupons of the bonds bought reduce the
+/// earlier liabilities still to be funded. This is the classic dedication heuristic rather than a
+/// linear program, so the cost can exceed that of the cheapest portfolio when coupon-rich
+/// candidates are worth buying for their coupons alone.
+///
+/// # Arguments
+///
+/// * `bonds` - The candidate bonds.
+/// * `liabilities` - The payment due in every period, where index `i` is due in period `i + 1`.
+/// * `reinvestment_rate` - The return per period earned on cash held from a repayment until the
+///   liability it funds.
+///
+/// # Returns
+///
+/// A Result containing the [`CashFlowMatch`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `bonds` or `liabilities` is empty, and an
+/// `IndicatorError::InvalidInput` if a bond has a price or face value that is not positive, a
+/// negative coupon, or a maturity of zero, a liability is negative, `reinvestment_rate` is not
+/// greater than `-1`, or no candidate matures by a liability that is left to fund.
+pub fn match_cash_flows(
+    bonds: &[Bond],
+    liabilities: &[f64],
+    reinvestment_rate: f64,
+) -> Result<CashFlowMatch, IndicatorError> {
+    if bonds.is_empty() || liabilities.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one bond and one liability are required".to_string(),
+        ));
+    }
+    if bonds.iter().any(|b| {
+        b.price.is_nan()
+            || b.price <= 0.0
+            || b.face.is_nan()
+            || b.face <= 0.0
+            || b.coupon.is_nan()
+            || b.coupon < 0.0
+            || b.maturity == 0
+    }) {
+        return Err(IndicatorError::InvalidInput(
+            "Every bond needs a positive price, face value, and maturity and a coupon that is not negative"
+                .to_string(),
+        ));
+    }
+    if liabilities.iter().any(|l| l.is_nan() || *l < 0.0)
+        || reinvestment_rate.is_nan()
+        || reinvestment_rate <= -1.0
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`liabilities` must not be negative and `reinvestment_rate` must be greater than -1"
+                .to_string(),
+        ));
+    }
+
+    let mut result = CashFlowMatch {
+        quantities: vec![0.0; bonds.len()],
+        cost: 0.0,
+        surplus: vec![0.0; liabilities.len()],
+    };
+    // Coupons received in every period from the bonds bought for later liabilities
+    let mut inflows = vec![0.0; liabilities.len()];
+    for period in (1..=liabilities.len()).rev() {
+        let need = liabilities[period - 1] - inflows[period - 1];
+        if need <= 0.0 {
+            result.surplus[period - 1] = -need;
+            continue;
+        }
+
+        // The cash delivered in `period` by one bond of every candidate maturing by then
+        let delivered = |bond: &Bond| {
+            bond.cash_flow(bond.maturity)
+                * (1.0 + reinvestment_rate).powi((period - bond.maturity) as i32)
+        };
+        let Some((index, bond)) = bonds
+            .iter()
+            .enumerate()
+            .filter(|(_, bond)| bond.maturity <= period)
+            .min_by(|a, b| (a.1.price / delivered(a.1)).total_cmp(&(b.1.price / delivered(b.1))))
+        else {
+            return Err(IndicatorError::InvalidInput(format!(
+                "No candidate bond matures by period {}",
+                period
+            )));
+        };
+
+        let quantity = need / delivered(bond);
+        result.quantities[index] += quantity;
+        result.cost += quantity * bond.price;
+        for inflow in &mut inflows[..bond.maturity - 1] {
+            *inflow += quantity * bond.coupon;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> [Bond; 4] {
+        [
+            Bond {
+                price: 97.0,
+                coupon: 0.0,
+                face: 100.0,
+                maturity: 1,
+            },
+            Bond {
+                price: 100.0,
+                coupon: 5.0,
+                face: 100.0,
+                maturity: 2,
+            },
+            Bond {
+                price: 98.0,
+                coupon: 4.0,
+                face: 100.0,
+                maturity: 3,
+            },
+            Bond {
+                price: 88.0,
+                coupon: 0.0,
+                face: 100.0,
+                maturity: 3,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_bond() {
+        let bond = candidates()[2];
+        assert_eq!(bond.cash_flow(0), 0.0);
+        assert_eq!(bond.cash_flow(1), 4.0);
+        assert_eq!(bond.cash_flow(3), 104.0);
+        assert_eq!(bond.cash_flow(4), 0.0);
+        assert!((bond.price_at_yield(0.04) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_match_cash_flows() {
+        // Test case with enough data
+        let bonds = candidates();
+        let result = match_cash_flows(&bonds, &[100.0, 100.0, 1000.0, 55.0], 0.01).unwrap();
+        // The zero coupon bond maturing in period 3 also funds period 4 through reinvestment
+        let expected = [
+            0.952_380_952_380_952_4,
+            0.952_380_952_380_952_3,
+            0.0,
+            10.544_554_455_445_544,
+        ];
+        for (quantity, expected) in result.quantities.iter().zip(expected) {
+            assert!((quantity - expected).abs() < 1e-12);
+        }
+        assert!((result.cost - 1_115.539_839_698_255_5).abs() < 1e-9);
+        assert!(result.surplus.iter().all(|s| s.abs() < 1e-12));
+
+        // Coupons beyond the earlier liabilities are surplus
+        let result = match_cash_flows(&bonds[1..3], &[1.0, 0.0, 104.0], 0.0).unwrap();
+        assert_eq!(result.quantities, vec![0.0, 1.0]);
+        assert_eq!(result.surplus, vec![3.0, 4.0, 0.0]);
+
+        // Test case with not enough data
+        let result = match_cash_flows(&[], &[100.0], 0.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid input
+        let result = match_cash_flows(&bonds[1..], &[100.0, 100.0], 0.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! OHLCV bars and the selection of the price an indicator is computed on.
+
+use crate::IndicatorError;
+
+/// The open, high, low, and close prices and the traded volume of one bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    /// First traded price of the bar
+    pub open: f64,
+    /// Highest traded price of the bar
+    pub high: f64,
+    /// Lowest traded price of the bar
+    pub low: f64,
+    /// Last traded price of the bar
+    pub close: f64,
+    /// Volume traded during the bar
+    pub volume: f64,
+}
+
+impl Candle {
+    /// Creates a new candle.
+    pub fn new(open: f64, high: f64, low: f64, close: f64, volume: f64) -> Self {
+        Candle {
+            open,
+            high,
+            low,
+            close,
+            volume,
+        }
+    }
+}
+
+/// The price of a [`Candle`] an indicator is computed on.
+#[derive(Debug, Clone, Copy)]
+pub enum PriceSource {
+    /// The opening price
+    Open,
+    /// The highest price
+    High,
+    /// The lowest price
+    Low,
+    /// The closing price
+    Close,
+    /// The median price, `(high + low) / 2`
+    Hl2,
+    /// The typical price, `(high + low + close) / 3`
+    Hlc3,
+    /// The average price, `(open + high + low + close) / 4`
+    Ohlc4,
+    /// A price derived from the candle by a user supplied function, e.g. `|c| (2.0 * c.close +
+    /// c.high + c.low) / 4.0`
+    Custom(fn(&Candle) -> f64),
+}
+
+impl PriceSource {
+    /// Returns the selected price of `candle`.
+    pub fn price(&self, candle: &Candle) -> f64 {
+        match self {
+            PriceSource::Open => candle.open,
+            PriceSource::High => candle.high,
+            PriceSource::Low => candle.low,
+            PriceSource::Close => candle.close,
+            PriceSource::Hl2 => (candle.high + candle.low) / 2.0,
+            PriceSource::Hlc3 => (candle.high + candle.low + candle.close) / 3.0,
+            PriceSource::Ohlc4 => (candle.open + candle.high + candle.low + candle.close) / 4.0,
+            PriceSource::Custom(f) => f(candle),
+        }
+    }
+
+    /// Returns the selected price of every candle.
+    pub fn extract(&self, candles: &[Candle]) -> Vec<f64> {
+        candles.iter().map(|candle| self.price(candle)).collect()
+    }
+
+    /// Computes a price based indicator on the selected price of every candle.
+    ///
+    /// # Arguments
+    ///
+    /// * `candles` - A slice of candles.
+    /// * `indicator` - The indicator to compute, mapping a price slice to its output values, e.g.
+    ///   `|prices| calculate_rsi(prices, 14)`.
+    ///
+    /// # Returns
+    ///
+    /// The result of `indicator`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `indicator`.
+    pub fn apply<F, T>(&self, candles: &[Candle], indicator: F) -> Result<T, IndicatorError>
+    where
+        F: FnOnce(&[f64]) -> Result<T, IndicatorError>,
+    {
+        indicator(&self.extract(candles))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_ema, calculate_macd};
+
+    #[test]
+    fn test_price_source() {
+        let candle = Candle::new(10.0, 14.0, 8.0, 12.0, 500.0);
+        assert_eq!(PriceSource::Open.price(&candle), 10.0);
+        assert_eq!(PriceSource::Close.price(&candle), 12.0);
+        assert_eq!(PriceSource::Hl2.price(&candle), 11.0);
+        assert_eq!(PriceSource::Hlc3.price(&candle), 34.0 / 3.0);
+        assert_eq!(PriceSource::Ohlc4.price(&candle), 11.0);
+        let weighted = PriceSource::Custom(|c| (2.0 * c.close + c.high + c.low) / 4.0);
+        assert_eq!(weighted.price(&candle), 11.5);
+    }
+
+    #[test]
+    fn test_apply() {
+        let candles = [
+            Candle::new(1.0, 2.0, 0.0, 1.5, 10.0),
+            Candle::new(1.5, 3.0, 1.0, 2.5, 10.0),
+            Candle::new(2.5, 4.0, 2.0, 3.5, 10.0),
+            Candle::new(3.5, 5.0, 3.0, 4.5, 10.0),
+        ];
+        let result = PriceSource::Hl2
+            .apply(&candles, |p| calculate_ema(p, 2))
+            .unwrap();
+        assert_eq!(result, vec![1.5, 2.5, 3.5]);
+
+        // Errors of the indicator are passed through
+        let result = PriceSource::Close.apply(&candles, |p| calculate_macd(p, 2, 4, 2));
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! Helpers for displaying series in charts: downsampling for rendering many points, and
+//! specifications of candlestick charts with indicator overlays for Vega-Lite and ECharts.
+
+use crate::candle::Candle;
+use crate::json::{escape_json, json_number};
+use crate::series::Series;
+use crate::IndicatorError;
+
+/// Selects the observations that keep the visual shape of a series when it is drawn with fewer
+/// points, using the Largest-Triangle-Three-Buckets (LTTB) algorithm.
+///
+/// The first and last observations are always kept. The observations in between are divided into
+/// `points - 2` buckets of equal size, and every bucket keeps the observation forming the largest
+/// triangle with the observation kept from the previous bucket and the average of the next bucket.
+/// Peaks and troughs are therefore kept, unlike with plain decimation. Series with at most
+/// `points` observations are kept whole.
+///
+/// # Arguments
+///
+/// * `timestamps` - Strictly increasing timestamps of the observations.
+/// * `values` - The observations, aligned with `timestamps`.
+/// * `points` - The number of observations to keep.
+///
+/// # Returns
+///
+/// A Result containing the increasing indices of the kept observations, which can also select
+/// the same bars from other series such as indicators, or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `timestamps` and `values` differ in length,
+/// and an `IndicatorError::InvalidInput` if `points` is less than 3.
+pub fn lttb_indices(
+    timestamps: &[i64],
+    values: &[f64],
+    points: usize,
+) -> Result<Vec<usize>, IndicatorError> {
+    if timestamps.len() != values.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`timestamps` and `values` must have the same length".to_string(),
+        ));
+    }
+    if points < 3 {
+        return Err(IndicatorError::InvalidInput(
+            "At least 3 points must be kept".to_string(),
+        ));
+    }
+    let n = values.len();
+    if n <= points {
+        return Ok((0..n).collect());
+    }
+
+    // Timestamps are taken relative to the first one to keep the areas precise
+    let x = |i: usize| (timestamps[i] - timestamps[0]) as f64;
+    let bucket_start = |bucket: usize| (bucket * (n - 2) / (points - 2) + 1).min(n);
+
+    let mut indices = Vec::with_capacity(points);
+    indices.push(0);
+    let mut previous = 0;
+    for bucket in 0..points - 2 {
+        let next = bucket_start(bucket + 1)..bucket_start(bucket + 2);
+        let count = next.len() as f64;
+        let average_x = next.clone().map(x).sum::<f64>() / count;
+        let average_y = values[next].iter().sum::<f64>() / count;
+
+        let (px, py) = (x(previous), values[previous]);
+        let area =
+            |i: usize| ((px - average_x) * (values[i] - py) - (px - x(i)) * (average_y - py)).abs();
+        let start = bucket_start(bucket);
+        (previous, _) = (start..bucket_start(bucket + 1))
+            .map(|i| (i, area(i)))
+            .fold((start, f64::NEG_INFINITY), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+        indices.push(previous);
+    }
+    indices.push(n - 1);
+
+    Ok(indices)
+}
+
+/// Downsamples a series to `points` observations for display, see [`lttb_indices`].
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `points` is less than 3.
+pub fn downsample_lttb(series: &Series, points: usize) -> Result<Series, IndicatorError> {
+    let indices = lttb_indices(series.timestamps(), series.values(), points)?;
+    Series::new(
+        indices.iter().map(|i| series.timestamps()[*i]).collect(),
+        indices.iter().map(|i| series.values()[*i]).collect(),
+    )
+}
+
+/// An indicator line drawn over the candles of a chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overlay<'a> {
+    /// Name of the line in the legend
+    pub name: &'a str,
+    /// Values of the indicator, aligned with the last candles as the indicator functions return
+    /// them, so that an SMA over 20 bars starts at the 20th candle
+    pub values: &'a [f64],
+}
+
+impl<'a> Overlay<'a> {
+    /// Creates an overlay named `name`.
+    pub fn new(name: &'a str, values: &'a [f64]) -> Self {
+        Overlay { name, values }
+    }
+
+    /// Returns the value of the line at candle `i` of `len` candles, if the line covers it.
+    fn at(&self, i: usize, len: usize) -> Option<f64> {
+        (i + self.values.len())
+            .checked_sub(len)
+            .map(|offset| self.values[offset])
+    }
+}
+
+/// Checks that every candle has a timestamp and that no overlay is longer than the candles.
+fn validate_chart(
+    timestamps: &[i64],
+    candles: &[Candle],
+    overlays: &[Overlay],
+) -> Result<(), IndicatorError> {
+    if timestamps.len() != candles.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`timestamps` and `candles` must have the same length".to_string(),
+        ));
+    }
+    if overlays.iter().any(|o| o.values.len() > candles.len()) {
+        return Err(IndicatorError::MismatchedLength(
+            "Overlays must not have more values than there are candles".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Creates a Vega-Lite specification of a candlestick chart with indicator lines over it.
+///
+/// Every candle becomes a row of the inline data with its `time` in milliseconds, as Vega-Lite
+/// expects, its prices and volume, and one field `overlay_<index>` per overlay that is `null`
+/// where the overlay does not cover the candle. Rising candles are green and falling ones red.
+///
+/// # Arguments
+///
+/// * `timestamps` - Unix timestamps of the candles in seconds.
+/// * `candles` - The candles to draw.
+/// * `overlays` - The indicator lines drawn over the candles.
+///
+/// # Returns
+///
+/// A Result containing the specification as JSON text or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `timestamps` and `candles` differ in length or
+/// an overlay has more values than there are candles.
+pub fn vega_lite_spec(
+    timestamps: &[i64],
+    candles: &[Candle],
+    overlays: &[Overlay],
+) -> Result<String, IndicatorError> {
+    validate_chart(timestamps, candles, overlays)?;
+
+    let rows = timestamps
+        .iter()
+        .zip(candles)
+        .enumerate()
+        .map(|(i, (time, candle))| {
+            let mut row = format!(
+                "{{\"time\":{},\"open\":{},\"high\":{},\"low\":{},\"close\":{},\"volume\":{}",
+                time * 1_000,
+                json_number(candle.open),
+                json_number(candle.high),
+                json_number(candle.low),
+                json_number(candle.close),
+                json_number(candle.volume)
+            );
+            for (j, overlay) in overlays.iter().enumerate() {
+                let value = overlay.at(i, candles.len()).unwrap_or(f64::NAN);
+                row.push_str(&format!(",\"overlay_{}\":{}", j, json_number(value)));
+            }
+            row.push('}');
+            row
+        })
+        .collect::<Vec<String>>();
+
+    let color = "{\"condition\":{\"test\":\"datum.open < datum.close\",\"value\":\"#06982d\"},\
+                 \"value\":\"#ae1325\"}";
+    let mut layers = vec![
+        format!(
+            "{{\"mark\":\"rule\",\"encoding\":{{\"y\":{{\"field\":\"low\",\"type\":\"quantitative\",\
+             \"scale\":{{\"zero\":false}},\"title\":\"Price\"}},\"y2\":{{\"field\":\"high\"}},\
+             \"color\":{}}}}}",
+            color
+        ),
+        format!(
+            "{{\"mark\":\"bar\",\"encoding\":{{\"y\":{{\"field\":\"open\",\"type\":\"quantitative\"}},\
+             \"y2\":{{\"field\":\"close\"}},\"color\":{}}}}}",
+            color
+        ),
+    ];
+    layers.extend(overlays.iter().enumerate().map(|(j, overlay)| {
+        format!(
+            "{{\"mark\":\"line\",\"encoding\":{{\"y\":{{\"field\":\"overlay_{}\",\
+             \"type\":\"quantitative\"}},\"color\":{{\"datum\":\"{}\"}}}}}}",
+            j,
+            escape_json(overlay.name)
+        )
+    }));
+
+    Ok(format!(
+        "{{\"$schema\":\"https://vega.github.io/schema/vega-lite/v5.json\",\
+         \"data\":{{\"values\":[{}]}},\
+         \"encoding\":{{\"x\":{{\"field\":\"time\",\"type\":\"temporal\",\"title\":null}}}},\
+         \"layer\":[{}]}}",
+        rows.join(","),
+        layers.join(",")
+    ))
+}
+
+/// Creates an ECharts option object of a candlestick chart with indicator lines over it.
+///
+/// The candles form a `candlestick` series on a time axis, with every item holding the time in
+/// milliseconds followed by the open, close, low, and high prices as ECharts orders them. Every
+/// overlay forms a `line` series over the candles it covers.
+///
+/// # Arguments
+///
+/// * `timestamps` - Unix timestamps of the candles in seconds.
+/// * `candles` - The candles to draw.
+/// * `overlays` - The indicator lines drawn over the candles.
+///
+/// # Returns
+///
+/// A Result containing the option object as JSON text or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `timestamps` and `candles` differ in length or
+/// an overlay has more values than there are candles.
+pub fn echarts_spec(
+    timestamps: &[i64],
+    candles: &[Candle],
+    overlays: &[Overlay],
+) -> Result<String, IndicatorError> {
+    validate_chart(timestamps, candles, overlays)?;
+
+    let items = timestamps
+        .iter()
+        .zip(candles)
+        .map(|(time, candle)| {
+            format!(
+                "[{},{},{},{},{}]",
+                time * 1_000,
+                json_number(candle.open),
+                json_number(candle.close),
+                json_number(candle.low),
+                json_number(candle.high)
+            )
+        })
+        .collect::<Vec<String>>();
+    let mut series = vec![format!(
+        "{{\"type\":\"candlestick\",\"name\":\"Price\",\"encode\":{{\"x\":0,\"y\":[1,2,3,4]}},\
+         \"data\":[{}]}}",
+        items.join(",")
+    )];
+    series.extend(overlays.iter().map(|overlay| {
+        let start = candles.len() - overlay.values.len();
+        let points = timestamps[start..]
+            .iter()
+            .zip(overlay.values)
+            .map(|(time, value)| format!("[{},{}]", time * 1_000, json_number(*value)))
+            .collect::<Vec<String>>();
+        format!(
+            "{{\"type\":\"line\",\"name\":\"{}\",\"showSymbol\":false,\"data\":[{}]}}",
+            escape_json(overlay.name),
+            points.join(",")
+        )
+    }));
+    let legend = overlays
+        .iter()
+        .map(|overlay| format!("\"{}\"", escape_json(overlay.name)))
+        .collect::<Vec<String>>();
+
+    Ok(format!(
+        "{{\"tooltip\":{{\"trigger\":\"axis\"}},\"legend\":{{\"data\":[\"Price\"{}]}},\
+         \"xAxis\":{{\"type\":\"time\"}},\"yAxis\":{{\"scale\":true}},\"series\":[{}]}}",
+        legend.iter().map(|l| format!(",{}", l)).collect::<String>(),
+        series.join(",")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lttb_indices() {
+        // Test case with enough data
+        let timestamps = (0..10).collect::<Vec<i64>>();
+        let values = [0.0, 1.0, 0.0, 0.0, 9.0, 0.0, 0.0, -7.0, 0.0, 0.0];
+        let result = lttb_indices(&timestamps, &values, 4).unwrap();
+        assert_eq!(result, vec![0, 4, 7, 9]);
+
+        // Short series are kept whole
+        let result = lttb_indices(&timestamps[..3], &values[..3], 5).unwrap();
+        assert_eq!(result, vec![0, 1, 2]);
+
+        // Test case with invalid input
+        let result = lttb_indices(&timestamps, &values, 2);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = lttb_indices(&timestamps[1..], &values, 4);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_downsample_lttb() {
+        let series = Series::new(
+            (0..1_000).map(|i| i * 60).collect(),
+            (0..1_000).map(|i| (i as f64 / 50.0).sin()).collect(),
+        )
+        .unwrap();
+        let result = downsample_lttb(&series, 100).unwrap();
+        assert_eq!(result.len(), 100);
+        assert_eq!(result.timestamps()[0], 0);
+        assert_eq!(result.timestamps()[99], 999 * 60);
+
+        // The extremes of the series survive
+        let max = result.values().iter().copied().fold(f64::MIN, f64::max);
+        assert!(max > 0.999);
+    }
+
+    #[test]
+    fn test_vega_lite_spec() {
+        let candles = [
+            Candle::new(1.0, 2.0, 0.5, 1.5, 10.0),
+            Candle::new(1.5, 2.5, 1.0, 2.0, 12.0),
+        ];
+        let sma = [1.75];
+        let spec =
+            vega_lite_spec(&[60, 120], &candles, &[Overlay::new("SMA \"2\"", &sma)]).unwrap();
+        assert!(
+            spec.starts_with("{\"$schema\":\"https://vega.github.io/schema/vega-lite/v5.json\"")
+        );
+        assert!(spec.contains(
+            "{\"time\":60000,\"open\":1.0,\"high\":2.0,\"low\":0.5,\"close\":1.5,\"volume\":10.0,\"overlay_0\":null}"
+        ));
+        assert!(spec.contains("\"overlay_0\":1.75}"));
+        assert!(spec.contains("\"color\":{\"datum\":\"SMA \\\"2\\\"\"}"));
+
+        // Test case with mismatched lengths
+        let result = vega_lite_spec(&[60], &candles, &[]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_echarts_spec() {
+        let candles = [
+            Candle::new(1.0, 2.0, 0.5, 1.5, 10.0),
+            Candle::new(1.5, 2.5, 1.0, 2.0, 12.0),
+        ];
+        let sma = [1.75];
+        let spec = echarts_spec(&[60, 120], &candles, &[Overlay::new("SMA", &sma)]).unwrap();
+        assert_eq!(
+            spec,
+            "{\"tooltip\":{\"trigger\":\"axis\"},\"legend\":{\"data\":[\"Price\",\"SMA\"]},\
+             \"xAxis\":{\"type\":\"time\"},\"yAxis\":{\"scale\":true},\"series\":[\
+             {\"type\":\"candlestick\",\"name\":\"Price\",\"encode\":{\"x\":0,\"y\":[1,2,3,4]},\
+             \"data\":[[60000,1.0,1.5,0.5,2.0],[120000,1.5,2.0,1.0,2.5]]},\
+             {\"type\":\"line\",\"name\":\"SMA\",\"showSymbol\":false,\"data\":[[120000,1.75]]}]}"
+        );
+
+        // Test case with an overlay longer than the candles
+        let result = echarts_spec(&[60, 120], &candles, &[Overlay::new("SMA", &[1.0; 3])]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+}
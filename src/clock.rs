@@ -0,0 +1,89 @@
+//! Sources of the current time for logic that runs both live and in simulations.
+//!
+//! Code that asks a [`Clock`] for the time instead of reading the system time directly can be
+//! driven by a [`SimulatedClock`] in tests and backtests, which makes its behaviour deterministic,
+//! and by a [`SystemClock`] in live use.
+
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of the current time as a Unix timestamp in seconds.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> i64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> i64 {
+        (**self).now()
+    }
+}
+
+/// The wall clock of the system.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as i64,
+            Err(error) => -(error.duration().as_secs() as i64),
+        }
+    }
+}
+
+/// A clock that only moves when it is told to.
+///
+/// The time is held in a [`Cell`], so a simulation can advance the clock while the components it
+/// drives hold a shared reference to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulatedClock {
+    time: Cell<i64>,
+}
+
+impl SimulatedClock {
+    /// Creates a clock showing `time`.
+    pub fn new(time: i64) -> Self {
+        SimulatedClock {
+            time: Cell::new(time),
+        }
+    }
+
+    /// Sets the time, which may move the clock backwards.
+    pub fn set(&self, time: i64) {
+        self.time.set(time);
+    }
+
+    /// Moves the clock forward by `seconds`.
+    pub fn advance(&self, seconds: i64) {
+        self.time.set(self.time.get() + seconds);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> i64 {
+        self.time.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulated_clock() {
+        let clock = SimulatedClock::new(1_700_000_000);
+        let shared: &dyn Clock = &clock;
+        assert_eq!(shared.now(), 1_700_000_000);
+        clock.advance(60);
+        assert_eq!(shared.now(), 1_700_000_060);
+        clock.set(5);
+        assert_eq!(shared.now(), 5);
+    }
+
+    #[test]
+    fn test_system_clock() {
+        // Any time after this code was written
+        assert!(SystemClock.now() > 1_700_000_000);
+    }
+}
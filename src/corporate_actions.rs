@@ -0,0 +1,339 @@
+//! Corporate actions such as splits, dividends, mergers, and symbol changes, and their application
+//! to price history and holdings.
+
+use crate::series::Series;
+use crate::IndicatorError;
+
+/// What happens to the shares of a [`CorporateAction`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorporateActionKind {
+    /// A stock split, giving `ratio` new shares for every old share, e.g. 2.0 for a 2-for-1 split
+    /// and 0.1 for a 1-for-10 reverse split
+    Split {
+        /// New shares per old share
+        ratio: f64,
+    },
+    /// A cash dividend of `amount` per share, going ex at the time of the action
+    Dividend {
+        /// Cash paid per share
+        amount: f64,
+    },
+    /// An acquisition paying `ratio` shares of `acquirer` and `cash` for every share
+    Merger {
+        /// Symbol of the acquiring company
+        acquirer: String,
+        /// Acquirer shares per share
+        ratio: f64,
+        /// Cash paid per share
+        cash: f64,
+    },
+    /// A change of the symbol the shares trade under
+    SymbolChange {
+        /// Symbol the shares trade under from the time of the action
+        new_symbol: String,
+    },
+}
+
+/// A corporate action of one company, effective from its timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorporateAction {
+    /// Unix timestamp in seconds from which the action is effective, e.g. the ex-date of a
+    /// dividend
+    pub time: i64,
+    /// Symbol of the company before the action
+    pub symbol: String,
+    /// What happens to the shares
+    pub kind: CorporateActionKind,
+}
+
+impl CorporateAction {
+    /// Creates a new corporate action.
+    pub fn new(time: i64, symbol: &str, kind: CorporateActionKind) -> Self {
+        CorporateAction {
+            time,
+            symbol: symbol.to_string(),
+            kind,
+        }
+    }
+
+    /// Checks that the ratios and amounts of the action are usable.
+    fn validate(&self) -> Result<(), IndicatorError> {
+        let valid = match &self.kind {
+            CorporateActionKind::Split { ratio } => ratio.is_finite() && *ratio > 0.0,
+            CorporateActionKind::Dividend { amount } => amount.is_finite() && *amount >= 0.0,
+            CorporateActionKind::Merger { ratio, cash, .. } => {
+                ratio.is_finite() && *ratio >= 0.0 && cash.is_finite() && *cash >= 0.0
+            }
+            CorporateActionKind::SymbolChange { .. } => true,
+        };
+        if !valid {
+            return Err(IndicatorError::InvalidInput(format!(
+                "Corporate action of `{}` at {} has an invalid ratio or amount",
+                self.symbol, self.time
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that corporate actions are valid and sorted by time.
+fn validate_actions(actions: &[CorporateAction]) -> Result<(), IndicatorError> {
+    if actions.windows(2).any(|w| w[0].time > w[1].time) {
+        return Err(IndicatorError::InvalidInput(
+            "Corporate actions must be sorted by time".to_string(),
+        ));
+    }
+    actions.iter().try_for_each(CorporateAction::validate)
+}
+
+/// Back-adjusts a price history for splits and dividends, so that returns across the actions
+/// reflect what a holder earned.
+///
+/// Prices before a split are divided by its ratio, and prices before a dividend are multiplied by
+/// `1 - amount / close`, where `close` is the last price before the ex-date. Symbol changes are
+/// followed, so actions under the new symbol adjust the history as well. Actions of other symbols
+/// and mergers leave the history unchanged.
+///
+/// # Arguments
+///
+/// * `series` - The price history.
+/// * `symbol` - The symbol of the history at its first observation.
+/// * `actions` - Corporate actions sorted by time.
+///
+/// # Returns
+///
+/// A Result containing the adjusted history or an `IndicatorError`. The latest prices are left
+/// unchanged.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `actions` are not sorted by time, a split ratio is
+/// not positive, an amount is negative, or a dividend is not less than the price before it.
+pub fn adjust_history(
+    series: &Series,
+    symbol: &str,
+    actions: &[CorporateAction],
+) -> Result<Series, IndicatorError> {
+    validate_actions(actions)?;
+
+    let timestamps = series.timestamps();
+    let mut values = series.values().to_vec();
+    let mut symbol = symbol.to_string();
+    for action in actions {
+        if action.symbol != symbol {
+            continue;
+        }
+        let bar = timestamps.partition_point(|t| *t < action.time);
+        let factor = match &action.kind {
+            CorporateActionKind::Split { ratio } => 1.0 / ratio,
+            CorporateActionKind::Dividend { amount } if bar > 0 => {
+                // The factor uses the unadjusted close, as later splits scale it like the
+                // dividend amount
+                let close = series.values()[bar - 1];
+                if *amount >= close {
+                    return Err(IndicatorError::InvalidInput(format!(
+                        "Dividend of `{}` at {} is not less than the price before it",
+                        action.symbol, action.time
+                    )));
+                }
+                1.0 - amount / close
+            }
+            CorporateActionKind::SymbolChange { new_symbol } => {
+                symbol = new_symbol.clone();
+                continue;
+            }
+            CorporateActionKind::Dividend { .. } | CorporateActionKind::Merger { .. } => continue,
+        };
+        values[..bar].iter_mut().for_each(|value| *value *= factor);
+    }
+
+    Series::new(timestamps.to_vec(), values)
+}
+
+/// A position in the shares of one company.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holding {
+    /// Symbol the shares trade under
+    pub symbol: String,
+    /// Number of shares held, negative for short positions
+    pub quantity: f64,
+    /// Total cost of the position
+    pub cost_basis: f64,
+}
+
+impl Holding {
+    /// Creates a new holding.
+    pub fn new(symbol: &str, quantity: f64, cost_basis: f64) -> Self {
+        Holding {
+            symbol: symbol.to_string(),
+            quantity,
+            cost_basis,
+        }
+    }
+
+    /// Applies a corporate action to the holding and returns the cash it pays, which is negative
+    /// for short positions.
+    ///
+    /// Splits scale the quantity, dividends pay cash, mergers convert the shares into shares of
+    /// the acquirer and pay the cash portion, and symbol changes rename the holding. The total
+    /// cost basis carries over unchanged. Actions of other symbols are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if the split ratio is not positive or an amount
+    /// is negative.
+    pub fn apply(&mut self, action: &CorporateAction) -> Result<f64, IndicatorError> {
+        action.validate()?;
+        if action.symbol != self.symbol {
+            return Ok(0.0);
+        }
+
+        match &action.kind {
+            CorporateActionKind::Split { ratio } => {
+                self.quantity *= ratio;
+                Ok(0.0)
+            }
+            CorporateActionKind::Dividend { amount } => Ok(self.quantity * amount),
+            CorporateActionKind::Merger {
+                acquirer,
+                ratio,
+                cash,
+            } => {
+                let paid = self.quantity * cash;
+                self.symbol = acquirer.clone();
+                self.quantity *= ratio;
+                Ok(paid)
+            }
+            CorporateActionKind::SymbolChange { new_symbol } => {
+                self.symbol = new_symbol.clone();
+                Ok(0.0)
+            }
+        }
+    }
+}
+
+/// Applies corporate actions in order to a set of holdings.
+///
+/// Holdings of the same symbol after a merger are kept separate, so that their cost bases remain
+/// traceable.
+///
+/// # Arguments
+///
+/// * `holdings` - The holdings to update in place.
+/// * `actions` - Corporate actions sorted by time.
+///
+/// # Returns
+///
+/// A Result containing the total cash paid to the holdings or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `actions` are not sorted by time, a split ratio is
+/// not positive, or an amount is negative.
+pub fn apply_to_holdings(
+    holdings: &mut [Holding],
+    actions: &[CorporateAction],
+) -> Result<f64, IndicatorError> {
+    validate_actions(actions)?;
+
+    let mut cash = 0.0;
+    for action in actions {
+        for holding in holdings.iter_mut() {
+            cash += holding.apply(action)?;
+        }
+    }
+    Ok(cash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_history() {
+        let series =
+            Series::new(vec![1, 2, 3, 4, 5], vec![100.0, 102.0, 51.0, 50.0, 52.0]).unwrap();
+        let actions = [
+            CorporateAction::new(3, "OLD", CorporateActionKind::Split { ratio: 2.0 }),
+            CorporateAction::new(
+                4,
+                "OLD",
+                CorporateActionKind::SymbolChange {
+                    new_symbol: "NEW".to_string(),
+                },
+            ),
+            CorporateAction::new(5, "NEW", CorporateActionKind::Dividend { amount: 1.0 }),
+            CorporateAction::new(5, "OTHER", CorporateActionKind::Split { ratio: 3.0 }),
+        ];
+        let result = adjust_history(&series, "OLD", &actions).unwrap();
+        let expected = [49.0, 49.98, 49.98, 49.0, 52.0];
+        assert_eq!(result.timestamps(), series.timestamps());
+        for (value, expected) in result.values().iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with invalid input
+        let unsorted = [actions[2].clone(), actions[0].clone()];
+        let split = [CorporateAction::new(
+            3,
+            "OLD",
+            CorporateActionKind::Split { ratio: 0.0 },
+        )];
+        let dividend = [CorporateAction::new(
+            2,
+            "OLD",
+            CorporateActionKind::Dividend { amount: 100.0 },
+        )];
+        for actions in [&unsorted[..], &split, &dividend] {
+            match adjust_history(&series, "OLD", actions) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_to_holdings() {
+        let mut holdings = [
+            Holding::new("TGT", 100.0, 5_000.0),
+            Holding::new("ACQ", -10.0, -800.0),
+        ];
+        let actions = [
+            CorporateAction::new(1, "TGT", CorporateActionKind::Split { ratio: 2.0 }),
+            CorporateAction::new(2, "TGT", CorporateActionKind::Dividend { amount: 0.5 }),
+            CorporateAction::new(
+                3,
+                "TGT",
+                CorporateActionKind::Merger {
+                    acquirer: "ACQ".to_string(),
+                    ratio: 0.25,
+                    cash: 3.0,
+                },
+            ),
+            CorporateAction::new(4, "ACQ", CorporateActionKind::Dividend { amount: 1.0 }),
+        ];
+        let cash = apply_to_holdings(&mut holdings, &actions).unwrap();
+        assert!((cash - (100.0 + 600.0 + 40.0)).abs() < 1e-9);
+        assert_eq!(holdings[0], Holding::new("ACQ", 50.0, 5_000.0));
+        assert_eq!(holdings[1], Holding::new("ACQ", -10.0, -800.0));
+
+        // Test case with invalid input
+        let merger = [CorporateAction::new(
+            1,
+            "ACQ",
+            CorporateActionKind::Merger {
+                acquirer: "X".to_string(),
+                ratio: 1.0,
+                cash: -1.0,
+            },
+        )];
+        match apply_to_holdings(&mut holdings, &merger) {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
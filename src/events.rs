@@ -0,0 +1,332 @@
+//! Dated events such as earnings releases and economic announcements, and the study of returns
+//! around them.
+
+use crate::regression::fit_ols;
+use crate::IndicatorError;
+
+/// Category of an [`Event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// A company earnings release
+    Earnings,
+    /// A dividend ex-date
+    Dividend,
+    /// A scheduled economic announcement, named for example `"FOMC"` or `"CPI"`
+    Economic(String),
+    /// Any other event, described by its name
+    Other(String),
+}
+
+/// A dated event, either concerning a single symbol or the whole market.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// Unix timestamp of the event in seconds
+    pub time: i64,
+    /// Category of the event
+    pub kind: EventKind,
+    /// Symbol concerned by the event, or `None` for market wide events such as FOMC meetings
+    pub symbol: Option<String>,
+}
+
+impl Event {
+    /// Creates a new event.
+    pub fn new(time: i64, kind: EventKind, symbol: Option<&str>) -> Self {
+        Event {
+            time,
+            kind,
+            symbol: symbol.map(str::to_string),
+        }
+    }
+
+    /// Returns `true` if the event concerns `symbol`, which market wide events always do.
+    pub fn applies_to(&self, symbol: &str) -> bool {
+        self.symbol.as_deref().is_none_or(|s| s == symbol)
+    }
+}
+
+/// Flags the bars that lie within a number of bars of an event.
+///
+/// The bar of an event is the first bar at or after its timestamp, and the `before` bars preceding
+/// it and the `after` bars following it are flagged along with it. An event after the last bar
+/// belongs to the bar that would follow the series, so it still flags the last `before` bars,
+/// e.g. to suppress entries ahead of an upcoming announcement. Combine the flags with a signal to
+/// suppress trading around events, for example only keeping entries where the flag is `false`.
+/// Filter `events` beforehand to select the relevant kinds and symbols, e.g. with
+/// [`Event::applies_to`].
+///
+/// # Arguments
+///
+/// * `timestamps` - Strictly increasing Unix timestamps of the bars in seconds.
+/// * `events` - The events to flag the bars around.
+/// * `before` - The number of bars before each event to flag.
+/// * `after` - The number of bars after each event to flag.
+///
+/// # Returns
+///
+/// A Result containing one flag per bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `timestamps` are not strictly increasing.
+pub fn flag_event_proximity(
+    timestamps: &[i64],
+    events: &[Event],
+    before: usize,
+    after: usize,
+) -> Result<Vec<bool>, IndicatorError> {
+    if timestamps.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(IndicatorError::InvalidInput(
+            "`timestamps` must be strictly increasing".to_string(),
+        ));
+    }
+
+    let mut flags = vec![false; timestamps.len()];
+    for event in events {
+        let bar = timestamps.partition_point(|t| *t < event.time);
+        let start = bar.saturating_sub(before);
+        let end = (bar + after + 1).min(timestamps.len());
+        if start < end {
+            flags[start..end].iter_mut().for_each(|flag| *flag = true);
+        }
+    }
+
+    Ok(flags)
+}
+
+/// Periods around each event used by [`calculate_event_study`], counted in bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventWindow {
+    /// Number of bars used to estimate the market model, ending just before the event window
+    pub estimation: usize,
+    /// Number of bars before the event bar included in the event window
+    pub before: usize,
+    /// Number of bars after the event bar included in the event window
+    pub after: usize,
+}
+
+/// Abnormal returns around a set of events, produced by [`calculate_event_study`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventStudy {
+    /// Offset of every bar of the event window from the event bar, from `-before` to `after`
+    pub offsets: Vec<i64>,
+    /// Indices of the events that could be studied. Events too close to the start or end of the
+    /// data, or whose market model could not be estimated, are left out.
+    pub included: Vec<usize>,
+    /// Abnormal returns of every included event at every offset
+    pub abnormal_returns: Vec<Vec<f64>>,
+    /// Cumulative abnormal return of every included event over the event window
+    pub cumulative_abnormal_returns: Vec<f64>,
+    /// Average abnormal return across the included events at every offset
+    pub average_abnormal_returns: Vec<f64>,
+    /// Cumulative average abnormal return from the start of the event window to every offset
+    pub cumulative_average_abnormal_returns: Vec<f64>,
+}
+
+/// Runs an event study with a market model adjustment.
+///
+/// For every event, the bar of the event is the first bar at or after its timestamp. The market
+/// model `r = alpha + beta * r_market` is fitted on the `estimation` bars preceding the event
+/// window, and the abnormal return of every bar of the event window is its return minus the
+/// return predicted by the model.
+///
+/// # Arguments
+///
+/// * `timestamps` - Strictly increasing Unix timestamps in seconds shared by all return series.
+/// * `returns` - The periodic returns of every symbol, aligned with `timestamps`.
+/// * `market` - The periodic returns of the market, aligned with `timestamps`.
+/// * `events` - The index in `returns` of the symbol concerned by every event and the timestamp of
+///   the event.
+/// * `window` - The estimation and event windows.
+///
+/// # Returns
+///
+/// A Result containing the [`EventStudy`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if a return series is not aligned with
+/// `timestamps`, an `IndicatorError::InvalidInput` if `timestamps` are not strictly increasing,
+/// the estimation window has fewer than 3 bars, or an event refers to a missing symbol, and an
+/// `IndicatorError::NotEnoughData` if no event could be studied.
+pub fn calculate_event_study(
+    timestamps: &[i64],
+    returns: &[&[f64]],
+    market: &[f64],
+    events: &[(usize, i64)],
+    window: EventWindow,
+) -> Result<EventStudy, IndicatorError> {
+    if market.len() != timestamps.len() || returns.iter().any(|r| r.len() != timestamps.len()) {
+        return Err(IndicatorError::MismatchedLength(
+            "`returns` and `market` must be aligned with `timestamps`".to_string(),
+        ));
+    }
+    if timestamps.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(IndicatorError::InvalidInput(
+            "`timestamps` must be strictly increasing".to_string(),
+        ));
+    }
+    if window.estimation < 3 {
+        return Err(IndicatorError::InvalidInput(
+            "The estimation window must have at least 3 bars".to_string(),
+        ));
+    }
+    if events.iter().any(|(symbol, _)| *symbol >= returns.len()) {
+        return Err(IndicatorError::InvalidInput(
+            "Events must refer to a symbol in `returns`".to_string(),
+        ));
+    }
+
+    let mut included = Vec::new();
+    let mut abnormal_returns = Vec::new();
+    for (i, (symbol, time)) in events.iter().enumerate() {
+        let bar = timestamps.partition_point(|t| t < time);
+        let Some(start) = bar.checked_sub(window.before + window.estimation) else {
+            continue;
+        };
+        let end = bar + window.after + 1;
+        if end > timestamps.len() {
+            continue;
+        }
+
+        let estimation = start..start + window.estimation;
+        let Ok(fit) = fit_ols(
+            &returns[*symbol][estimation.clone()],
+            &[&market[estimation.clone()]],
+        ) else {
+            continue;
+        };
+        included.push(i);
+        abnormal_returns.push(
+            (estimation.end..end)
+                .map(|t| returns[*symbol][t] - fit.alpha - fit.betas[0] * market[t])
+                .collect::<Vec<f64>>(),
+        );
+    }
+    if included.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "No event has enough data around it".to_string(),
+        ));
+    }
+
+    let count = included.len() as f64;
+    let average_abnormal_returns = (0..window.before + window.after + 1)
+        .map(|k| abnormal_returns.iter().map(|ar| ar[k]).sum::<f64>() / count)
+        .collect::<Vec<f64>>();
+    let mut cumulative = 0.0;
+    Ok(EventStudy {
+        offsets: (-(window.before as i64)..=window.after as i64).collect(),
+        included,
+        cumulative_abnormal_returns: abnormal_returns.iter().map(|ar| ar.iter().sum()).collect(),
+        abnormal_returns,
+        cumulative_average_abnormal_returns: average_abnormal_returns
+            .iter()
+            .map(|ar| {
+                cumulative += ar;
+                cumulative
+            })
+            .collect(),
+        average_abnormal_returns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_event_proximity() {
+        let timestamps = (0..10).map(|t| t * 100).collect::<Vec<i64>>();
+        let events = [
+            Event::new(250, EventKind::Earnings, Some("AAPL")),
+            Event::new(900, EventKind::Economic("FOMC".to_string()), None),
+            Event::new(700, EventKind::Earnings, Some("MSFT")),
+        ];
+
+        // Test case with events concerning a single symbol
+        let relevant = events
+            .iter()
+            .filter(|e| e.applies_to("AAPL"))
+            .cloned()
+            .collect::<Vec<Event>>();
+        assert_eq!(relevant.len(), 2);
+        let flags = flag_event_proximity(&timestamps, &relevant, 1, 1).unwrap();
+        let expected = [
+            false, false, true, true, true, false, false, false, true, true,
+        ];
+        assert_eq!(flags, expected);
+
+        // Events after the last bar still flag the bars before them
+        let late = [Event::new(1_050, EventKind::Dividend, None)];
+        let flags = flag_event_proximity(&timestamps, &late, 2, 0).unwrap();
+        assert_eq!(flags.iter().filter(|f| **f).count(), 2);
+        assert!(flags[8] && flags[9]);
+
+        // Test case with unsorted timestamps
+        let result = flag_event_proximity(&[100, 50], &events, 1, 1);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_event_study() {
+        let timestamps = (0..12).map(|t| t * 100).collect::<Vec<i64>>();
+        let market = [
+            0.01, -0.02, 0.015, 0.0, -0.01, 0.02, 0.005, -0.005, 0.01, -0.015, 0.0, 0.01,
+        ];
+        // Both stocks follow a market model exactly, apart from jumps on their event bars
+        let mut a = market.iter().map(|m| 0.001 + 1.5 * m).collect::<Vec<f64>>();
+        let mut b = market
+            .iter()
+            .map(|m| -0.002 + 0.5 * m)
+            .collect::<Vec<f64>>();
+        a[7] += 0.04;
+        b[8] += 0.02;
+        let window = EventWindow {
+            estimation: 5,
+            before: 1,
+            after: 2,
+        };
+        let events = [(0, 650), (1, 800), (0, 50), (1, 1_100)];
+
+        // Test case with enough data
+        let result = calculate_event_study(&timestamps, &[&a, &b], &market, &events, window);
+        let result = result.unwrap();
+        assert_eq!(result.offsets, vec![-1, 0, 1, 2]);
+        assert_eq!(result.included, vec![0, 1]);
+        let expected = [0.0, 0.03, 0.0, 0.0];
+        for (actual, expected) in result.average_abnormal_returns.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+        let expected = [0.0, 0.03, 0.03, 0.03];
+        for (actual, expected) in result
+            .cumulative_average_abnormal_returns
+            .iter()
+            .zip(expected)
+        {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+        assert!((result.cumulative_abnormal_returns[0] - 0.04).abs() < 1e-9);
+
+        // Test case with not enough data
+        let result = calculate_event_study(&timestamps, &[&a, &b], &market, &events[2..], window);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_event_study(&timestamps, &[&a[1..]], &market, &events, window);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Financial functions with the semantics of their Excel and LibreOffice Calc namesakes.
+//!
+//! Arguments follow the spreadsheet order and sign convention: money paid out is negative and
+//! money received is positive, and `payment_type` is 0 for payments at the end of each period and
+//! 1 for payments at the beginning. Optional spreadsheet arguments are required here, with the
+//! spreadsheet defaults being 0 for `fv` and `payment_type` and 0.1 for `guess`. Where a
+//! spreadsheet returns `#NUM!` or `#DIV/0!` these functions return an
+//! `IndicatorError::InvalidInput`.
+
+use crate::calendar::days_from_civil;
+use crate::IndicatorError;
+
+/// Maximum number of iterations of [`rate`], as in Excel.
+const RATE_ITERATIONS: usize = 20;
+
+/// Change of the rate below which [`rate`] has converged, as in Excel.
+const RATE_TOLERANCE: f64 = 1e-7;
+
+/// Maximum number of iterations of [`xirr`], as in Excel.
+const XIRR_ITERATIONS: usize = 100;
+
+/// Change of the rate below which [`xirr`] has converged, as in Excel.
+const XIRR_TOLERANCE: f64 = 1e-6;
+
+/// Number of days between the Excel epoch, 1899-12-30, and the Unix epoch.
+const SERIAL_OF_UNIX_EPOCH: i64 = 25_569;
+
+/// Returns the spreadsheet date serial number of a civil date, for use with [`xirr`].
+///
+/// Serial numbers count days from 1899-12-30, which Excel numbers 0, so they match Excel for every
+/// date from 1900-03-01 on.
+pub fn date_serial(year: i64, month: i64, day: i64) -> i64 {
+    days_from_civil(year, month, day) + SERIAL_OF_UNIX_EPOCH
+}
+
+/// Checks that `payment_type` is 0 or 1.
+fn require_payment_type(payment_type: u8) -> Result<(), IndicatorError> {
+    if payment_type > 1 {
+        return Err(IndicatorError::InvalidInput(
+            "`payment_type` must be 0 or 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the result of a function is finite, where a spreadsheet would return `#NUM!` or
+/// `#DIV/0!`.
+fn require_finite(value: f64, function: &str) -> Result<f64, IndicatorError> {
+    if !value.is_finite() {
+        return Err(IndicatorError::InvalidInput(format!(
+            "The arguments of `{}` have no finite result",
+            function
+        )));
+    }
+    Ok(value)
+}
+
+/// Calculates the payment per period of an annuity, like `PMT`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `payment_type` is not 0 or 1, `nper` is zero, or
+/// the payment is not finite.
+pub fn pmt(
+    rate: f64,
+    nper: f64,
+    pv: f64,
+    fv: f64,
+    payment_type: u8,
+) -> Result<f64, IndicatorError> {
+    require_payment_type(payment_type)?;
+    if nper == 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`nper` must not be zero".to_string(),
+        ));
+    }
+    if rate == 0.0 {
+        return require_finite(-(pv + fv) / nper, "pmt");
+    }
+    let growth = (1.0 + rate).powf(nper);
+    let due = 1.0 + rate * f64::from(payment_type);
+    require_finite(-rate * (pv * growth + fv) / (due * (growth - 1.0)), "pmt")
+}
+
+/// Calculates the future value of an annuity, like `FV`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `payment_type` is not 0 or 1, or the future value
+/// is not finite.
+pub fn fv(
+    rate: f64,
+    nper: f64,
+    pmt: f64,
+    pv: f64,
+    payment_type: u8,
+) -> Result<f64, IndicatorError> {
+    require_payment_type(payment_type)?;
+    if rate == 0.0 {
+        return require_finite(-(pv + pmt * nper), "fv");
+    }
+    let growth = (1.0 + rate).powf(nper);
+    let due = 1.0 + rate * f64::from(payment_type);
+    require_finite(-(pv * growth + pmt * due * (growth - 1.0) / rate), "fv")
+}
+
+/// Calculates the number of periods of an annuity, like `NPER`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `payment_type` is not 0 or 1, or if no number of
+/// periods reaches `fv`.
+pub fn nper(
+    rate: f64,
+    pmt: f64,
+    pv: f64,
+    fv: f64,
+    payment_type: u8,
+) -> Result<f64, IndicatorError> {
+    require_payment_type(payment_type)?;
+    let periods = if rate == 0.0 {
+        -(pv + fv) / pmt
+    } else {
+        let due = pmt * (1.0 + rate * f64::from(payment_type));
+        ((due - fv * rate) / (due + pv * rate)).ln() / (1.0 + rate).ln()
+    };
+    if !periods.is_finite() {
+        return Err(IndicatorError::InvalidInput(
+            "No number of periods reaches `fv`".to_string(),
+        ));
+    }
+    Ok(periods)
+}
+
+/// Calculates the interest part of the payment of period `per`, like `IPMT`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `payment_type` is not 0 or 1, or `per` is not
+/// between 1 and `nper`.
+pub fn ipmt(
+    rate: f64,
+    per: f64,
+    nper: f64,
+    pv: f64,
+    fv: f64,
+    payment_type: u8,
+) -> Result<f64, IndicatorError> {
+    require_payment_type(payment_type)?;
+    if per < 1.0 || per > nper {
+        return Err(IndicatorError::InvalidInput(
+            "`per` must be between 1 and `nper`".to_string(),
+        ));
+    }
+    if payment_type == 1 && per == 1.0 {
+        return Ok(0.0);
+    }
+    let payment = pmt(rate, nper, pv, fv, payment_type)?;
+    let interest = self::fv(rate, per - 1.0, payment, pv, payment_type)? * rate;
+    Ok(if payment_type == 1 {
+        interest / (1.0 + rate)
+    } else {
+        interest
+    })
+}
+
+/// Calculates the principal part of the payment of period `per`, like `PPMT`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `payment_type` is not 0 or 1, or `per` is not
+/// between 1 and `nper`.
+pub fn ppmt(
+    rate: f64,
+    per: f64,
+    nper: f64,
+    pv: f64,
+    fv: f64,
+    payment_type: u8,
+) -> Result<f64, IndicatorError> {
+    Ok(pmt(rate, nper, pv, fv, payment_type)? - ipmt(rate, per, nper, pv, fv, payment_type)?)
+}
+
+/// Calculates the interest paid from period `start` to period `end`, both included, like
+/// `CUMIPMT`.
+///
+/// As in Excel, the periods are truncated to whole numbers and the loan has no balloon payment.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `rate`, `nper`, or `pv` is not positive,
+/// `payment_type` is not 0 or 1, or `start` and `end` are not periods with `start <= end`.
+pub fn cumipmt(
+    rate: f64,
+    nper: f64,
+    pv: f64,
+    start: f64,
+    end: f64,
+    payment_type: u8,
+) -> Result<f64, IndicatorError> {
+    let (start, end) = (start.trunc(), end.trunc());
+    if rate.is_nan() || rate <= 0.0 || nper.is_nan() || nper <= 0.0 || pv.is_nan() || pv <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`rate`, `nper`, and `pv` must be positive".to_string(),
+        ));
+    }
+    if start < 1.0 || end < start || end > nper {
+        return Err(IndicatorError::InvalidInput(
+            "`start` and `end` must be periods with `start <= end`".to_string(),
+        ));
+    }
+    let mut total = 0.0;
+    let mut per = start;
+    while per <= end {
+        total += ipmt(rate, per, nper, pv, 0.0, payment_type)?;
+        per += 1.0;
+    }
+    Ok(total)
+}
+
+/// Calculates the interest rate per period of an annuity, like `RATE`.
+///
+/// The rate is found with Newton's method started at `guess`, stopping after 20 iterations as
+/// Excel does.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `payment_type` is not 0 or 1, or if the iteration
+/// does not converge.
+pub fn rate(
+    nper: f64,
+    pmt: f64,
+    pv: f64,
+    fv: f64,
+    payment_type: u8,
+    guess: f64,
+) -> Result<f64, IndicatorError> {
+    require_payment_type(payment_type)?;
+    let due = f64::from(payment_type);
+    // The future value of all cash flows, which is zero at the rate
+    let balance = |r: f64| {
+        if r.abs() < 1e-12 {
+            pv + pmt * nper + fv
+        } else {
+            let growth = (1.0 + r).powf(nper);
+            pv * growth + pmt * (1.0 + r * due) * (growth - 1.0) / r + fv
+        }
+    };
+
+    let mut r = guess;
+    for _ in 0..RATE_ITERATIONS {
+        let step = 1e-7 * r.abs().max(1e-7);
+        let slope = (balance(r + step) - balance(r - step)) / (2.0 * step);
+        let next = r - balance(r) / slope;
+        if !next.is_finite() {
+            break;
+        }
+        if (next - r).abs() < RATE_TOLERANCE {
+            return Ok(next);
+        }
+        r = next;
+    }
+    Err(IndicatorError::InvalidInput(
+        "The rate did not converge".to_string(),
+    ))
+}
+
+/// Calculates the annual internal rate of return of cash flows at irregular dates, like `XIRR`.
+///
+/// Dates are spreadsheet serial numbers, see [`date_serial`], truncated to whole days, and the
+/// cash flows are discounted over the days since the first date divided by 365, as in Excel.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `values` and `dates` differ in length, and an
+/// `IndicatorError::InvalidInput` if the cash flows do not contain both a positive and a negative
+/// value, a date precedes the first date, or the iteration does not converge.
+pub fn xirr(values: &[f64], dates: &[f64], guess: f64) -> Result<f64, IndicatorError> {
+    if values.len() != dates.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`values` and `dates` must have the same length".to_string(),
+        ));
+    }
+    if !values.iter().any(|v| *v > 0.0) || !values.iter().any(|v| *v < 0.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`values` must contain a positive and a negative cash flow".to_string(),
+        ));
+    }
+    let first = dates[0].trunc();
+    if dates.iter().any(|d| d.trunc() < first) {
+        return Err(IndicatorError::InvalidInput(
+            "No date may precede the first date".to_string(),
+        ));
+    }
+
+    let years = dates
+        .iter()
+        .map(|d| (d.trunc() - first) / 365.0)
+        .collect::<Vec<f64>>();
+    let mut r = guess;
+    for _ in 0..XIRR_ITERATIONS {
+        let (mut npv, mut slope) = (0.0, 0.0);
+        for (value, t) in values.iter().zip(&years) {
+            let discount = (1.0 + r).powf(*t);
+            npv += value / discount;
+            slope -= t * value / (discount * (1.0 + r));
+        }
+        let next = r - npv / slope;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+        if (next - r).abs() < XIRR_TOLERANCE {
+            return Ok(next);
+        }
+        r = next;
+    }
+    Err(IndicatorError::InvalidInput(
+        "The rate did not converge".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected values are the results Excel documents for these arguments, to the digits it shows
+
+    #[test]
+    fn test_pmt() {
+        assert!((pmt(0.08 / 12.0, 10.0, 10_000.0, 0.0, 0).unwrap() + 1_037.03).abs() < 5e-3);
+        assert!((pmt(0.08 / 12.0, 10.0, 10_000.0, 0.0, 1).unwrap() + 1_030.16).abs() < 5e-3);
+        assert_eq!(pmt(0.0, 10.0, 1_000.0, 0.0, 0).unwrap(), -100.0);
+
+        // Test case with invalid input
+        let result = pmt(0.01, 10.0, 1_000.0, 0.0, 2);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+        for (rate, nper) in [(0.01, 0.0), (0.0, 0.0), (0.01, f64::INFINITY)] {
+            let result = pmt(rate, nper, 1_000.0, 0.0, 0);
+            match result {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_fv() {
+        assert!((fv(0.06 / 12.0, 10.0, -200.0, -500.0, 1).unwrap() - 2_581.40).abs() < 5e-3);
+        assert!((fv(0.11 / 12.0, 35.0, -2_000.0, 0.0, 1).unwrap() - 82_846.25).abs() < 5e-3);
+        assert_eq!(fv(0.01, 0.0, -100.0, -500.0, 0).unwrap(), 500.0);
+
+        // Test case with invalid input
+        let result = fv(-1.0, -2.0, -100.0, -500.0, 0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_nper() {
+        let rate = 0.12 / 12.0;
+        assert!((nper(rate, -100.0, -1_000.0, 10_000.0, 1).unwrap() - 59.673_865_7).abs() < 5e-8);
+        assert!((nper(rate, -100.0, -1_000.0, 10_000.0, 0).unwrap() - 60.082_122_9).abs() < 5e-8);
+        assert!((nper(rate, -100.0, -1_000.0, 0.0, 0).unwrap() + 9.578_594_04).abs() < 5e-8);
+
+        // Test case with a payment that never repays the loan
+        let result = nper(rate, -5.0, 1_000.0, 0.0, 0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_ipmt() {
+        assert!((ipmt(0.1 / 12.0, 1.0, 36.0, 8_000.0, 0.0, 0).unwrap() + 66.67).abs() < 5e-3);
+        assert!((ipmt(0.1, 3.0, 3.0, 8_000.0, 0.0, 0).unwrap() + 292.45).abs() < 5e-3);
+        assert_eq!(ipmt(0.1, 1.0, 3.0, 8_000.0, 0.0, 1).unwrap(), 0.0);
+
+        // Test case with a period outside the annuity
+        let result = ipmt(0.1, 4.0, 3.0, 8_000.0, 0.0, 0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_ppmt() {
+        assert!((ppmt(0.1 / 12.0, 1.0, 24.0, 2_000.0, 0.0, 0).unwrap() + 75.62).abs() < 5e-3);
+        assert!((ppmt(0.08, 10.0, 10.0, 200_000.0, 0.0, 0).unwrap() + 27_598.05).abs() < 5e-3);
+    }
+
+    #[test]
+    fn test_cumipmt() {
+        let rate = 0.09 / 12.0;
+        assert!((cumipmt(rate, 360.0, 125_000.0, 13.0, 24.0, 0).unwrap() + 11_135.23).abs() < 5e-3);
+        assert!((cumipmt(rate, 360.0, 125_000.0, 1.0, 1.0, 0).unwrap() + 937.50).abs() < 5e-3);
+
+        // Test case with invalid input
+        let result = cumipmt(rate, 360.0, -125_000.0, 1.0, 1.0, 0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate() {
+        let result = rate(48.0, -200.0, 8_000.0, 0.0, 0, 0.1).unwrap();
+        assert!((result - 0.007_701_472_5).abs() < 5e-11);
+        assert!((result * 12.0 - 0.0924).abs() < 5e-5);
+
+        // Test case without a solution
+        let result = rate(10.0, 100.0, 1_000.0, 0.0, 0, 0.1);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_xirr() {
+        let values = [-10_000.0, 2_750.0, 4_250.0, 3_250.0, 2_750.0];
+        let dates = [
+            date_serial(2008, 1, 1),
+            date_serial(2008, 3, 1),
+            date_serial(2008, 10, 30),
+            date_serial(2009, 2, 15),
+            date_serial(2009, 4, 1),
+        ]
+        .map(|d| d as f64);
+        assert_eq!(dates[0], 39_448.0);
+        let result = xirr(&values, &dates, 0.1).unwrap();
+        assert!((result - 0.373_362_535).abs() < 5e-9);
+
+        // Test case without a positive cash flow
+        let result = xirr(&[-1.0, -2.0], &dates[..2], 0.1);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = xirr(&values, &dates[1..], 0.1);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+}
@@ -0,0 +1,731 @@
+//! Execution quality analysis from trades and quotes, and simulation of execution schedules.
+//!
+//! Trade signs are `1` for buyer initiated trades, `-1` for seller initiated trades, and `0` for
+//! trades whose direction cannot be determined.
+
+use crate::IndicatorError;
+
+/// Checks that every parallel input slice has the same length as the trade prices.
+fn require_aligned(prices: &[f64], others: &[&[f64]]) -> Result<(), IndicatorError> {
+    if others.iter().any(|o| o.len() != prices.len()) {
+        return Err(IndicatorError::MismatchedLength(
+            "Quotes must be aligned with `prices`".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Classifies trades as buys or sells with the tick rule.
+///
+/// A trade above the previous trade price is a buy and a trade below it is a sell. Trades at the
+/// previous price inherit the previous classification. The first trade, and any trades before the
+/// first price change, are unclassified.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of trade prices in execution order.
+///
+/// # Returns
+///
+/// A vector with one trade sign per trade.
+pub fn classify_tick_rule(prices: &[f64]) -> Vec<i8> {
+    let mut sign = 0;
+    prices
+        .iter()
+        .enumerate()
+        .map(|(i, price)| {
+            if i > 0 {
+                let previous = prices[i - 1];
+                if *price > previous {
+                    sign = 1;
+                } else if *price < previous {
+                    sign = -1;
+                }
+            }
+            sign
+        })
+        .collect()
+}
+
+/// Classifies trades as buys or sells with the Lee-Ready algorithm.
+///
+/// Trades above the quote midpoint are buys and trades below it are sells. Trades exactly at the
+/// midpoint fall back to the tick rule. The quotes passed in should be the quotes prevailing at
+/// each trade, with any reporting delay already applied by the caller.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of trade prices in execution order.
+/// * `bids` - The prevailing bid price at each trade.
+/// * `asks` - The prevailing ask price at each trade.
+///
+/// # Returns
+///
+/// A Result containing one trade sign per trade or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `bids` or `asks` is not aligned with `prices`.
+pub fn classify_lee_ready(
+    prices: &[f64],
+    bids: &[f64],
+    asks: &[f64],
+) -> Result<Vec<i8>, IndicatorError> {
+    require_aligned(prices, &[bids, asks])?;
+
+    let ticks = classify_tick_rule(prices);
+    Ok(prices
+        .iter()
+        .zip(bids.iter().zip(asks))
+        .zip(ticks)
+        .map(|((price, (bid, ask)), tick)| {
+            let mid = (bid + ask) / 2.0;
+            if *price > mid {
+                1
+            } else if *price < mid {
+                -1
+            } else {
+                tick
+            }
+        })
+        .collect())
+}
+
+/// Calculates the effective spread of each trade, `2 * sign * (price - mid)`.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of trade prices in execution order.
+/// * `bids` - The prevailing bid price at each trade.
+/// * `asks` - The prevailing ask price at each trade.
+/// * `signs` - The trade sign of each trade, for example from [`classify_lee_ready`].
+///
+/// # Returns
+///
+/// A Result containing the effective spread of each trade in price units or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the quotes or signs are not aligned with
+/// `prices`.
+pub fn calculate_effective_spread(
+    prices: &[f64],
+    bids: &[f64],
+    asks: &[f64],
+    signs: &[i8],
+) -> Result<Vec<f64>, IndicatorError> {
+    require_aligned(prices, &[bids, asks])?;
+    if signs.len() != prices.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`signs` must be aligned with `prices`".to_string(),
+        ));
+    }
+
+    Ok(prices
+        .iter()
+        .zip(bids.iter().zip(asks))
+        .zip(signs)
+        .map(|((price, (bid, ask)), sign)| 2.0 * f64::from(*sign) * (price - (bid + ask) / 2.0))
+        .collect())
+}
+
+/// Calculates the realized spread of each trade, `2 * sign * (price - future_mid)`, where the
+/// future midpoint is measured `horizon` trades later.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of trade prices in execution order.
+/// * `bids` - The prevailing bid price at each trade.
+/// * `asks` - The prevailing ask price at each trade.
+/// * `signs` - The trade sign of each trade, for example from [`classify_lee_ready`].
+/// * `horizon` - The number of trades after which the midpoint is measured.
+///
+/// # Returns
+///
+/// A Result containing the realized spread in price units of every trade that has a midpoint
+/// `horizon` trades later, or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the quotes or signs are not aligned with
+/// `prices`, and an `IndicatorError::NotEnoughData` if the length of `prices` is not greater than
+/// `horizon`.
+pub fn calculate_realized_spread(
+    prices: &[f64],
+    bids: &[f64],
+    asks: &[f64],
+    signs: &[i8],
+    horizon: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_aligned(prices, &[bids, asks])?;
+    if signs.len() != prices.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`signs` must be aligned with `prices`".to_string(),
+        ));
+    }
+    if prices.len() <= horizon {
+        return Err(IndicatorError::NotEnoughData(
+            "`prices` must have more than `horizon` items".to_string(),
+        ));
+    }
+
+    Ok((0..prices.len() - horizon)
+        .map(|i| {
+            let future_mid = (bids[i + horizon] + asks[i + horizon]) / 2.0;
+            2.0 * f64::from(signs[i]) * (prices[i] - future_mid)
+        })
+        .collect())
+}
+
+/// Direction of an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    /// An order that buys the instrument
+    Buy,
+    /// An order that sells the instrument
+    Sell,
+}
+
+impl OrderSide {
+    /// Returns `1.0` for buys and `-1.0` for sells, so that `sign * (paid - benchmark)` is a cost.
+    fn sign(&self) -> f64 {
+        match self {
+            OrderSide::Buy => 1.0,
+            OrderSide::Sell => -1.0,
+        }
+    }
+}
+
+/// A single execution of part of an order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fill {
+    /// Execution price
+    pub price: f64,
+    /// Executed quantity
+    pub quantity: f64,
+}
+
+impl Fill {
+    /// Creates a new fill.
+    pub fn new(price: f64, quantity: f64) -> Self {
+        Fill { price, quantity }
+    }
+}
+
+/// Execution benchmarks produced by [`calculate_execution_benchmarks`].
+///
+/// Slippage is expressed in basis points of the benchmark price, with positive values meaning the
+/// order did worse than the benchmark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExecutionBenchmarks {
+    /// Quantity weighted average price of the fills
+    pub average_price: f64,
+    /// Volume weighted average price of the market over the interval
+    pub vwap: f64,
+    /// Time weighted average price of the market over the interval, each price weighted by how
+    /// long it stood
+    pub twap: f64,
+    /// Market price at the start of the interval
+    pub arrival_price: f64,
+    /// Slippage versus `vwap`
+    pub vwap_slippage_bps: f64,
+    /// Slippage versus `twap`
+    pub twap_slippage_bps: f64,
+    /// Slippage versus `arrival_price`, also known as implementation shortfall
+    pub arrival_slippage_bps: f64,
+}
+
+/// Compares the fills of an order against the VWAP, TWAP and arrival price of the market over the
+/// execution interval.
+///
+/// The interval runs from the first to the last entry of the tape, and the TWAP weights each price
+/// by how long it stood, from its own timestamp until the next entry, so bursts of prints do not
+/// skew it. A tape with a single entry has a TWAP of that price.
+///
+/// # Arguments
+///
+/// * `side` - The direction of the order.
+/// * `fills` - The executions of the order.
+/// * `tape_timestamps` - Strictly increasing Unix timestamps in seconds of the tape entries.
+/// * `tape_prices` - Market prices over the interval, sampled at regular times or per trade.
+/// * `tape_volumes` - Market volume at each entry of `tape_prices`.
+///
+/// # Returns
+///
+/// A Result containing the [`ExecutionBenchmarks`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the tape slices differ in length, an
+/// `IndicatorError::NotEnoughData` if `fills` or the tape is empty, and an
+/// `IndicatorError::InvalidInput` if `tape_timestamps` are not strictly increasing or the fills or
+/// the tape have no positive total quantity.
+pub fn calculate_execution_benchmarks(
+    side: OrderSide,
+    fills: &[Fill],
+    tape_timestamps: &[i64],
+    tape_prices: &[f64],
+    tape_volumes: &[f64],
+) -> Result<ExecutionBenchmarks, IndicatorError> {
+    if tape_prices.len() != tape_volumes.len() || tape_prices.len() != tape_timestamps.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`tape_timestamps`, `tape_prices` and `tape_volumes` must have the same length"
+                .to_string(),
+        ));
+    }
+    if fills.is_empty() || tape_prices.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "`fills` and the tape must not be empty".to_string(),
+        ));
+    }
+    if tape_timestamps.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(IndicatorError::InvalidInput(
+            "`tape_timestamps` must be strictly increasing".to_string(),
+        ));
+    }
+
+    let filled = fills.iter().map(|f| f.quantity).sum::<f64>();
+    let volume = tape_volumes.iter().sum::<f64>();
+    if filled <= 0.0 || volume <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`fills` and `tape_volumes` must have a positive total quantity".to_string(),
+        ));
+    }
+
+    let average_price = fills.iter().map(|f| f.price * f.quantity).sum::<f64>() / filled;
+    let vwap = tape_prices
+        .iter()
+        .zip(tape_volumes)
+        .map(|(p, v)| p * v)
+        .sum::<f64>()
+        / volume;
+    let duration = tape_timestamps[tape_timestamps.len() - 1] - tape_timestamps[0];
+    let twap = if duration == 0 {
+        tape_prices[0]
+    } else {
+        tape_prices
+            .iter()
+            .zip(tape_timestamps.windows(2))
+            .map(|(p, t)| p * (t[1] - t[0]) as f64)
+            .sum::<f64>()
+            / duration as f64
+    };
+    let arrival_price = tape_prices[0];
+    let slippage =
+        |benchmark: f64| side.sign() * (average_price - benchmark) / benchmark * 10_000.0;
+
+    Ok(ExecutionBenchmarks {
+        average_price,
+        vwap,
+        twap,
+        arrival_price,
+        vwap_slippage_bps: slippage(vwap),
+        twap_slippage_bps: slippage(twap),
+        arrival_slippage_bps: slippage(arrival_price),
+    })
+}
+
+/// Rule used by [`simulate_schedule`] to slice a parent order into child orders.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Trade the same quantity in every bar
+    Twap,
+    /// Trade in proportion to an expected volume profile with one weight per bar. The profile
+    /// should be estimated from past data, not from the tape being simulated.
+    Vwap(Vec<f64>),
+    /// Trade a fixed fraction of the market volume of every bar until the order is complete
+    Pov(f64),
+}
+
+/// Outcome of executing a parent order with [`simulate_schedule`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleSimulation {
+    /// Child order executed in each bar of the tape, including bars with no quantity.
+    pub fills: Vec<Fill>,
+    /// Total quantity executed, which may fall short of the parent order for POV schedules.
+    pub filled_quantity: f64,
+    /// Quantity weighted average execution price, `NaN` if nothing was executed.
+    pub average_price: f64,
+    /// Market price of the first bar, the decision price of the order.
+    pub arrival_price: f64,
+    /// Implementation shortfall versus `arrival_price` in basis points, positive for costs.
+    pub shortfall_bps: f64,
+    /// Implementation shortfall versus `arrival_price` in currency, positive for costs.
+    pub shortfall_cost: f64,
+}
+
+/// Simulates executing a parent order over a historical tape with a given slicing schedule.
+///
+/// Each child order executes at its bar's price moved against the order by a linear temporary
+/// impact, `price * (1 + impact * child / bar_volume)` for buys and `price * (1 - impact * child /
+/// bar_volume)` for sells.
+///
+/// # Arguments
+///
+/// * `side` - The direction of the parent order.
+/// * `quantity` - The size of the parent order.
+/// * `schedule` - The slicing rule of the parent order.
+/// * `tape_prices` - Market price of each bar of the execution interval.
+/// * `tape_volumes` - Market volume of each bar of the execution interval.
+/// * `impact` - The temporary impact coefficient, `0.0` to execute at the tape prices.
+///
+/// # Returns
+///
+/// A Result containing the [`ScheduleSimulation`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the tape slices or a VWAP profile do not have
+/// the same length, an `IndicatorError::NotEnoughData` if the tape is empty, and an
+/// `IndicatorError::InvalidInput` if `quantity` is not positive, a tape price or volume is not
+/// finite, a VWAP profile weight is negative or not finite, a VWAP profile does not have a positive
+/// sum, or a POV participation rate is not within `(0, 1]`.
+pub fn simulate_schedule(
+    side: OrderSide,
+    quantity: f64,
+    schedule: &Schedule,
+    tape_prices: &[f64],
+    tape_volumes: &[f64],
+    impact: f64,
+) -> Result<ScheduleSimulation, IndicatorError> {
+    if tape_prices.len() != tape_volumes.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`tape_prices` and `tape_volumes` must have the same length".to_string(),
+        ));
+    }
+    if tape_prices.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "The tape must not be empty".to_string(),
+        ));
+    }
+    if quantity.is_nan() || quantity <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`quantity` must be positive".to_string(),
+        ));
+    }
+    if tape_prices
+        .iter()
+        .chain(tape_volumes)
+        .any(|v| !v.is_finite())
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`tape_prices` and `tape_volumes` must be finite".to_string(),
+        ));
+    }
+
+    let bars = tape_prices.len();
+    let children = match schedule {
+        Schedule::Twap => vec![quantity / bars as f64; bars],
+        Schedule::Vwap(profile) => {
+            if profile.len() != bars {
+                return Err(IndicatorError::MismatchedLength(
+                    "The VWAP profile must have one weight per bar".to_string(),
+                ));
+            }
+            if profile.iter().any(|w| !w.is_finite() || *w < 0.0) {
+                return Err(IndicatorError::InvalidInput(
+                    "The VWAP profile weights must be finite and non-negative".to_string(),
+                ));
+            }
+            let total = profile.iter().sum::<f64>();
+            if total <= 0.0 {
+                return Err(IndicatorError::InvalidInput(
+                    "The VWAP profile must have a positive sum".to_string(),
+                ));
+            }
+            profile.iter().map(|w| quantity * w / total).collect()
+        }
+        Schedule::Pov(participation) => {
+            if !(*participation > 0.0 && *participation <= 1.0) {
+                return Err(IndicatorError::InvalidInput(
+                    "The POV participation rate must be within (0, 1]".to_string(),
+                ));
+            }
+            let mut remaining = quantity;
+            tape_volumes
+                .iter()
+                .map(|volume| {
+                    let child = (participation * volume).clamp(0.0, remaining);
+                    remaining -= child;
+                    child
+                })
+                .collect::<Vec<f64>>()
+        }
+    };
+
+    let fills = children
+        .iter()
+        .zip(tape_prices.iter().zip(tape_volumes))
+        .map(|(child, (price, volume))| {
+            let participation = if *volume > 0.0 { child / volume } else { 0.0 };
+            Fill::new(price * (1.0 + side.sign() * impact * participation), *child)
+        })
+        .collect::<Vec<Fill>>();
+
+    let filled_quantity = children.iter().sum::<f64>();
+    let average_price = fills.iter().map(|f| f.price * f.quantity).sum::<f64>() / filled_quantity;
+    let arrival_price = tape_prices[0];
+    let shortfall = side.sign() * (average_price - arrival_price);
+
+    Ok(ScheduleSimulation {
+        fills,
+        filled_quantity,
+        average_price,
+        arrival_price,
+        shortfall_bps: shortfall / arrival_price * 10_000.0,
+        shortfall_cost: shortfall * filled_quantity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRICES: [f64; 5] = [100.0, 100.05, 100.05, 100.02, 100.03];
+    const BIDS: [f64; 5] = [99.98, 100.0, 100.0, 99.98, 100.01];
+    const ASKS: [f64; 5] = [100.02, 100.06, 100.1, 100.02, 100.05];
+
+    #[test]
+    fn test_classify_tick_rule() {
+        assert_eq!(classify_tick_rule(&PRICES), vec![0, 1, 1, -1, 1]);
+        assert_eq!(classify_tick_rule(&[]), Vec::<i8>::new());
+    }
+
+    #[test]
+    fn test_classify_lee_ready() {
+        // Test case with enough data
+        let result = classify_lee_ready(&PRICES, &BIDS, &ASKS).unwrap();
+        assert_eq!(result, vec![0, 1, 1, 1, 1]);
+
+        // Test case with mismatched lengths
+        let result = classify_lee_ready(&PRICES, &BIDS[1..], &ASKS);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_effective_spread() {
+        // Test case with enough data
+        let signs = [1, 1, -1, 1, 0];
+        let result = calculate_effective_spread(&PRICES, &BIDS, &ASKS, &signs).unwrap();
+        let expected = [0.0, 0.04, 0.0, 0.04, 0.0];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_effective_spread(&PRICES, &BIDS, &ASKS, &signs[1..]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_realized_spread() {
+        // Test case with enough data
+        let signs = [1, 1, 1, 1, 1];
+        let result = calculate_realized_spread(&PRICES, &BIDS, &ASKS, &signs, 2).unwrap();
+        let expected = [-0.1, 0.1, 0.04];
+        assert_eq!(result.len(), expected.len());
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_realized_spread(&PRICES, &BIDS, &ASKS, &signs, 5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_execution_benchmarks() {
+        // Test case with enough data
+        let fills = [Fill::new(100.1, 40.0), Fill::new(100.3, 60.0)];
+        let timestamps = [0, 60, 120, 180];
+        let prices = [100.0, 100.2, 100.4, 100.2];
+        let volumes = [100.0, 300.0, 200.0, 400.0];
+        let result =
+            calculate_execution_benchmarks(OrderSide::Buy, &fills, &timestamps, &prices, &volumes)
+                .unwrap();
+        assert!((result.average_price - 100.22).abs() < 1e-9);
+        assert!((result.vwap - 100.22).abs() < 1e-9);
+        assert!((result.twap - 100.2).abs() < 1e-9);
+        assert_eq!(result.arrival_price, 100.0);
+        assert!(result.vwap_slippage_bps.abs() < 1e-9);
+        assert!((result.twap_slippage_bps - 0.02 / 100.2 * 10_000.0).abs() < 1e-9);
+        assert!((result.arrival_slippage_bps - 22.0).abs() < 1e-9);
+
+        // A sell at the same prices beats the arrival price
+        let result =
+            calculate_execution_benchmarks(OrderSide::Sell, &fills, &timestamps, &prices, &volumes)
+                .unwrap();
+        assert!((result.arrival_slippage_bps + 22.0).abs() < 1e-9);
+
+        // Test case with a burst of prints, where the price that stood longest dominates the TWAP
+        let bursty = [0, 1, 2, 180];
+        let result =
+            calculate_execution_benchmarks(OrderSide::Buy, &fills, &bursty, &prices, &volumes)
+                .unwrap();
+        assert!((result.twap - (100.0 + 100.2 + 100.4 * 178.0) / 180.0).abs() < 1e-9);
+        assert!((result.vwap - 100.22).abs() < 1e-9);
+
+        // Test case with a single tape entry
+        let result =
+            calculate_execution_benchmarks(OrderSide::Buy, &fills, &[0], &[100.0], &[100.0])
+                .unwrap();
+        assert_eq!(result.twap, 100.0);
+
+        // Test case with not enough data
+        let result =
+            calculate_execution_benchmarks(OrderSide::Buy, &[], &timestamps, &prices, &volumes);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with unsorted timestamps
+        let result = calculate_execution_benchmarks(
+            OrderSide::Buy,
+            &fills,
+            &[0, 60, 60, 180],
+            &prices,
+            &volumes,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result =
+            calculate_execution_benchmarks(OrderSide::Buy, &fills, &[0, 60], &prices, &volumes);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_simulate_schedule() {
+        let prices = [100.0, 100.2, 100.4, 100.2];
+        let volumes = [100.0, 300.0, 200.0, 400.0];
+
+        // Test case with a TWAP schedule and no impact
+        let result = simulate_schedule(
+            OrderSide::Buy,
+            100.0,
+            &Schedule::Twap,
+            &prices,
+            &volumes,
+            0.0,
+        )
+        .unwrap();
+        assert!(result.fills.iter().all(|f| f.quantity == 25.0));
+        assert!((result.average_price - 100.2).abs() < 1e-9);
+        assert!((result.shortfall_bps - 20.0).abs() < 1e-9);
+        assert!((result.shortfall_cost - 20.0).abs() < 1e-9);
+
+        // Test case with a VWAP profile and linear impact
+        let profile = Schedule::Vwap(vec![1.0, 1.0, 0.0, 2.0]);
+        let result =
+            simulate_schedule(OrderSide::Sell, 40.0, &profile, &prices, &volumes, 0.1).unwrap();
+        let quantities = result
+            .fills
+            .iter()
+            .map(|f| f.quantity)
+            .collect::<Vec<f64>>();
+        assert_eq!(quantities, vec![10.0, 10.0, 0.0, 20.0]);
+        assert!((result.fills[0].price - 99.0).abs() < 1e-9);
+        assert!((result.fills[3].price - 99.699).abs() < 1e-9);
+
+        // Test case with a POV schedule that completes early
+        let result = simulate_schedule(
+            OrderSide::Buy,
+            50.0,
+            &Schedule::Pov(0.1),
+            &prices,
+            &volumes,
+            0.0,
+        )
+        .unwrap();
+        let quantities = result
+            .fills
+            .iter()
+            .map(|f| f.quantity)
+            .collect::<Vec<f64>>();
+        assert_eq!(quantities, vec![10.0, 30.0, 10.0, 0.0]);
+        assert_eq!(result.filled_quantity, 50.0);
+
+        // Test case with an invalid participation rate
+        let result = simulate_schedule(
+            OrderSide::Buy,
+            50.0,
+            &Schedule::Pov(1.5),
+            &prices,
+            &volumes,
+            0.0,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with VWAP profiles with invalid weights
+        for weights in [
+            vec![1.0, -1.0, 1.0, 1.0],
+            vec![1.0, f64::NAN, 1.0, 1.0],
+            vec![1.0, f64::INFINITY, 1.0, 1.0],
+            vec![0.0; 4],
+        ] {
+            let result = simulate_schedule(
+                OrderSide::Buy,
+                50.0,
+                &Schedule::Vwap(weights),
+                &prices,
+                &volumes,
+                0.0,
+            );
+            match result {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+
+        // Test case with a volume that is not finite
+        let result = simulate_schedule(
+            OrderSide::Buy,
+            50.0,
+            &Schedule::Pov(0.1),
+            &prices,
+            &[100.0, f64::NAN, 100.0, 100.0],
+            0.0,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Cross-sectional factor signals, such as time series momentum, carry, and value, and their
+//! conversion into rank scores that can be compared and combined across assets.
+//!
+//! Every factor takes one price history per asset, ordered from oldest to newest with the same
+//! sampling for every asset, and produces one raw value per asset as of the latest bar. Assets
+//! without enough history get `NaN`, which [`rank_scores`] leaves out of the ranking.
+
+use crate::futures::FuturesCurve;
+use crate::IndicatorError;
+
+/// Calculates the momentum factor of each asset, the return from `lookback` bars ago to `skip`
+/// bars ago.
+///
+/// With monthly prices a `lookback` of 12 and a `skip` of 1 gives the classic 12-1 momentum,
+/// which leaves out the latest month to avoid its short-term reversal.
+///
+/// # Arguments
+///
+/// * `prices` - The price history of each asset.
+/// * `lookback` - The number of bars back to the start of the measured return.
+/// * `skip` - The number of most recent bars left out of the measured return.
+///
+/// # Returns
+///
+/// A Result containing the momentum of each asset or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `skip` is not less than `lookback`.
+pub fn calculate_momentum_factor(
+    prices: &[&[f64]],
+    lookback: usize,
+    skip: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    if skip >= lookback {
+        return Err(IndicatorError::InvalidInput(
+            "`skip` must be less than `lookback`".to_string(),
+        ));
+    }
+    Ok(prices
+        .iter()
+        .map(|history| {
+            if history.len() <= lookback {
+                return f64::NAN;
+            }
+            let last = history.len() - 1;
+            history[last - skip] / history[last - lookback] - 1.0
+        })
+        .collect())
+}
+
+/// Calculates the value factor of each asset as the log reversal over `lookback` bars, the log of
+/// the price `lookback` bars ago over the latest price.
+///
+/// Long-term losers score high, a price-only proxy for value that needs no fundamentals and
+/// works across asset classes, e.g. with a `lookback` of 60 monthly bars.
+///
+/// # Arguments
+///
+/// * `prices` - The price history of each asset.
+/// * `lookback` - The number of bars back to the reference price.
+///
+/// # Returns
+///
+/// A Result containing the value score of each asset or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `lookback` is zero.
+pub fn calculate_value_factor(
+    prices: &[&[f64]],
+    lookback: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    if lookback == 0 {
+        return Err(IndicatorError::InvalidInput(
+            "`lookback` must be positive".to_string(),
+        ));
+    }
+    Ok(prices
+        .iter()
+        .map(|history| {
+            if history.len() <= lookback {
+                return f64::NAN;
+            }
+            let last = history.len() - 1;
+            (history[last - lookback] / history[last]).ln()
+        })
+        .collect())
+}
+
+/// Calculates the carry factor of each futures market as the annualized roll yield between its
+/// first two contracts, which is positive when the curve is backwardated.
+///
+/// For markets quoted another way, such as the interest rate differential of a currency pair,
+/// the raw carry can be passed to [`rank_scores`] directly.
+pub fn calculate_carry_factor(curves: &[FuturesCurve]) -> Vec<f64> {
+    curves.iter().map(|curve| curve.roll_yields()[0]).collect()
+}
+
+/// Converts raw factor values into cross-sectional rank scores between `-1` for the lowest and
+/// `1` for the highest value.
+///
+/// Tied values share their average rank, `NaN` values are left out of the ranking and stay
+/// `NaN`, and a single ranked value scores `0`. Scores of different factors are on the same
+/// scale, so they can be combined with [`combine_scores`].
+pub fn rank_scores(values: &[f64]) -> Vec<f64> {
+    let mut order = (0..values.len())
+        .filter(|i| !values[*i].is_nan())
+        .collect::<Vec<usize>>();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut scores = vec![f64::NAN; values.len()];
+    let count = order.len();
+    let mut start = 0;
+    while start < count {
+        let mut end = start + 1;
+        while end < count && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // Zero based average rank of the tied values, centered on the middle of the ranking
+        let rank = (start + end - 1) as f64 / 2.0;
+        let score = if count > 1 {
+            2.0 * rank / (count - 1) as f64 - 1.0
+        } else {
+            0.0
+        };
+        for i in &order[start..end] {
+            scores[*i] = score;
+        }
+        start = end;
+    }
+    scores
+}
+
+/// Combines the rank scores of several factors into a weighted composite score per asset.
+///
+/// # Arguments
+///
+/// * `scores` - The scores of each factor, with one value per asset.
+/// * `weights` - The weight of each factor, which are normalized to sum to one.
+///
+/// # Returns
+///
+/// A Result containing the composite score of each asset or an `IndicatorError`. An asset with a
+/// `NaN` score for any factor gets a `NaN` composite.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `scores` is empty, an
+/// `IndicatorError::MismatchedLength` if `weights` and `scores` differ in length or the factors
+/// cover different numbers of assets, and an `IndicatorError::InvalidInput` if a weight is
+/// negative or the weights sum to zero.
+pub fn combine_scores(scores: &[&[f64]], weights: &[f64]) -> Result<Vec<f64>, IndicatorError> {
+    let Some(assets) = scores.first().map(|s| s.len()) else {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one factor is required".to_string(),
+        ));
+    };
+    if weights.len() != scores.len() || scores.iter().any(|s| s.len() != assets) {
+        return Err(IndicatorError::MismatchedLength(
+            "`weights` and every factor of `scores` must align".to_string(),
+        ));
+    }
+    let total = weights.iter().sum::<f64>();
+    if weights.iter().any(|w| w.is_nan() || *w < 0.0) || total <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`weights` must not be negative and must have a positive sum".to_string(),
+        ));
+    }
+
+    Ok((0..assets)
+        .map(|i| {
+            scores
+                .iter()
+                .zip(weights)
+                .map(|(factor, weight)| factor[i] * weight)
+                .sum::<f64>()
+                / total
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histories() -> Vec<Vec<f64>> {
+        vec![
+            (0..13).map(|i| 100.0 + 2.0 * i as f64).collect(),
+            vec![50.0; 13],
+            (0..13).map(|i| 100.0 - i as f64).collect(),
+            vec![10.0; 5],
+        ]
+    }
+
+    #[test]
+    fn test_calculate_momentum_factor() {
+        // Test case with enough data
+        let histories = histories();
+        let prices = histories.iter().map(|h| h.as_slice()).collect::<Vec<_>>();
+        let result = calculate_momentum_factor(&prices, 12, 1).unwrap();
+        assert!((result[0] - 0.22).abs() < 1e-12);
+        assert!(result[1].abs() < 1e-12);
+        assert!((result[2] - -0.11).abs() < 1e-12);
+        assert!(result[3].is_nan());
+
+        // Test case with invalid input
+        let result = calculate_momentum_factor(&prices, 12, 12);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_value_factor() {
+        // Test case with enough data
+        let histories = histories();
+        let prices = histories.iter().map(|h| h.as_slice()).collect::<Vec<_>>();
+        let result = calculate_value_factor(&prices, 12).unwrap();
+        assert!((result[0] - (100.0_f64 / 124.0).ln()).abs() < 1e-12);
+        assert!(result[1].abs() < 1e-12);
+        assert!((result[2] - (100.0_f64 / 88.0).ln()).abs() < 1e-12);
+        assert!(result[3].is_nan());
+
+        // Test case with invalid input
+        let result = calculate_value_factor(&prices, 0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_carry_factor() {
+        let curves = [
+            FuturesCurve::new(&[0.25, 0.5], &[100.0, 98.0]).unwrap(),
+            FuturesCurve::new(&[0.25, 0.5], &[98.0, 100.0]).unwrap(),
+        ];
+        let result = calculate_carry_factor(&curves);
+        assert!((result[0] - 0.080_810_829_270_077_88).abs() < 1e-12);
+        assert!((result[1] - -0.080_810_829_270_077_88).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rank_scores() {
+        assert_eq!(rank_scores(&[0.22, 0.0, -0.11]), vec![1.0, 0.0, -1.0]);
+        let result = rank_scores(&[1.0, f64::NAN, 1.0, 3.0]);
+        assert_eq!(result[0], -0.5);
+        assert!(result[1].is_nan());
+        assert_eq!(result[2], -0.5);
+        assert_eq!(result[3], 1.0);
+        let result = rank_scores(&[f64::NAN, 4.0]);
+        assert!(result[0].is_nan());
+        assert_eq!(result[1], 0.0);
+    }
+
+    #[test]
+    fn test_combine_scores() {
+        // Test case with enough data
+        let momentum = [1.0, 0.0, -1.0];
+        let value = [-1.0, 1.0, 0.0];
+        let result = combine_scores(&[&momentum, &value], &[3.0, 1.0]).unwrap();
+        assert_eq!(result, vec![0.5, 0.25, -0.75]);
+
+        // Test case with not enough data
+        let result = combine_scores(&[], &[]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = combine_scores(&[&momentum, &value[..2]], &[0.5, 0.5]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let result = combine_scores(&[&momentum, &value], &[1.0, -1.0]);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
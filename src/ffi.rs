@@ -0,0 +1,150 @@
+//! A C ABI for using the streaming indicators from other languages.
+//!
+//! Streaming states are opaque handles owned by the caller: a `*_new` function allocates a state
+//! and returns a pointer to it, the `*_update` and `*_preview` functions use it, and the `*_free`
+//! function releases it. The functions return one of the status codes below, and write values
+//! through an output pointer only when they return [`LIBFIN_OK`]. The symbols are not mangled,
+//! so a C header declares them as written here, e.g.
+//! `int32_t rsi_state_update(RsiState *state, double price, double *out);`.
+//!
+//! The module is only compiled with the `ffi` feature, so crates linking libfin do not export
+//! these symbols. The `libfin-capi` crate in `capi/` builds them into a shared and a static C
+//! library.
+
+use crate::streaming::{StreamingIndicator, StreamingRsi};
+
+/// The call succeeded and its value was written to the output pointer.
+pub const LIBFIN_OK: i32 = 0;
+
+/// The state has not seen enough prices yet and no value was written.
+pub const LIBFIN_NOT_ENOUGH_DATA: i32 = 1;
+
+/// A null pointer was passed and nothing was done.
+pub const LIBFIN_NULL_POINTER: i32 = -1;
+
+/// An opaque streaming RSI state.
+pub struct RsiState(StreamingRsi);
+
+/// Writes `value` through `out` and returns the matching status code.
+///
+/// # Safety
+///
+/// `out` must be valid for writes, so callers check it for null before calling this.
+unsafe fn write_value(value: Option<f64>, out: *mut f64) -> i32 {
+    match value {
+        Some(value) => {
+            *out = value;
+            LIBFIN_OK
+        }
+        None => LIBFIN_NOT_ENOUGH_DATA,
+    }
+}
+
+/// Creates a streaming RSI over `window` price changes, or returns null if `window` is zero.
+///
+/// The state must be released with [`rsi_state_free`].
+#[no_mangle]
+pub extern "C" fn rsi_state_new(window: usize) -> *mut RsiState {
+    if window == 0 {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(RsiState(StreamingRsi::new(window))))
+}
+
+/// Adds the closing price of a bar to the state and writes the RSI to `out` once it is available.
+///
+/// # Safety
+///
+/// `state` must be null or a pointer returned by [`rsi_state_new`] that was not freed, and `out`
+/// must be null or valid for writes. Both pointers are checked for null before anything is read or
+/// written, and a null pointer returns [`LIBFIN_NULL_POINTER`]. The state must not be used from
+/// several threads at once.
+#[no_mangle]
+pub unsafe extern "C" fn rsi_state_update(state: *mut RsiState, price: f64, out: *mut f64) -> i32 {
+    if state.is_null() || out.is_null() {
+        return LIBFIN_NULL_POINTER;
+    }
+    write_value((*state).0.update(price), out)
+}
+
+/// Writes the provisional RSI of a forming bar at `price` to `out`, leaving the state unchanged.
+///
+/// # Safety
+///
+/// `state` must be null or a pointer returned by [`rsi_state_new`] that was not freed, and `out`
+/// must be null or valid for writes. Both pointers are checked for null before anything is read or
+/// written, and a null pointer returns [`LIBFIN_NULL_POINTER`].
+#[no_mangle]
+pub unsafe extern "C" fn rsi_state_preview(
+    state: *const RsiState,
+    price: f64,
+    out: *mut f64,
+) -> i32 {
+    if state.is_null() || out.is_null() {
+        return LIBFIN_NULL_POINTER;
+    }
+    write_value((*state).0.preview(price), out)
+}
+
+/// Releases a state created by [`rsi_state_new`]. Null pointers are ignored.
+///
+/// # Safety
+///
+/// `state` must be null or a pointer returned by [`rsi_state_new`] that was not freed, and must
+/// not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn rsi_state_free(state: *mut RsiState) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculate_rsi;
+
+    #[test]
+    fn test_rsi_state() {
+        let closes = [44.0, 44.5, 43.8, 44.2, 45.1, 45.0, 46.2];
+        let expected = calculate_rsi(&closes, 3).unwrap();
+
+        let state = rsi_state_new(3);
+        assert!(!state.is_null());
+        let mut values = Vec::new();
+        let mut out = f64::NAN;
+        unsafe {
+            for close in closes {
+                let mut preview = f64::NAN;
+                let status = rsi_state_preview(state, close, &mut preview);
+                match rsi_state_update(state, close, &mut out) {
+                    LIBFIN_OK => {
+                        assert_eq!(status, LIBFIN_OK);
+                        assert_eq!(preview, out);
+                        values.push(out);
+                    }
+                    status => assert_eq!(status, LIBFIN_NOT_ENOUGH_DATA),
+                }
+            }
+            assert_eq!(
+                rsi_state_update(std::ptr::null_mut(), 1.0, &mut out),
+                LIBFIN_NULL_POINTER
+            );
+            assert_eq!(
+                rsi_state_update(state, 1.0, std::ptr::null_mut()),
+                LIBFIN_NULL_POINTER
+            );
+            assert_eq!(
+                rsi_state_preview(state, 1.0, std::ptr::null_mut()),
+                LIBFIN_NULL_POINTER
+            );
+            rsi_state_free(state);
+            rsi_state_free(std::ptr::null_mut());
+        }
+        assert_eq!(values.len(), expected.len());
+        for (value, expected) in values.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+        assert!(rsi_state_new(0).is_null());
+    }
+}
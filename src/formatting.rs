@@ -0,0 +1,378 @@
+//! Formatting and parsing of prices and rates the way markets quote them.
+
+use crate::IndicatorError;
+
+/// Rounds `price` to the nearest multiple of `tick`, rounding halves away from zero.
+///
+/// Ticks that are not positive and finite leave the price unchanged.
+pub fn round_to_tick(price: f64, tick: f64) -> f64 {
+    if !tick.is_finite() || tick <= 0.0 {
+        return price;
+    }
+    // Rounding the quotient first removes representation errors such as 0.3 / 0.1 = 2.9999...
+    let ticks = ((price / tick) * 1e9).round() / 1e9;
+    ticks.round() * tick
+}
+
+/// Formats a bond price in 32nds, e.g. `99.515625` as `"99-16+"`.
+///
+/// The price is rounded to the nearest quarter of a 32nd. Quarters are written as a trailing `2`,
+/// halves as `+`, and three quarters as `6`, so `99-162` is 99 and 16.25 32nds.
+pub fn format_32nds(price: f64) -> String {
+    let sign = if price < 0.0 { "-" } else { "" };
+    let quarters = (price.abs() * 128.0).round() as u64;
+    let (handle, quarters) = (quarters / 128, quarters % 128);
+    let suffix = ["", "2", "+", "6"][(quarters % 4) as usize];
+    format!("{}{}-{:02}{}", sign, handle, quarters / 4, suffix)
+}
+
+/// Parses a bond price in 32nds as written by [`format_32nds`], e.g. `"99-16+"` as `99.515625`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `quote` is not a handle and a number of 32nds
+/// below 32 separated by `-`, optionally followed by `+` or a digit `2` or `6`.
+pub fn parse_32nds(quote: &str) -> Result<f64, IndicatorError> {
+    let invalid = || IndicatorError::InvalidInput(format!("`{}` is not a price in 32nds", quote));
+    let (negative, unsigned) = match quote.trim().strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, quote.trim()),
+    };
+    let (handle, fraction) = unsigned.split_once('-').ok_or_else(invalid)?;
+    let handle = handle.parse::<u64>().map_err(|_| invalid())?;
+    let (digits, quarter) = match fraction.strip_suffix('+') {
+        Some(digits) => (digits, 2),
+        None if fraction.len() == 3 => match &fraction[2..] {
+            "2" => (&fraction[..2], 1),
+            "6" => (&fraction[..2], 3),
+            _ => return Err(invalid()),
+        },
+        None => (fraction, 0),
+    };
+    if digits.len() != 2 {
+        return Err(invalid());
+    }
+    let thirty_seconds = digits.parse::<u64>().map_err(|_| invalid())?;
+    if thirty_seconds >= 32 {
+        return Err(invalid());
+    }
+    let price = handle as f64 + (thirty_seconds * 4 + quarter) as f64 / 128.0;
+    Ok(if negative { -price } else { price })
+}
+
+/// Returns the pip size of a currency pair quoted in `quote_currency`: 0.01 for the Japanese yen
+/// and 0.0001 otherwise.
+pub fn pip_size(quote_currency: &str) -> f64 {
+    if quote_currency.eq_ignore_ascii_case("JPY") {
+        0.01
+    } else {
+        0.0001
+    }
+}
+
+/// Converts a change of an exchange rate into pips.
+pub fn to_pips(change: f64, pip_size: f64) -> f64 {
+    change / pip_size
+}
+
+/// Converts a number of pips into a change of an exchange rate.
+pub fn from_pips(pips: f64, pip_size: f64) -> f64 {
+    pips * pip_size
+}
+
+/// Converts a rate, e.g. `0.0125`, into basis points, e.g. `125.0`.
+pub fn to_bps(rate: f64) -> f64 {
+    rate * 10_000.0
+}
+
+/// Converts basis points, e.g. `125.0`, into a rate, e.g. `0.0125`.
+pub fn from_bps(bps: f64) -> f64 {
+    bps / 10_000.0
+}
+
+/// Formats a rate in basis points with `decimals` decimals, e.g. `0.00125` as `"12.5 bp"`.
+pub fn format_bps(rate: f64, decimals: usize) -> String {
+    format!("{:.*} bp", decimals, to_bps(rate))
+}
+
+/// Parses a rate in basis points as written by [`format_bps`], e.g. `"12.5 bp"` as `0.00125`.
+///
+/// The unit may be written as `bp` or `bps`, with or without a space before it.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `text` is not a decimal number followed by the
+/// unit.
+pub fn parse_bps(text: &str) -> Result<f64, IndicatorError> {
+    let invalid = || IndicatorError::InvalidInput(format!("`{}` is not a rate in bp", text));
+    let trimmed = text.trim();
+    let number = trimmed
+        .strip_suffix("bps")
+        .or_else(|| trimmed.strip_suffix("bp"))
+        .ok_or_else(invalid)?;
+    let plain = Locale {
+        group_separator: None,
+        ..Locale::EN_US
+    };
+    let bps = parse_number(number.trim_end(), plain).map_err(|_| invalid())?;
+    Ok(from_bps(bps))
+}
+
+/// The separators a locale writes numbers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// Separator between the integer and fractional digits
+    pub decimal_separator: char,
+    /// Separator between groups of three integer digits, if any
+    pub group_separator: Option<char>,
+    /// Whether a space separates a number from the percent sign
+    pub space_before_percent: bool,
+}
+
+impl Locale {
+    /// English as written in the United States, e.g. `1,234.5%`
+    pub const EN_US: Locale = Locale {
+        decimal_separator: '.',
+        group_separator: Some(','),
+        space_before_percent: false,
+    };
+    /// German as written in Germany, e.g. `1.234,5 %`
+    pub const DE_DE: Locale = Locale {
+        decimal_separator: ',',
+        group_separator: Some('.'),
+        space_before_percent: true,
+    };
+    /// French as written in France, e.g. `1 234,5 %` with narrow no-break spaces
+    pub const FR_FR: Locale = Locale {
+        decimal_separator: ',',
+        group_separator: Some('\u{202f}'),
+        space_before_percent: true,
+    };
+    /// Swiss German, e.g. `1’234.5%`
+    pub const DE_CH: Locale = Locale {
+        decimal_separator: '.',
+        group_separator: Some('’'),
+        space_before_percent: false,
+    };
+}
+
+/// Formats a number with `decimals` decimals and the separators of `locale`.
+pub fn format_number(value: f64, decimals: usize, locale: Locale) -> String {
+    let formatted = format!("{:.*}", decimals, value);
+    if !value.is_finite() {
+        return formatted;
+    }
+    let (sign, unsigned) = match formatted.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", formatted.as_str()),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    let mut result = sign.to_string();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i).is_multiple_of(3) {
+            result.extend(locale.group_separator);
+        }
+        result.push(digit);
+    }
+    if !fraction.is_empty() {
+        result.push(locale.decimal_separator);
+        result.push_str(fraction);
+    }
+    result
+}
+
+/// Parses a number written with the separators of `locale`, the inverse of [`format_number`].
+///
+/// Group separators are optional, but where present they must separate groups of three integer
+/// digits.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `text` is not an optionally negative decimal
+/// number in `locale`.
+pub fn parse_number(text: &str, locale: Locale) -> Result<f64, IndicatorError> {
+    let invalid = || IndicatorError::InvalidInput(format!("`{}` is not a number", text));
+    let is_digits = |digits: &str| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+    let trimmed = text.trim();
+    let (negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+    let (integer, fraction) = match unsigned.split_once(locale.decimal_separator) {
+        Some((integer, fraction)) if is_digits(fraction) => (integer, fraction),
+        Some(_) => return Err(invalid()),
+        None => (unsigned, ""),
+    };
+
+    let mut digits = String::with_capacity(unsigned.len());
+    let groups = match locale.group_separator {
+        Some(separator) => integer.split(separator).collect::<Vec<&str>>(),
+        None => vec![integer],
+    };
+    for (i, group) in groups.iter().enumerate() {
+        let valid_length = match (groups.len(), i) {
+            (1, _) => true,
+            (_, 0) => group.len() <= 3,
+            _ => group.len() == 3,
+        };
+        if !is_digits(group) || !valid_length {
+            return Err(invalid());
+        }
+        digits.push_str(group);
+    }
+    if !fraction.is_empty() {
+        digits.push('.');
+        digits.push_str(fraction);
+    }
+    let value = digits.parse::<f64>().map_err(|_| invalid())?;
+    Ok(if negative { -value } else { value })
+}
+
+/// Formats a fraction as a percentage with `decimals` decimals in `locale`, e.g. `0.125` as
+/// `"12.5%"` in [`Locale::EN_US`] and `"12,5 %"` in [`Locale::DE_DE`].
+pub fn format_percent(value: f64, decimals: usize, locale: Locale) -> String {
+    let space = if locale.space_before_percent {
+        "\u{a0}"
+    } else {
+        ""
+    };
+    format!(
+        "{}{}%",
+        format_number(value * 100.0, decimals, locale),
+        space
+    )
+}
+
+/// Parses a percentage written in `locale` into a fraction, the inverse of [`format_percent`],
+/// e.g. `"12,5 %"` in [`Locale::DE_DE`] as `0.125`.
+///
+/// Any spacing between the number and the percent sign is accepted.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `text` is not a number in `locale` followed by a
+/// percent sign.
+pub fn parse_percent(text: &str, locale: Locale) -> Result<f64, IndicatorError> {
+    let invalid = || IndicatorError::InvalidInput(format!("`{}` is not a percentage", text));
+    let number = text.trim().strip_suffix('%').ok_or_else(invalid)?;
+    let percent = parse_number(number.trim_end(), locale).map_err(|_| invalid())?;
+    Ok(percent / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_to_tick() {
+        assert_eq!(round_to_tick(101.237, 0.05), 101.25);
+        assert!((round_to_tick(0.3, 0.1) - 0.3).abs() < 1e-15);
+        assert_eq!(round_to_tick(99.515_625, 1.0 / 64.0), 99.515_625);
+        assert_eq!(round_to_tick(-1.125, 0.25), -1.25);
+        assert_eq!(round_to_tick(1.3, 0.0), 1.3);
+    }
+
+    #[test]
+    fn test_32nds() {
+        assert_eq!(format_32nds(99.5), "99-16");
+        assert_eq!(format_32nds(99.515_625), "99-16+");
+        assert_eq!(format_32nds(99.0 + 16.25 / 32.0), "99-162");
+        assert_eq!(format_32nds(101.0 + 3.75 / 32.0), "101-036");
+        assert_eq!(format_32nds(99.999), "100-00");
+        assert_eq!(parse_32nds("99-16+").unwrap(), 99.515_625);
+        assert_eq!(parse_32nds("101-036").unwrap(), 101.0 + 3.75 / 32.0);
+        for price in [0.0, 99.5, 100.0 + 31.75 / 32.0, -2.25] {
+            assert_eq!(parse_32nds(&format_32nds(price)).unwrap(), price);
+        }
+
+        // Test case with invalid input
+        for quote in ["99.16", "99-32", "99-1", "99-163", "x-16"] {
+            match parse_32nds(quote) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pips_and_bps() {
+        assert!((to_pips(1.1050 - 1.1025, pip_size("USD")) - 25.0).abs() < 1e-9);
+        assert!((to_pips(151.20 - 150.95, pip_size("jpy")) - 25.0).abs() < 1e-9);
+        assert!((from_pips(25.0, 0.0001) - 0.0025).abs() < 1e-15);
+        assert_eq!(to_bps(0.0125), 125.0);
+        assert_eq!(from_bps(125.0), 0.0125);
+        assert_eq!(format_bps(0.001_25, 1), "12.5 bp");
+        assert_eq!(parse_bps("12.5 bp").unwrap(), 0.001_25);
+        assert_eq!(parse_bps("-40bps").unwrap(), -0.004);
+        for rate in [0.0, 0.001_25, -0.0125, 0.5] {
+            assert!((parse_bps(&format_bps(rate, 2)).unwrap() - rate).abs() < 1e-15);
+        }
+
+        // Test case with invalid input
+        for text in [
+            "12.5", "12.5 %", "bp", "1,250 bp", "12. bp", "inf bp", "1e3 bp",
+        ] {
+            match parse_bps(text) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_percent() {
+        assert_eq!(
+            format_number(-1_234_567.891, 2, Locale::EN_US),
+            "-1,234,567.89"
+        );
+        assert_eq!(format_number(999.0, 0, Locale::DE_DE), "999");
+        assert_eq!(format_percent(12.345, 1, Locale::EN_US), "1,234.5%");
+        assert_eq!(format_percent(12.345, 1, Locale::DE_DE), "1.234,5\u{a0}%");
+        assert_eq!(
+            format_percent(12.345, 1, Locale::FR_FR),
+            "1\u{202f}234,5\u{a0}%"
+        );
+        assert_eq!(format_percent(0.125, 2, Locale::DE_CH), "12.50%");
+    }
+
+    #[test]
+    fn test_parse_percent() {
+        assert_eq!(
+            parse_number("-1,234,567.89", Locale::EN_US).unwrap(),
+            -1_234_567.89
+        );
+        assert_eq!(parse_number("1234,5", Locale::DE_DE).unwrap(), 1_234.5);
+        assert_eq!(parse_percent("12.5%", Locale::EN_US).unwrap(), 0.125);
+        assert_eq!(parse_percent("12,5 %", Locale::DE_DE).unwrap(), 0.125);
+        for locale in [Locale::EN_US, Locale::DE_DE, Locale::FR_FR, Locale::DE_CH] {
+            for value in [0.0, 0.125, -0.034_5, 12.345, 12_345.678_9] {
+                let text = format_percent(value, 4, locale);
+                assert!((parse_percent(&text, locale).unwrap() - value).abs() < 1e-9);
+            }
+        }
+
+        // Test case with invalid input
+        for text in [
+            "12.5",
+            "12..5%",
+            "1,23,456%",
+            "1.234,5 %",
+            "12,5,%",
+            "%",
+            "-%",
+            "x%",
+        ] {
+            match parse_percent(text, Locale::EN_US) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+}
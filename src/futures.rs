@@ -0,0 +1,250 @@
+//! Term structure analytics of a futures curve, such as its shape, slope, and roll yield.
+//!
+//! Yields and slopes are continuously compounded and annualized by the time between expiries, so
+//! a roll yield of `0.05` means the curve rolls up 5% per year when it is backwardated.
+
+use crate::IndicatorError;
+
+/// The shape of a futures curve, produced by [`FuturesCurve::shape`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveShape {
+    /// Prices rise with expiry, so long positions lose value as contracts roll toward spot
+    Contango,
+    /// Prices fall with expiry, so long positions gain value as contracts roll toward spot
+    Backwardation,
+    /// All contracts have the same price
+    Flat,
+    /// Prices both rise and fall along the curve
+    Mixed,
+}
+
+/// The prices of the contracts of a futures curve on one date, ordered by expiry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesCurve {
+    years_to_expiry: Vec<f64>,
+    prices: Vec<f64>,
+}
+
+impl FuturesCurve {
+    /// Creates a futures curve from contract expiries and prices.
+    ///
+    /// # Arguments
+    ///
+    /// * `years_to_expiry` - The time to expiry in years of each contract, in increasing order.
+    /// * `prices` - The price of each contract.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the `FuturesCurve` or an `IndicatorError`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::MismatchedLength` if `years_to_expiry` and `prices` differ in
+    /// length, an `IndicatorError::NotEnoughData` if there are fewer than two contracts, and an
+    /// `IndicatorError::InvalidInput` if the expiries are not positive and strictly increasing or
+    /// a price is not positive.
+    pub fn new(years_to_expiry: &[f64], prices: &[f64]) -> Result<Self, IndicatorError> {
+        if years_to_expiry.len() != prices.len() {
+            return Err(IndicatorError::MismatchedLength(
+                "`years_to_expiry` and `prices` must have the same length".to_string(),
+            ));
+        }
+        if prices.len() < 2 {
+            return Err(IndicatorError::NotEnoughData(
+                "A futures curve needs at least two contracts".to_string(),
+            ));
+        }
+        let first = years_to_expiry[0];
+        if first.is_nan()
+            || first <= 0.0
+            || years_to_expiry
+                .windows(2)
+                .any(|t| t[1].is_nan() || t[1] <= t[0])
+        {
+            return Err(IndicatorError::InvalidInput(
+                "`years_to_expiry` must be positive and strictly increasing".to_string(),
+            ));
+        }
+        if prices.iter().any(|price| price.is_nan() || *price <= 0.0) {
+            return Err(IndicatorError::InvalidInput(
+                "`prices` must be positive".to_string(),
+            ));
+        }
+        Ok(FuturesCurve {
+            years_to_expiry: years_to_expiry.to_vec(),
+            prices: prices.to_vec(),
+        })
+    }
+
+    /// Returns the time to expiry in years of each contract.
+    pub fn years_to_expiry(&self) -> &[f64] {
+        &self.years_to_expiry
+    }
+
+    /// Returns the price of each contract.
+    pub fn prices(&self) -> &[f64] {
+        &self.prices
+    }
+
+    /// Classifies the curve by the direction of the price changes between adjacent contracts.
+    /// Contracts with equal prices do not break contango or backwardation.
+    pub fn shape(&self) -> CurveShape {
+        let rising = self.prices.windows(2).any(|p| p[1] > p[0]);
+        let falling = self.prices.windows(2).any(|p| p[1] < p[0]);
+        match (rising, falling) {
+            (true, false) => CurveShape::Contango,
+            (false, true) => CurveShape::Backwardation,
+            (false, false) => CurveShape::Flat,
+            (true, true) => CurveShape::Mixed,
+        }
+    }
+
+    /// Calculates the annualized log slope between the first and last contracts. The slope is
+    /// positive in contango and negative in backwardation.
+    pub fn slope(&self) -> f64 {
+        let last = self.prices.len() - 1;
+        (self.prices[last] / self.prices[0]).ln()
+            / (self.years_to_expiry[last] - self.years_to_expiry[0])
+    }
+
+    /// Calculates the annualized roll yield between each pair of adjacent contracts.
+    ///
+    /// The value at index `i` is the yield earned by holding contract `i + 1` while it converges
+    /// to the price of contract `i`, so it is positive where the curve is backwardated.
+    pub fn roll_yields(&self) -> Vec<f64> {
+        self.prices
+            .windows(2)
+            .zip(self.years_to_expiry.windows(2))
+            .map(|(p, t)| (p[0] / p[1]).ln() / (t[1] - t[0]))
+            .collect()
+    }
+
+    /// Calculates the annualized roll yield of the front contract as it converges to spot.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `spot` is not positive.
+    pub fn spot_roll_yield(&self, spot: f64) -> Result<f64, IndicatorError> {
+        if spot.is_nan() || spot <= 0.0 {
+            return Err(IndicatorError::InvalidInput(
+                "`spot` must be positive".to_string(),
+            ));
+        }
+        Ok((spot / self.prices[0]).ln() / self.years_to_expiry[0])
+    }
+}
+
+/// Calculates carry signals from the front roll yield of a sequence of futures curves.
+///
+/// The signal is `1` (long) when the roll yield between the first two contracts exceeds
+/// `threshold`, `-1` (short) when it is below `-threshold`, and `0` otherwise. The curves can be
+/// the history of one market, giving signals in the form taken by
+/// [`crate::signals::evaluate_signals`], or several markets on one date.
+///
+/// # Arguments
+///
+/// * `curves` - The futures curves to derive signals from.
+/// * `threshold` - The annualized roll yield a curve must exceed before taking a position.
+///
+/// # Returns
+///
+/// A Result containing a vector of signals or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `threshold` is negative.
+pub fn calculate_carry_signals(
+    curves: &[FuturesCurve],
+    threshold: f64,
+) -> Result<Vec<i8>, IndicatorError> {
+    if threshold.is_nan() || threshold < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`threshold` must not be negative".to_string(),
+        ));
+    }
+    Ok(curves
+        .iter()
+        .map(|curve| {
+            let roll_yield = curve.roll_yields()[0];
+            if roll_yield > threshold {
+                1
+            } else if roll_yield < -threshold {
+                -1
+            } else {
+                0
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_futures_curve() {
+        // Test case with enough data
+        let curve = FuturesCurve::new(&[0.25, 0.5, 1.0], &[100.0, 98.0, 95.0]).unwrap();
+        assert_eq!(curve.shape(), CurveShape::Backwardation);
+        assert!((curve.slope() - -0.068_391_059_183_400_77).abs() < 1e-12);
+        let roll_yields = curve.roll_yields();
+        assert!((roll_yields[0] - 0.080_810_829_270_077_88).abs() < 1e-12);
+        assert!((roll_yields[1] - 0.062_181_174_140_062_36).abs() < 1e-12);
+        let spot_roll_yield = curve.spot_roll_yield(101.0).unwrap();
+        assert!((spot_roll_yield - 0.039_801_323_412_672_37).abs() < 1e-12);
+
+        let contango = FuturesCurve::new(&[0.25, 0.5], &[95.0, 100.0]).unwrap();
+        assert_eq!(contango.shape(), CurveShape::Contango);
+        let mixed = FuturesCurve::new(&[0.25, 0.5, 1.0], &[95.0, 100.0, 98.0]).unwrap();
+        assert_eq!(mixed.shape(), CurveShape::Mixed);
+        let flat = FuturesCurve::new(&[0.25, 0.5], &[100.0, 100.0]).unwrap();
+        assert_eq!(flat.shape(), CurveShape::Flat);
+
+        // Test case with not enough data
+        let result = FuturesCurve::new(&[0.25], &[100.0]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = FuturesCurve::new(&[0.25, 0.5], &[100.0]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let result = FuturesCurve::new(&[0.5, 0.25], &[100.0, 98.0]);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_carry_signals() {
+        let curves = [
+            FuturesCurve::new(&[0.25, 0.5], &[100.0, 98.0]).unwrap(),
+            FuturesCurve::new(&[0.25, 0.5], &[100.0, 100.5]).unwrap(),
+            FuturesCurve::new(&[0.25, 0.5], &[98.0, 100.0]).unwrap(),
+        ];
+        let result = calculate_carry_signals(&curves, 0.05).unwrap();
+        assert_eq!(result, vec![1, 0, -1]);
+
+        // Test case with invalid input
+        let result = calculate_carry_signals(&curves, -0.05);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
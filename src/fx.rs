@@ -0,0 +1,194 @@
+//! FX forwards priced by covered interest parity, forward points, and diagnostics of deviations
+//! from parity.
+//!
+//! Currency pairs are quoted as units of the quote currency per unit of the base currency, e.g.
+//! EURUSD in dollars per euro. Interest rates are simple money market rates for the period, with
+//! `years` the year fraction of the period under the market's day count convention.
+
+use crate::IndicatorError;
+
+/// Checks the common inputs of the parity calculations.
+fn require_parity_inputs(spot: f64, years: f64, rates: &[f64]) -> Result<(), IndicatorError> {
+    if !(spot > 0.0 && years > 0.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`spot` and `years` must be positive".to_string(),
+        ));
+    }
+    if rates
+        .iter()
+        .any(|rate| rate.is_nan() || 1.0 + rate * years <= 0.0)
+    {
+        return Err(IndicatorError::InvalidInput(
+            "Every rate must give a positive growth factor over the period".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates the outright forward rate implied by covered interest parity,
+/// `spot * (1 + quote_rate * years) / (1 + base_rate * years)`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `spot` or `years` is not positive, or a rate gives
+/// a growth factor that is not positive.
+pub fn forward_rate(
+    spot: f64,
+    base_rate: f64,
+    quote_rate: f64,
+    years: f64,
+) -> Result<f64, IndicatorError> {
+    require_parity_inputs(spot, years, &[base_rate, quote_rate])?;
+    Ok(spot * (1.0 + quote_rate * years) / (1.0 + base_rate * years))
+}
+
+/// Converts an outright forward into forward points, the difference to spot in pips, e.g. with a
+/// pip size from [`crate::formatting::pip_size`].
+pub fn forward_points(spot: f64, forward: f64, pip_size: f64) -> f64 {
+    (forward - spot) / pip_size
+}
+
+/// Converts forward points into an outright forward.
+pub fn outright_forward(spot: f64, points: f64, pip_size: f64) -> f64 {
+    spot + points * pip_size
+}
+
+/// Calculates the base currency rate implied by a spot, a forward, and the quote currency rate.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `spot`, `forward`, or `years` is not positive, or
+/// `quote_rate` gives a growth factor that is not positive.
+pub fn implied_base_rate(
+    spot: f64,
+    forward: f64,
+    quote_rate: f64,
+    years: f64,
+) -> Result<f64, IndicatorError> {
+    require_parity_inputs(spot, years, &[quote_rate])?;
+    if forward.is_nan() || forward <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`forward` must be positive".to_string(),
+        ));
+    }
+    Ok((spot * (1.0 + quote_rate * years) / forward - 1.0) / years)
+}
+
+/// Calculates the quote currency rate implied by a spot, a forward, and the base currency rate.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `spot`, `forward`, or `years` is not positive, or
+/// `base_rate` gives a growth factor that is not positive.
+pub fn implied_quote_rate(
+    spot: f64,
+    forward: f64,
+    base_rate: f64,
+    years: f64,
+) -> Result<f64, IndicatorError> {
+    require_parity_inputs(spot, years, &[base_rate])?;
+    if forward.is_nan() || forward <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`forward` must be positive".to_string(),
+        ));
+    }
+    Ok((forward / spot * (1.0 + base_rate * years) - 1.0) / years)
+}
+
+/// How far a quoted forward deviates from covered interest parity, produced by
+/// [`covered_interest_parity`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParityDeviation {
+    /// Forward implied by parity with the money market rates
+    pub theoretical_forward: f64,
+    /// Quoted forward minus the theoretical forward, in forward points
+    pub mispricing_points: f64,
+    /// Base currency rate implied by the quoted forward and the quote currency rate
+    pub implied_base_rate: f64,
+    /// Implied minus money market base currency rate, the cross-currency basis of borrowing the
+    /// base currency synthetically through the FX swap instead of the money market
+    pub basis: f64,
+}
+
+/// Compares a quoted forward with covered interest parity.
+///
+/// # Arguments
+///
+/// * `spot` - The spot rate.
+/// * `forward` - The quoted outright forward rate.
+/// * `base_rate` - The money market rate of the base currency.
+/// * `quote_rate` - The money market rate of the quote currency.
+/// * `years` - The year fraction of the period to the forward date.
+/// * `pip_size` - The pip size of the pair, which scales the forward points.
+///
+/// # Returns
+///
+/// A Result containing the [`ParityDeviation`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `spot`, `forward`, or `years` is not positive, or
+/// a rate gives a growth factor that is not positive.
+pub fn covered_interest_parity(
+    spot: f64,
+    forward: f64,
+    base_rate: f64,
+    quote_rate: f64,
+    years: f64,
+    pip_size: f64,
+) -> Result<ParityDeviation, IndicatorError> {
+    let theoretical_forward = forward_rate(spot, base_rate, quote_rate, years)?;
+    let implied_base_rate = implied_base_rate(spot, forward, quote_rate, years)?;
+    Ok(ParityDeviation {
+        theoretical_forward,
+        mispricing_points: forward_points(theoretical_forward, forward, pip_size),
+        implied_base_rate,
+        basis: implied_base_rate - base_rate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_rate() {
+        // EURUSD with euro rates at 3% and dollar rates at 5% over six months
+        let forward = forward_rate(1.10, 0.03, 0.05, 0.5).unwrap();
+        assert!((forward - 1.110_837_438_423_645_5).abs() < 1e-12);
+        let points = forward_points(1.10, forward, 1e-4);
+        assert!((points - 108.374_384_236_453_74).abs() < 1e-8);
+        assert!((outright_forward(1.10, points, 1e-4) - forward).abs() < 1e-12);
+        let base = implied_base_rate(1.10, forward, 0.05, 0.5).unwrap();
+        let quote = implied_quote_rate(1.10, forward, 0.03, 0.5).unwrap();
+        assert!((base - 0.03).abs() < 1e-12);
+        assert!((quote - 0.05).abs() < 1e-12);
+
+        // Test case with invalid input
+        let result = forward_rate(1.10, -3.0, 0.05, 0.5);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_covered_interest_parity() {
+        let result = covered_interest_parity(1.10, 1.11, 0.03, 0.05, 0.5, 1e-4).unwrap();
+        assert!((result.theoretical_forward - 1.110_837_438_423_645_5).abs() < 1e-12);
+        assert!((result.mispricing_points - -8.374_384_236_453_647).abs() < 1e-8);
+        assert!((result.implied_base_rate - 0.031_531_531_531_531_43).abs() < 1e-12);
+        assert!((result.basis - 0.001_531_531_531_531_432_6).abs() < 1e-12);
+
+        // Test case with invalid input
+        let result = covered_interest_parity(1.10, 0.0, 0.03, 0.05, 0.5, 1e-4);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
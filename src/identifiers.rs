@@ -0,0 +1,344 @@
+//! Validated security identifiers: ISINs, CUSIPs, and FIGIs.
+//!
+//! Every identifier is checked for its length, its alphabet, and its check digit on construction,
+//! so a value of one of these types is always well formed and can key instrument data safely.
+
+use std::fmt;
+
+use crate::IndicatorError;
+
+/// Returns the value of an identifier character: digits are themselves, letters count from 10 for
+/// `A` to 35 for `Z`, and the CUSIP symbols `*`, `@`, and `#` are 36, 37, and 38.
+fn character_value(c: u8) -> Option<u32> {
+    match c {
+        b'0'..=b'9' => Some((c - b'0') as u32),
+        b'A'..=b'Z' => Some((c - b'A') as u32 + 10),
+        b'*' => Some(36),
+        b'@' => Some(37),
+        b'#' => Some(38),
+        _ => None,
+    }
+}
+
+/// Sums the digits of a number.
+fn digit_sum(mut value: u32) -> u32 {
+    let mut sum = 0;
+    while value > 0 {
+        sum += value % 10;
+        value /= 10;
+    }
+    sum
+}
+
+/// Calculates the check digit shared by CUSIPs and FIGIs, which double the value of every second
+/// character and sum the digits of the results.
+fn modulus_10_double_add_double(base: &[u8]) -> Option<u8> {
+    let mut sum = 0;
+    for (i, &c) in base.iter().enumerate() {
+        let value = character_value(c)?;
+        sum += digit_sum(if i % 2 == 1 { value * 2 } else { value });
+    }
+    Some(((10 - sum % 10) % 10) as u8 + b'0')
+}
+
+/// Calculates the check digit of an ISIN, the Luhn check digit of the base with its letters
+/// expanded into two digits each.
+fn isin_check_digit(base: &[u8]) -> Option<u8> {
+    let mut digits = Vec::with_capacity(2 * base.len());
+    for &c in base {
+        let value = character_value(c).filter(|&v| v < 36)?;
+        if value >= 10 {
+            digits.push(value / 10);
+        }
+        digits.push(value % 10);
+    }
+    let sum = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 0 { digit_sum(2 * d) } else { d })
+        .sum::<u32>();
+    Some(((10 - sum % 10) % 10) as u8 + b'0')
+}
+
+/// Checks the shape of an identifier and returns its bytes.
+fn require_identifier<'a>(
+    kind: &str,
+    identifier: &'a str,
+    len: usize,
+) -> Result<&'a [u8], IndicatorError> {
+    let bytes = identifier.as_bytes();
+    if bytes.len() != len {
+        return Err(IndicatorError::InvalidInput(format!(
+            "{} `{}` must have {} characters",
+            kind, identifier, len
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Returns an error for an identifier whose check digit does not match its base.
+fn check_digit_error(kind: &str, identifier: &str) -> IndicatorError {
+    IndicatorError::InvalidInput(format!(
+        "{} `{}` has an invalid character or check digit",
+        kind, identifier
+    ))
+}
+
+/// An International Securities Identification Number, a two letter country code, a nine
+/// character national identifier, and a check digit, e.g. `US0378331005`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Isin(String);
+
+impl Isin {
+    /// Validates an ISIN.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `isin` is not 12 characters long, does not
+    /// start with two uppercase letters, contains characters other than digits and uppercase
+    /// letters, or has the wrong check digit.
+    pub fn new(isin: &str) -> Result<Isin, IndicatorError> {
+        let bytes = require_identifier("ISIN", isin, 12)?;
+        if !bytes[..2].iter().all(u8::is_ascii_uppercase)
+            || isin_check_digit(&bytes[..11]) != Some(bytes[11])
+        {
+            return Err(check_digit_error("ISIN", isin));
+        }
+        Ok(Isin(isin.to_string()))
+    }
+
+    /// Creates an ISIN from a country code and a national identifier, appending the check digit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `country` is not two uppercase letters or
+    /// `nsin` is not nine digits and uppercase letters.
+    pub fn from_parts(country: &str, nsin: &str) -> Result<Isin, IndicatorError> {
+        let base = format!("{}{}", country, nsin);
+        let bytes = require_identifier("ISIN base", &base, 11)?;
+        match isin_check_digit(bytes) {
+            Some(check) if country.len() == 2 => Isin::new(&format!("{}{}", base, check as char)),
+            _ => Err(check_digit_error("ISIN base", &base)),
+        }
+    }
+
+    /// Creates the ISIN of a security with a CUSIP in a CUSIP country, such as `US` or `CA`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `country` is not two uppercase letters or the
+    /// CUSIP contains one of the symbols `*`, `@`, or `#`, which ISINs do not allow.
+    pub fn from_cusip(country: &str, cusip: &Cusip) -> Result<Isin, IndicatorError> {
+        Isin::from_parts(country, cusip.as_str())
+    }
+
+    /// Returns the identifier as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the two letter country code.
+    pub fn country(&self) -> &str {
+        &self.0[..2]
+    }
+
+    /// Returns the nine character national identifier.
+    pub fn nsin(&self) -> &str {
+        &self.0[2..11]
+    }
+
+    /// Returns the CUSIP embedded in the ISIN of a US or Canadian security, or `None` for other
+    /// countries or a national identifier that is not a valid CUSIP.
+    pub fn to_cusip(&self) -> Option<Cusip> {
+        match self.country() {
+            "US" | "CA" => Cusip::new(self.nsin()).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Isin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A CUSIP, the nine character identifier of North American securities, e.g. `037833100`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cusip(String);
+
+impl Cusip {
+    /// Validates a CUSIP.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `cusip` is not 9 characters long, contains
+    /// characters other than digits, uppercase letters, `*`, `@`, and `#`, or has the wrong check
+    /// digit.
+    pub fn new(cusip: &str) -> Result<Cusip, IndicatorError> {
+        let bytes = require_identifier("CUSIP", cusip, 9)?;
+        if modulus_10_double_add_double(&bytes[..8]) != Some(bytes[8]) {
+            return Err(check_digit_error("CUSIP", cusip));
+        }
+        Ok(Cusip(cusip.to_string()))
+    }
+
+    /// Creates a CUSIP from its first eight characters, appending the check digit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `base` is not 8 characters long or contains
+    /// characters other than digits, uppercase letters, `*`, `@`, and `#`.
+    pub fn from_base(base: &str) -> Result<Cusip, IndicatorError> {
+        let bytes = require_identifier("CUSIP base", base, 8)?;
+        let check = modulus_10_double_add_double(bytes)
+            .ok_or_else(|| check_digit_error("CUSIP base", base))?;
+        Ok(Cusip(format!("{}{}", base, check as char)))
+    }
+
+    /// Returns the identifier as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the six character issuer code.
+    pub fn issuer(&self) -> &str {
+        &self.0[..6]
+    }
+
+    /// Returns the two character issue code.
+    pub fn issue(&self) -> &str {
+        &self.0[6..8]
+    }
+}
+
+impl fmt::Display for Cusip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Country code prefixes that FIGIs may not start with, to avoid confusion with ISINs.
+const FIGI_RESERVED_PREFIXES: [&[u8]; 7] = [b"BS", b"BM", b"GG", b"GB", b"GH", b"KY", b"VG"];
+
+/// A Financial Instrument Global Identifier, twelve consonants and digits whose third character
+/// is `G`, e.g. `BBG000BLNNH6`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Figi(String);
+
+impl Figi {
+    /// Validates a FIGI.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `figi` is not 12 characters long, contains
+    /// characters other than digits and uppercase consonants, does not have a `G` as its third
+    /// character, starts with a reserved prefix, or has the wrong check digit.
+    pub fn new(figi: &str) -> Result<Figi, IndicatorError> {
+        let bytes = require_identifier("FIGI", figi, 12)?;
+        let consonants = bytes[..11]
+            .iter()
+            .all(|c| c.is_ascii_digit() || (c.is_ascii_uppercase() && !b"AEIOU".contains(c)));
+        if !consonants
+            || bytes[2] != b'G'
+            || FIGI_RESERVED_PREFIXES.contains(&&bytes[..2])
+            || modulus_10_double_add_double(&bytes[..11]) != Some(bytes[11])
+        {
+            return Err(check_digit_error("FIGI", figi));
+        }
+        Ok(Figi(figi.to_string()))
+    }
+
+    /// Returns the identifier as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Figi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isin() {
+        let isin = Isin::new("US0378331005").unwrap();
+        assert_eq!(isin.country(), "US");
+        assert_eq!(isin.nsin(), "037833100");
+        assert_eq!(isin.to_cusip().unwrap().as_str(), "037833100");
+        assert_eq!(isin.to_string(), "US0378331005");
+        assert_eq!(
+            Isin::from_parts("GB", "000263494").unwrap().as_str(),
+            "GB0002634946"
+        );
+        assert_eq!(Isin::new("GB0002634946").unwrap().to_cusip(), None);
+        let cusip = Cusip::new("38259P508").unwrap();
+        assert_eq!(
+            Isin::from_cusip("US", &cusip).unwrap().as_str(),
+            "US38259P5089"
+        );
+
+        // Test case with invalid input
+        for isin in [
+            "US0378331006",
+            "US037833100",
+            "us0378331005",
+            "120378331005",
+        ] {
+            match Isin::new(isin) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cusip() {
+        let cusip = Cusip::new("037833100").unwrap();
+        assert_eq!(cusip.issuer(), "037833");
+        assert_eq!(cusip.issue(), "10");
+        assert_eq!(Cusip::from_base("38259P50").unwrap().as_str(), "38259P508");
+
+        // Test case with invalid input
+        for cusip in ["037833101", "03783310", "03783310!"] {
+            match Cusip::new(cusip) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_figi() {
+        assert_eq!(Figi::new("BBG000BLNNH6").unwrap().as_str(), "BBG000BLNNH6");
+        assert_eq!(
+            Figi::new("BBG000B9XRY4").unwrap().to_string(),
+            "BBG000B9XRY4"
+        );
+
+        // Test case with invalid input
+        for figi in [
+            "BBG000BLNNH7",
+            "BBA000BLNNH6",
+            "BBG000BLNNH",
+            "GBG000BLNNH6",
+        ] {
+            match Figi::new(figi) {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Construction of price-weighted, capitalization-weighted, and equal-weighted index series from
+//! their constituents.
+//!
+//! An index level is the value of a basket of constituent units divided by a divisor. Whenever
+//! the basket changes, through a membership change, a change of shares outstanding, or a
+//! rebalance, the divisor is adjusted so that the level is continuous across the change.
+
+use crate::IndicatorError;
+
+/// How the constituents of an index are weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    /// One unit of every member, like the Dow Jones Industrial Average
+    Price,
+    /// The shares outstanding of every member, like the S&P 500
+    MarketCap,
+    /// Equal values of every member, reset every `rebalance_every` bars and whenever the
+    /// membership changes, and drifting with the prices in between
+    Equal {
+        /// Number of bars between rebalances
+        rebalance_every: usize,
+    },
+}
+
+/// The history of one potential member of an index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constituent<'a> {
+    /// Price of every bar, which may be `NaN` on bars the constituent is not a member
+    pub prices: &'a [f64],
+    /// Shares outstanding of every bar, only used by [`Weighting::MarketCap`] and otherwise
+    /// allowed to be empty
+    pub shares: &'a [f64],
+    /// Whether the constituent is a member of the index from the close of every bar
+    pub members: &'a [bool],
+}
+
+/// An index series produced by [`build_index`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSeries {
+    /// Level of the index at every bar
+    pub levels: Vec<f64>,
+    /// Divisor in effect after the close of every bar
+    pub divisors: Vec<f64>,
+}
+
+/// Checks the constituents of an index and returns the number of bars.
+fn validate_constituents(
+    constituents: &[Constituent],
+    weighting: Weighting,
+) -> Result<usize, IndicatorError> {
+    let Some(bars) = constituents.first().map(|c| c.prices.len()) else {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one constituent is required".to_string(),
+        ));
+    };
+    if bars == 0 {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one bar is required".to_string(),
+        ));
+    }
+    let needs_shares = weighting == Weighting::MarketCap;
+    if constituents.iter().any(|c| {
+        c.prices.len() != bars
+            || c.members.len() != bars
+            || (needs_shares && c.shares.len() != bars)
+    }) {
+        return Err(IndicatorError::MismatchedLength(
+            "Every constituent must cover the same bars".to_string(),
+        ));
+    }
+    if weighting == (Weighting::Equal { rebalance_every: 0 }) {
+        return Err(IndicatorError::InvalidInput(
+            "`rebalance_every` must be positive".to_string(),
+        ));
+    }
+    Ok(bars)
+}
+
+/// Values a basket of units at the prices of a bar.
+fn basket_value(
+    constituents: &[Constituent],
+    units: &[f64],
+    bar: usize,
+) -> Result<f64, IndicatorError> {
+    let mut value = 0.0;
+    for (constituent, units) in constituents.iter().zip(units) {
+        if *units == 0.0 {
+            continue;
+        }
+        let price = constituent.prices[bar];
+        if !(price.is_finite() && price > 0.0) {
+            return Err(IndicatorError::InvalidInput(format!(
+                "Members must have positive prices, found {} at bar {}",
+                price, bar
+            )));
+        }
+        value += units * price;
+    }
+    Ok(value)
+}
+
+/// Builds an index series from its constituents with divisor maintenance.
+///
+/// The basket of the first bar is valued at `base_level`. On every following bar the level is the
+/// value of the previous basket at the bar's prices divided by the divisor, after which the basket
+/// is updated for membership, share, and rebalancing changes and the divisor is adjusted to keep
+/// the level unchanged. Members leaving the index must therefore still have a price on the bar
+/// they leave.
+///
+/// # Arguments
+///
+/// * `constituents` - The histories of all constituents that are members at some bar.
+/// * `weighting` - How the members are weighted.
+/// * `base_level` - The level of the index at the first bar, e.g. 100.
+///
+/// # Returns
+///
+/// A Result containing the [`IndexSeries`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if there are no constituents or bars, an
+/// `IndicatorError::MismatchedLength` if the constituents cover different numbers of bars, and an
+/// `IndicatorError::InvalidInput` if `base_level` is not positive and finite, a bar has no
+/// members, a member has no positive price or shares, or `rebalance_every` is zero.
+pub fn build_index(
+    constituents: &[Constituent],
+    weighting: Weighting,
+    base_level: f64,
+) -> Result<IndexSeries, IndicatorError> {
+    let bars = validate_constituents(constituents, weighting)?;
+    if !(base_level.is_finite() && base_level > 0.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`base_level` must be positive and finite".to_string(),
+        ));
+    }
+
+    let mut units = vec![0.0; constituents.len()];
+    let mut divisor = f64::NAN;
+    let mut result = IndexSeries {
+        levels: Vec::with_capacity(bars),
+        divisors: Vec::with_capacity(bars),
+    };
+    for bar in 0..bars {
+        let level = if bar == 0 {
+            base_level
+        } else {
+            basket_value(constituents, &units, bar)? / divisor
+        };
+
+        let membership_changed = bar == 0
+            || constituents
+                .iter()
+                .any(|c| c.members[bar] != c.members[bar - 1]);
+        for (i, (constituent, units)) in constituents.iter().zip(units.iter_mut()).enumerate() {
+            if !constituent.members[bar] {
+                *units = 0.0;
+                continue;
+            }
+            match weighting {
+                Weighting::Price => *units = 1.0,
+                Weighting::MarketCap => *units = constituent.shares[bar],
+                Weighting::Equal { rebalance_every } => {
+                    if membership_changed || bar % rebalance_every == 0 {
+                        *units = 1.0 / constituent.prices[bar];
+                    }
+                }
+            }
+            if !(units.is_finite() && *units > 0.0) {
+                return Err(IndicatorError::InvalidInput(format!(
+                    "Member {} has no positive price or shares at bar {}",
+                    i, bar
+                )));
+            }
+        }
+        let value = basket_value(constituents, &units, bar)?;
+        if value == 0.0 {
+            return Err(IndicatorError::InvalidInput(format!(
+                "The index has no members at bar {}",
+                bar
+            )));
+        }
+
+        divisor = value / level;
+        result.levels.push(level);
+        result.divisors.push(divisor);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_index() {
+        // Price weighting with a member joining at the second bar
+        let a = [10.0, 11.0, 12.0];
+        let b = [20.0, 22.0, 20.0];
+        let c = [f64::NAN, 30.0, 33.0];
+        let constituents = [
+            Constituent {
+                prices: &a,
+                shares: &[],
+                members: &[true; 3],
+            },
+            Constituent {
+                prices: &b,
+                shares: &[],
+                members: &[true; 3],
+            },
+            Constituent {
+                prices: &c,
+                shares: &[],
+                members: &[false, true, true],
+            },
+        ];
+        let result = build_index(&constituents, Weighting::Price, 100.0).unwrap();
+        let expected = [100.0, 110.0, 65.0 * 110.0 / 63.0];
+        for (level, expected) in result.levels.iter().zip(&expected) {
+            assert!((level - expected).abs() < 1e-9);
+        }
+        assert!((result.divisors[0] - 0.3).abs() < 1e-12);
+        assert!((result.divisors[1] - 63.0 / 110.0).abs() < 1e-12);
+
+        // Capitalization weighting with a share issuance that does not move the level
+        let constituents = [
+            Constituent {
+                prices: &a,
+                shares: &[100.0, 100.0, 200.0],
+                members: &[true; 3],
+            },
+            Constituent {
+                prices: &b,
+                shares: &[50.0; 3],
+                members: &[true; 3],
+            },
+        ];
+        let result = build_index(&constituents, Weighting::MarketCap, 100.0).unwrap();
+        for (level, expected) in result.levels.iter().zip([100.0, 110.0, 110.0]) {
+            assert!((level - expected).abs() < 1e-9);
+        }
+        assert!((result.divisors[2] - 3_400.0 / 110.0).abs() < 1e-9);
+
+        // Equal weighting rebalanced every other bar
+        let constituents = [
+            Constituent {
+                prices: &[10.0, 20.0, 20.0, 10.0],
+                shares: &[],
+                members: &[true; 4],
+            },
+            Constituent {
+                prices: &[10.0; 4],
+                shares: &[],
+                members: &[true; 4],
+            },
+        ];
+        let weighting = Weighting::Equal { rebalance_every: 2 };
+        let result = build_index(&constituents, weighting, 100.0).unwrap();
+        for (level, expected) in result.levels.iter().zip([100.0, 150.0, 150.0, 112.5]) {
+            assert!((level - expected).abs() < 1e-9);
+        }
+
+        // Test case with mismatched lengths
+        let result = build_index(&constituents[..1], Weighting::MarketCap, 100.0);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let empty = [Constituent {
+            prices: &[10.0, 11.0],
+            shares: &[],
+            members: &[true, false],
+        }];
+        let result = build_index(&empty, Weighting::Price, 100.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid base levels
+        for base_level in [0.0, -100.0, f64::NAN, f64::INFINITY] {
+            let result = build_index(&constituents, Weighting::Price, base_level);
+            match result {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+}
@@ -1,3 +1,43 @@
+pub mod alerts;
+pub mod bonds;
+mod calendar;
+pub mod candle;
+pub mod chart;
+pub mod clock;
+pub mod corporate_actions;
+pub mod events;
+pub mod excel;
+pub mod execution;
+pub mod factors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod formatting;
+pub mod futures;
+pub mod fx;
+pub mod identifiers;
+pub mod index;
+mod json;
+mod linalg;
+pub mod margin;
+pub mod mortgage;
+pub mod options;
+pub mod orderbook;
+pub mod perpetual;
+pub mod portfolio;
+pub mod regression;
+pub mod replay;
+pub mod resample;
+pub mod risk;
+pub mod scenario;
+pub mod series;
+pub mod signals;
+mod special;
+pub mod stats;
+pub mod storage;
+pub mod streaming;
+pub mod validation;
+pub mod volatility;
+
 /// Error type for equity indicators
 #[non_exhaustive]
 #[derive(Debug)]
@@ -5,6 +45,15 @@ pub enum IndicatorError {
     /// Indicates that not enough data points were provided to an indicator function to satisfy the
     /// given window
     NotEnoughData(String),
+    /// Indicates that parallel input slices, such as returns and factor returns, do not have the
+    /// same length
+    MismatchedLength(String),
+    /// Indicates that a system of equations could not be solved because its matrix is singular,
+    /// for example when regression factors are perfectly collinear
+    SingularMatrix(String),
+    /// Indicates that the input data violates a documented precondition, for example timestamps
+    /// that are not sorted
+    InvalidInput(String),
 }
 
 impl std::fmt::Display for IndicatorError {
@@ -28,26 +77,33 @@ impl std::error::Error for IndicatorError {}
 ///
 /// # Errors
 ///
-/// Returns an `IndicatorError::NotEnoughData` if the length of `prices` is less than or equal to
-/// `window`.
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than or equal to `window`.
 pub fn calculate_rsi(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
     // Check if prices array has enough elements
-    if prices.len() <= window {
-        return Err(IndicatorError::NotEnoughData(
-            "Not enough data points to calculate RSI".to_string(),
-        ));
-    }
+    require_lookback(prices, window)?;
 
     // Calculate price changes
-    let price_changes = prices[1..].iter().zip(prices.iter()).map(|(x, y)| x - y);
+    let price_changes = prices[1..]
+        .iter()
+        .zip(prices.iter())
+        .map(|(x, y)| x - y)
+        .collect::<Vec<f64>>();
+
+    Ok(rsi_from_changes(&price_changes, window))
+}
 
+/// Calculates the RSI from a series of price changes, which must have at least `window` items,
+/// with a positive `window`.
+fn rsi_from_changes(price_changes: &[f64], window: usize) -> Vec<f64> {
     // Separate gains and losses
     let gains: Vec<f64> = price_changes
-        .clone()
-        .map(|x| if x > 0.0 { x } else { 0.0 })
+        .iter()
+        .map(|x| if *x > 0.0 { *x } else { 0.0 })
         .collect();
     let losses: Vec<f64> = price_changes
-        .map(|x| if x < 0.0 { -x } else { 0.0 })
+        .iter()
+        .map(|x| if *x < 0.0 { -x } else { 0.0 })
         .collect();
 
     // Calculate average gains and losses over the window
@@ -55,8 +111,8 @@ pub fn calculate_rsi(prices: &[f64], window: usize) -> Result<Vec<f64>, Indicato
     let mut avg_loss = losses.iter().take(window).sum::<f64>() / window as f64;
 
     // Calculate RSI for each element in the specified window to the end
-    let mut rsi_values = Vec::with_capacity(prices.len() - window);
-    for i in window..prices.len() {
+    let mut rsi_values = Vec::with_capacity(price_changes.len() + 1 - window);
+    for i in window..=price_changes.len() {
         let current_gain = gains[i - 1];
         let current_loss = losses[i - 1];
 
@@ -75,11 +131,16 @@ pub fn calculate_rsi(prices: &[f64], window: usize) -> Result<Vec<f64>, Indicato
         rsi_values.push(rsi);
     }
 
-    Ok(rsi_values)
+    rsi_values
 }
 
 /// Calculates the Exponential Moving Average (EMA) for a given price array and window size.
 ///
+/// The EMA is updated with the recurrence `ema += (price - ema) * smoothing`, which scales the
+/// rounding error of every earlier update by `1 - smoothing`. Errors therefore decay instead of
+/// accumulating, and the EMA stays accurate over arbitrarily long series and windows. Rolling
+/// sums do accumulate errors, see [`stats::SumMode`] for ways to control their drift.
+///
 /// # Arguments
 ///
 /// * `prices` - A slice of price data.
@@ -91,18 +152,15 @@ pub fn calculate_rsi(prices: &[f64], window: usize) -> Result<Vec<f64>, Indicato
 ///
 /// # Errors
 ///
-/// Returns an `IndicatorError::NotEnoughData` if the length of `prices` is less than `window`.
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`.
 pub fn calculate_ema(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
-    if prices.len() < window {
-        return Err(IndicatorError::NotEnoughData(
-            "`prices` must have at least `window` items".to_string(),
-        ));
-    }
+    require_window(prices, window)?;
 
     let smoothing = 2.0 / (window as f64 + 1.0);
 
     let sma = prices.iter().take(window).sum::<f64>() / window as f64;
-    let mut ema_values = Vec::with_capacity(prices.len() - window);
+    let mut ema_values = Vec::with_capacity(prices.len() - window + 1);
     ema_values.push(sma);
 
     for i in window..prices.len() {
@@ -133,26 +191,68 @@ pub fn calculate_ema(prices: &[f64], window: usize) -> Result<Vec<f64>, Indicato
 ///
 /// Returns an `IndicatorError::NotEnoughData` if the length of `prices` is insufficient to
 /// calculate any of the moving averages for the `short_window`, `long_window`, or the `signal_window`.
+#[allow(clippy::type_complexity)]
 pub fn calculate_macd(
     prices: &[f64],
     short_window: usize,
     long_window: usize,
     signal_window: usize,
 ) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>), IndicatorError> {
-    let mut ema_short = calculate_ema(prices, short_window)?;
-    let ema_long = calculate_ema(prices, long_window)?;
-    ema_short = ema_short[long_window - short_window..].to_owned();
+    calculate_macd_with(
+        prices,
+        short_window,
+        long_window,
+        signal_window,
+        MovingAverageKind::Ema,
+    )
+}
+
+/// Calculates the Moving Average Convergence Divergence (MACD) using the given type of moving
+/// average for both legs and the signal line.
+///
+/// [`calculate_macd`] is equivalent to this function with [`MovingAverageKind::Ema`]. Other kinds
+/// match platforms that build MACD from, for example, simple moving averages.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `short_window` - The size of the short-term moving average window.
+/// * `long_window` - The size of the long-term moving average window.
+/// * `signal_window` - The size of the signal line window.
+/// * `kind` - The type of moving average to use.
+///
+/// # Returns
+///
+/// A Result containing a tuple of MACD line, signal line, and histogram or an `IndicatorError` if
+/// there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if the length of `prices` is insufficient to
+/// calculate any of the moving averages for the `short_window`, `long_window`, or the
+/// `signal_window`.
+#[allow(clippy::type_complexity)]
+pub fn calculate_macd_with(
+    prices: &[f64],
+    short_window: usize,
+    long_window: usize,
+    signal_window: usize,
+    kind: MovingAverageKind,
+) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>), IndicatorError> {
+    let ma_short = calculate_moving_average(prices, short_window, kind)?;
+    let ma_long = calculate_moving_average(prices, long_window, kind)?;
 
-    let mut macd_line = ema_short
+    // Both legs end at the last price, so align them on their most recent values
+    let len = ma_short.len().min(ma_long.len());
+    let mut macd_line = ma_short[ma_short.len() - len..]
         .iter()
-        .zip(&ema_long)
+        .zip(&ma_long[ma_long.len() - len..])
         .map(|(a, b)| a - b)
         .collect::<Vec<f64>>();
-    let signal_line = calculate_ema(&macd_line, signal_window)?;
+    let signal_line = calculate_moving_average(&macd_line, signal_window, kind)?;
     macd_line = macd_line[macd_line.len() - signal_line.len()..].to_owned();
 
     let histogram = macd_line
-        .clone()
         .iter()
         .zip(&signal_line)
         .map(|(a, b)| a - b)
@@ -160,74 +260,3887 @@ pub fn calculate_macd(
     Ok((macd_line, signal_line, histogram))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The lines of TRIX, produced by [`calculate_trix`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trix {
+    /// Percent change of the triple smoothed EMA from the previous bar
+    pub trix: Vec<f64>,
+    /// EMA of the TRIX line, if a signal window was given
+    pub signal: Option<Vec<f64>>,
+}
 
-    #[test]
-    fn test_calculate_rsi() {
-        // Test case with enough data
-        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-        let window = 3;
-        let result = calculate_rsi(prices.as_slice(), window).unwrap();
-        assert_eq!(result, vec![100.0, 100.0]);
+/// Calculates TRIX, the one bar rate of change of an EMA of an EMA of an EMA of the prices, with
+/// an optional signal line.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window of each of the three EMAs, typically 15.
+/// * `signal_window` - The size of the window of the signal line EMA, typically 9, or `None` for
+///   no signal line.
+///
+/// # Returns
+///
+/// A Result containing the [`Trix`] lines or an `IndicatorError`. The lines end at the last
+/// price, and with a signal line the TRIX line is trimmed to the length of the signal line.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if a window is zero or there are too few prices for
+/// the three EMAs, one change, and the signal line.
+pub fn calculate_trix(
+    prices: &[f64],
+    window: usize,
+    signal_window: Option<usize>,
+) -> Result<Trix, IndicatorError> {
+    let triple = &calculate_nested_emas(prices, window, 3)?[2];
+    if triple.len() < 2 {
+        return Err(IndicatorError::NotEnoughData(
+            "TRIX needs at least two values of the triple smoothed EMA".to_string(),
+        ));
+    }
 
-        // Test case with not enough data
-        let prices = vec![1.0, 2.0];
-        let window = 3;
-        let result = calculate_rsi(prices.as_slice(), window);
-        assert!(result.is_err());
-        match result {
-            Err(IndicatorError::NotEnoughData(_)) => (),
-            _ => {
-                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
-            }
+    let trix = triple
+        .windows(2)
+        .map(|w| 100.0 * (w[1] / w[0] - 1.0))
+        .collect::<Vec<f64>>();
+    match signal_window {
+        Some(signal_window) => {
+            let signal = calculate_ema(&trix, signal_window)?;
+            Ok(Trix {
+                trix: trix[trix.len() - signal.len()..].to_vec(),
+                signal: Some(signal),
+            })
         }
+        None => Ok(Trix { trix, signal: None }),
     }
+}
 
-    #[test]
-    fn test_calculate_ema() {
-        // Test case with enough data
-        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-        let window = 3;
-        let result = calculate_ema(prices.as_slice(), window).unwrap();
-        assert_eq!(result, vec![2.0, 3.0, 4.0]);
+/// The lines of a median price channel, produced by [`calculate_median_channel`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MedianChannel {
+    /// Rolling median of the high prices
+    pub upper: Vec<f64>,
+    /// Rolling median of the closing prices
+    pub middle: Vec<f64>,
+    /// Rolling median of the low prices
+    pub lower: Vec<f64>,
+}
 
-        // Test case with not enough data
-        let prices = vec![1.0, 2.0];
-        let window = 3;
-        let result = calculate_ema(prices.as_slice(), window);
-        assert!(result.is_err());
-        match result {
-            Err(IndicatorError::NotEnoughData(_)) => (),
-            _ => {
-                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
-            }
-        }
+/// Calculates a median price channel, a robust alternative to a Donchian channel that is not
+/// moved by a single extreme bar.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The number of bars in each window.
+///
+/// # Returns
+///
+/// A Result containing the [`MedianChannel`] or an `IndicatorError`. The value at index `i` of
+/// each line covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and the
+/// errors of [`stats::calculate_rolling_quantile`] otherwise.
+pub fn calculate_median_channel(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+) -> Result<MedianChannel, IndicatorError> {
+    require_hlc(high, low, close)?;
+
+    Ok(MedianChannel {
+        upper: stats::calculate_rolling_quantile(high, window, 0.5)?,
+        middle: stats::calculate_rolling_quantile(close, window, 0.5)?,
+        lower: stats::calculate_rolling_quantile(low, window, 0.5)?,
+    })
+}
+
+/// Quantile based bands around a rolling median, produced by [`calculate_percentile_bands`] and
+/// [`calculate_iqr_bands`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantileBands {
+    /// Upper band
+    pub upper: Vec<f64>,
+    /// Rolling median of the prices
+    pub middle: Vec<f64>,
+    /// Lower band
+    pub lower: Vec<f64>,
+}
+
+/// Calculates percentile channel bands, the `lower_q` and `upper_q` quantiles of the latest
+/// `window` prices around their median.
+///
+/// Unlike standard deviation bands, percentile bands make no assumption about the distribution of
+/// prices and are not widened by a few extreme moves, which suits fat-tailed assets.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The number of prices in each window.
+/// * `lower_q` - The quantile of the lower band, e.g. `0.05`.
+/// * `upper_q` - The quantile of the upper band, e.g. `0.95`.
+///
+/// # Returns
+///
+/// A Result containing the [`QuantileBands`] or an `IndicatorError`. The value at index `i` of
+/// each band covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `lower_q` is greater than `upper_q`, and the
+/// errors of [`stats::calculate_rolling_quantile`] otherwise.
+pub fn calculate_percentile_bands(
+    prices: &[f64],
+    window: usize,
+    lower_q: f64,
+    upper_q: f64,
+) -> Result<QuantileBands, IndicatorError> {
+    if lower_q > upper_q {
+        return Err(IndicatorError::InvalidInput(
+            "`lower_q` must not be greater than `upper_q`".to_string(),
+        ));
     }
 
-    #[test]
-    fn test_calculate_macd() {
-        // Test case with enough data
-        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-        let short_window = 2;
-        let long_window = 4;
-        let signal_window = 2;
-        let result =
-            calculate_macd(prices.as_slice(), short_window, long_window, signal_window).unwrap();
-        assert_eq!(result, (vec![1.0], vec![1.0], vec![0.0]));
+    Ok(QuantileBands {
+        upper: stats::calculate_rolling_quantile(prices, window, upper_q)?,
+        middle: stats::calculate_rolling_quantile(prices, window, 0.5)?,
+        lower: stats::calculate_rolling_quantile(prices, window, lower_q)?,
+    })
+}
 
-        // Test case with not enough data
-        let prices = vec![1.0, 2.0];
-        let short_window = 2;
-        let long_window = 4;
-        let signal_window = 2;
-        let result = calculate_macd(prices.as_slice(), short_window, long_window, signal_window);
-        assert!(result.is_err());
-        match result {
-            Err(IndicatorError::NotEnoughData(_)) => (),
-            _ => {
-                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+/// Calculates interquartile range outlier bands, Tukey's fences computed over a rolling window.
+///
+/// The bands are `Q1 - multiplier * IQR` and `Q3 + multiplier * IQR`, where `Q1` and `Q3` are the
+/// first and third quartiles of the latest `window` prices and `IQR = Q3 - Q1`. Prices outside
+/// the bands are outliers; a `multiplier` of 1.5 is the conventional choice.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The number of prices in each window.
+/// * `multiplier` - The number of interquartile ranges between the quartiles and the bands.
+///
+/// # Returns
+///
+/// A Result containing the [`QuantileBands`] or an `IndicatorError`. The value at index `i` of
+/// each band covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `multiplier` is negative, and the errors of
+/// [`stats::calculate_rolling_quantile`] otherwise.
+pub fn calculate_iqr_bands(
+    prices: &[f64],
+    window: usize,
+    multiplier: f64,
+) -> Result<QuantileBands, IndicatorError> {
+    if multiplier.is_nan() || multiplier < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`multiplier` must not be negative".to_string(),
+        ));
+    }
+
+    let q1 = stats::calculate_rolling_quantile(prices, window, 0.25)?;
+    let q3 = stats::calculate_rolling_quantile(prices, window, 0.75)?;
+    let fence = |(q3, q1): (&f64, &f64)| multiplier * (q3 - q1);
+    Ok(QuantileBands {
+        upper: q3.iter().zip(&q1).map(|q| q.0 + fence(q)).collect(),
+        middle: stats::calculate_rolling_quantile(prices, window, 0.5)?,
+        lower: q3.iter().zip(&q1).map(|q| q.1 - fence(q)).collect(),
+    })
+}
+
+/// Calculates the percent rank of every price, the percentage of the previous `window` prices
+/// that are less than or equal to it.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The number of previous prices each price is ranked against.
+///
+/// # Returns
+///
+/// A Result containing a vector of percent ranks between 0 and 100 or an `IndicatorError`. The
+/// value at index `i` ranks the price at index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than or equal to `window`.
+pub fn calculate_percent_rank(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_lookback(prices, window)?;
+
+    Ok(prices
+        .windows(window + 1)
+        .map(|w| {
+            let current = w[window];
+            let below = w[..window].iter().filter(|p| **p <= current).count();
+            100.0 * below as f64 / window as f64
+        })
+        .collect())
+}
+
+/// Checks that an indicator comparing prices with the price `window` bars earlier has a positive
+/// window and more prices than the window.
+fn require_lookback(prices: &[f64], window: usize) -> Result<(), IndicatorError> {
+    if window == 0 || prices.len() <= window {
+        return Err(IndicatorError::NotEnoughData(
+            "`prices` must have more than `window` items and `window` must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates the Rate of Change (ROC), the percent change of every price from the price `window`
+/// bars earlier.
+///
+/// A previous price of zero gives an infinite or `NaN` rate of change.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The number of bars to look back.
+///
+/// # Returns
+///
+/// A Result containing a vector of ROC values in percent or an `IndicatorError`. The value at
+/// index `i` is the change from index `i` to index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than or equal to `window`.
+pub fn calculate_roc(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_lookback(prices, window)?;
+
+    Ok(prices
+        .iter()
+        .zip(&prices[window..])
+        .map(|(previous, current)| 100.0 * (current / previous - 1.0))
+        .collect())
+}
+
+/// Calculates the momentum of every price, its difference from the price `window` bars earlier.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The number of bars to look back.
+///
+/// # Returns
+///
+/// A Result containing a vector of momentum values or an `IndicatorError`. The value at index `i`
+/// is the change from index `i` to index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than or equal to `window`.
+pub fn calculate_momentum(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_lookback(prices, window)?;
+
+    Ok(prices
+        .iter()
+        .zip(&prices[window..])
+        .map(|(previous, current)| current - previous)
+        .collect())
+}
+
+/// Checks that a moving average has a positive window and enough prices to fill it.
+fn require_window(prices: &[f64], window: usize) -> Result<(), IndicatorError> {
+    if window == 0 || prices.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "`prices` must have at least `window` items and `window` must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Averages every window of prices with `weights`, the last weight applying to the newest price.
+fn weighted_average(prices: &[f64], weights: &[f64]) -> Vec<f64> {
+    let total = weights.iter().sum::<f64>();
+    prices
+        .windows(weights.len())
+        .map(|w| w.iter().zip(weights).map(|(p, w)| p * w).sum::<f64>() / total)
+        .collect()
+}
+
+/// Calculates the Simple Moving Average (SMA), the mean of the prices of each window.
+///
+/// The means are updated with a running sum, see [`stats::calculate_rolling_mean`] for sums that
+/// stay accurate over very long series.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating SMA.
+///
+/// # Returns
+///
+/// A Result containing a vector of SMA values or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`.
+pub fn calculate_sma(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    stats::calculate_rolling_mean(prices, window, stats::SumMode::Fast)
+}
+
+/// Calculates the Weighted Moving Average (WMA), which weights the prices of each window linearly
+/// from 1 for the oldest to `window` for the newest.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating WMA.
+///
+/// # Returns
+///
+/// A Result containing a vector of WMA values or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`.
+pub fn calculate_wma(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    let weights = (1..=window).map(|w| w as f64).collect::<Vec<f64>>();
+    Ok(weighted_average(prices, &weights))
+}
+
+/// Calculates the Triangular Moving Average (TMA), a simple moving average of a simple moving
+/// average that weights the middle of each window most heavily.
+///
+/// The inner average spans `ceil(window / 2)` prices and the outer one `floor(window / 2) + 1`
+/// values, so together they span exactly `window` prices.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating TMA.
+///
+/// # Returns
+///
+/// A Result containing a vector of TMA values or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`.
+pub fn calculate_tma(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    let inner = calculate_sma(prices, window.div_ceil(2))?;
+    calculate_sma(&inner, window / 2 + 1)
+}
+
+/// Calculates the Sine Weighted Moving Average (SWMA), which weights the `i`-th price of each
+/// window by `sin(i * pi / (window + 1))`, emphasizing the middle of the window with a smooth
+/// bell shaped profile.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating SWMA.
+///
+/// # Returns
+///
+/// A Result containing a vector of SWMA values or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`.
+pub fn calculate_swma(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    let weights = (1..=window)
+        .map(|i| (i as f64 * std::f64::consts::PI / (window as f64 + 1.0)).sin())
+        .collect::<Vec<f64>>();
+    Ok(weighted_average(prices, &weights))
+}
+
+/// Calculates Wilder's Running Moving Average (RMA), an exponential moving average with a
+/// smoothing factor of `1 / window` seeded with the simple moving average of the first window.
+///
+/// This is the smoothing used by the RSI and the Average True Range.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating RMA.
+///
+/// # Returns
+///
+/// A Result containing a vector of RMA values or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`.
+pub fn calculate_rma(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    let mut rma = prices[..window].iter().sum::<f64>() / window as f64;
+    let mut rma_values = Vec::with_capacity(prices.len() - window + 1);
+    rma_values.push(rma);
+    for price in &prices[window..] {
+        rma += (price - rma) / window as f64;
+        rma_values.push(rma);
+    }
+
+    Ok(rma_values)
+}
+
+/// Calculates the Hull Moving Average (HMA), a weighted moving average of `2 * WMA(window / 2) -
+/// WMA(window)` over `sqrt(window)` values, which follows prices with little lag.
+///
+/// The final smoothing needs `floor(sqrt(window)) - 1` values beyond the first window, so the HMA
+/// starts that many prices later than the other moving averages.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating HMA.
+///
+/// # Returns
+///
+/// A Result containing a vector of HMA values or an `IndicatorError` if there is not enough data.
+/// The value at index `i` covers the prices ending at index `i + window + floor(sqrt(window)) -
+/// 2`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window + floor(sqrt(window)) - 1`.
+pub fn calculate_hma(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    let half = calculate_wma(prices, (window / 2).max(1))?;
+    let full = calculate_wma(prices, window)?;
+    let raw = half[half.len() - full.len()..]
+        .iter()
+        .zip(&full)
+        .map(|(h, f)| 2.0 * h - f)
+        .collect::<Vec<f64>>();
+    calculate_wma(&raw, ((window as f64).sqrt() as usize).max(1))
+}
+
+/// Moves a KAMA value toward the last of `prices`, the trailing `window + 1` prices, by the
+/// smoothing constant of their efficiency ratio.
+pub(crate) fn kama_step(kama: f64, prices: &[f64], fast: usize, slow: usize) -> f64 {
+    let price = prices[prices.len() - 1];
+    let direction = (price - prices[0]).abs();
+    let volatility = prices.windows(2).map(|p| (p[1] - p[0]).abs()).sum::<f64>();
+    let efficiency = if volatility > 0.0 {
+        direction / volatility
+    } else {
+        0.0
+    };
+    let fast = 2.0 / (fast as f64 + 1.0);
+    let slow = 2.0 / (slow as f64 + 1.0);
+    let smoothing = (efficiency * (fast - slow) + slow).powi(2);
+    kama + smoothing * (price - kama)
+}
+
+/// Calculates Kaufman's Adaptive Moving Average (KAMA), an exponential moving average whose
+/// smoothing follows the efficiency ratio of the last `window` price changes, the net change
+/// over the sum of absolute changes.
+///
+/// The smoothing constant moves between those of EMAs over `slow` prices, when prices go nowhere,
+/// and `fast` prices, when they trend without retracing, and is squared to damp noise further. The
+/// KAMA is seeded with the price at index `window - 1`.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The number of price changes in the efficiency ratio, commonly 10.
+/// * `fast` - The window of the fastest smoothing, commonly 2.
+/// * `slow` - The window of the slowest smoothing, commonly 30.
+///
+/// # Returns
+///
+/// A Result containing a vector of KAMA values or an `IndicatorError`. The value at index `i`
+/// covers the prices ending at index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// not greater than `window`, and an `IndicatorError::InvalidInput` if `fast` is zero or greater
+/// than `slow`.
+pub fn calculate_kama(
+    prices: &[f64],
+    window: usize,
+    fast: usize,
+    slow: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_lookback(prices, window)?;
+    if fast == 0 || fast > slow {
+        return Err(IndicatorError::InvalidInput(
+            "`fast` must be positive and not greater than `slow`".to_string(),
+        ));
+    }
+
+    let mut kama = prices[window - 1];
+    Ok(prices
+        .windows(window + 1)
+        .map(|w| {
+            kama = kama_step(kama, w, fast, slow);
+            kama
+        })
+        .collect())
+}
+
+/// Calculates `depth` EMAs over `window` prices, each smoothing the previous one, so the first is
+/// the EMA of `prices`, trimmed to the final EMA so that they are aligned.
+fn calculate_nested_emas(
+    prices: &[f64],
+    window: usize,
+    depth: usize,
+) -> Result<Vec<Vec<f64>>, IndicatorError> {
+    let mut emas: Vec<Vec<f64>> = Vec::with_capacity(depth);
+    for _ in 0..depth {
+        let ema = calculate_ema(emas.last().map_or(prices, |e| e.as_slice()), window)?;
+        emas.push(ema);
+    }
+    let len = emas.last().map_or(0, Vec::len);
+    Ok(emas
+        .into_iter()
+        .map(|e| e[e.len() - len..].to_vec())
+        .collect())
+}
+
+/// Calculates the Double Exponential Moving Average (DEMA), `2 * EMA - EMA(EMA)`, which cancels
+/// most of the lag of the EMA.
+///
+/// Both EMAs are seeded with the SMA of their first window, so the DEMA starts `window - 1`
+/// prices later than the EMA and its first value covers the prices up to index
+/// `2 * window - 2`.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window of both EMAs.
+///
+/// # Returns
+///
+/// A Result containing a vector of DEMA values or an `IndicatorError` if there is not enough data.
+/// The value at index `i` covers the prices ending at index `i + 2 * window - 2`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `2 * window - 1`.
+pub fn calculate_dema(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    let emas = calculate_nested_emas(prices, window, 2)?;
+    Ok(emas[0]
+        .iter()
+        .zip(&emas[1])
+        .map(|(ema, ema_of_ema)| 2.0 * ema - ema_of_ema)
+        .collect())
+}
+
+/// Calculates the Triple Exponential Moving Average (TEMA), `3 * EMA - 3 * EMA(EMA) +
+/// EMA(EMA(EMA))`, which cancels even more of the lag of the EMA than the DEMA.
+///
+/// Every EMA is seeded with the SMA of its first window, so the TEMA has a lookback of
+/// `3 * (window - 1)` prices: its first value covers the prices up to index `3 * window - 3` and
+/// it has `prices.len() - 3 * window + 3` values.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window of all three EMAs.
+///
+/// # Returns
+///
+/// A Result containing a vector of TEMA values or an `IndicatorError` if there is not enough data.
+/// The value at index `i` covers the prices ending at index `i + 3 * window - 3`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `3 * window - 2`.
+pub fn calculate_tema(prices: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    let emas = calculate_nested_emas(prices, window, 3)?;
+    Ok((0..emas[2].len())
+        .map(|i| 3.0 * emas[0][i] - 3.0 * emas[1][i] + emas[2][i])
+        .collect())
+}
+
+/// Type of moving average used by [`calculate_moving_average`] and the indicators built on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovingAverageKind {
+    /// Simple moving average, the equally weighted mean of the window
+    Sma,
+    /// Exponential moving average, see [`calculate_ema`]
+    Ema,
+    /// Linearly weighted moving average, see [`calculate_wma`]
+    Wma,
+    /// Triangular moving average, see [`calculate_tma`]
+    Tma,
+    /// Sine weighted moving average, see [`calculate_swma`]
+    Swma,
+    /// Wilder's running moving average, see [`calculate_rma`]
+    Rma,
+    /// Hull moving average, see [`calculate_hma`]
+    Hma,
+    /// Double exponential moving average, see [`calculate_dema`]
+    Dema,
+    /// Triple exponential moving average, see [`calculate_tema`]
+    Tema,
+}
+
+/// Calculates a moving average of the given kind.
+///
+/// Every kind except [`MovingAverageKind::Hma`], [`MovingAverageKind::Dema`], and
+/// [`MovingAverageKind::Tema`] returns one value per complete window, so the output has
+/// `prices.len() - window + 1` values and the value at index `i` covers the window ending at index
+/// `i + window - 1`. The HMA, DEMA, and TEMA start later, see [`calculate_hma`],
+/// [`calculate_dema`], and [`calculate_tema`].
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating the moving average.
+/// * `kind` - The type of moving average to calculate.
+///
+/// # Returns
+///
+/// A Result containing a vector of moving average values or an `IndicatorError` if there is not
+/// enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`, or too short for an HMA, DEMA, or TEMA.
+pub fn calculate_moving_average(
+    prices: &[f64],
+    window: usize,
+    kind: MovingAverageKind,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_window(prices, window)?;
+
+    match kind {
+        MovingAverageKind::Sma => calculate_sma(prices, window),
+        MovingAverageKind::Ema => calculate_ema(prices, window),
+        MovingAverageKind::Wma => calculate_wma(prices, window),
+        MovingAverageKind::Tma => calculate_tma(prices, window),
+        MovingAverageKind::Swma => calculate_swma(prices, window),
+        MovingAverageKind::Rma => calculate_rma(prices, window),
+        MovingAverageKind::Hma => calculate_hma(prices, window),
+        MovingAverageKind::Dema => calculate_dema(prices, window),
+        MovingAverageKind::Tema => calculate_tema(prices, window),
+    }
+}
+
+/// An indicator of a single price series and its parameters, for pipelines that choose the
+/// indicator at runtime such as [`series::Series::indicator_on_timeframe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndicatorSpec {
+    /// A moving average, see [`calculate_moving_average`]
+    MovingAverage {
+        /// Type of moving average
+        kind: MovingAverageKind,
+        /// Size of the window
+        window: usize,
+    },
+    /// Relative Strength Index, see [`calculate_rsi`]
+    Rsi {
+        /// Size of the window
+        window: usize,
+    },
+    /// Rate of Change, see [`calculate_roc`]
+    Roc {
+        /// Number of bars to look back
+        window: usize,
+    },
+    /// Momentum, see [`calculate_momentum`]
+    Momentum {
+        /// Number of bars to look back
+        window: usize,
+    },
+    /// Percent rank, see [`calculate_percent_rank`]
+    PercentRank {
+        /// Number of previous prices to rank against
+        window: usize,
+    },
+}
+
+impl IndicatorSpec {
+    /// Calculates the indicator over `prices`.
+    ///
+    /// Like the underlying functions, the result ends at the last price and omits the leading
+    /// bars where the indicator is not defined.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying indicator function.
+    pub fn calculate(&self, prices: &[f64]) -> Result<Vec<f64>, IndicatorError> {
+        match *self {
+            IndicatorSpec::MovingAverage { kind, window } => {
+                calculate_moving_average(prices, window, kind)
+            }
+            IndicatorSpec::Rsi { window } => calculate_rsi(prices, window),
+            IndicatorSpec::Roc { window } => calculate_roc(prices, window),
+            IndicatorSpec::Momentum { window } => calculate_momentum(prices, window),
+            IndicatorSpec::PercentRank { window } => calculate_percent_rank(prices, window),
+        }
+    }
+}
+
+/// The lines of Bollinger Bands and their derived series, produced by [`calculate_bollinger`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BollingerBands {
+    /// Middle line plus `k` standard deviations
+    pub upper: Vec<f64>,
+    /// Moving average of the prices
+    pub middle: Vec<f64>,
+    /// Middle line minus `k` standard deviations
+    pub lower: Vec<f64>,
+    /// Position of the price within the bands, `(price - lower) / (upper - lower)`, 0 at the lower
+    /// band and 1 at the upper band
+    pub percent_b: Vec<f64>,
+    /// Width of the bands relative to the middle line, `(upper - lower) / middle`
+    pub bandwidth: Vec<f64>,
+}
+
+/// Calculates Bollinger Bands, a simple moving average enclosed by bands `k` standard deviations
+/// above and below it.
+///
+/// The standard deviation is the population standard deviation of the prices in each window. A
+/// window of equal prices has bands that coincide with the middle line and a %B of `NaN`.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating the bands.
+/// * `k` - The number of standard deviations between the middle line and each band, typically 2.
+///
+/// # Returns
+///
+/// A Result containing the [`BollingerBands`] or an `IndicatorError`. The value at index `i` of
+/// each series covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is
+/// less than `window`, and an `IndicatorError::InvalidInput` if `k` is negative or `NaN`.
+pub fn calculate_bollinger(
+    prices: &[f64],
+    window: usize,
+    k: f64,
+) -> Result<BollingerBands, IndicatorError> {
+    calculate_bollinger_with(prices, window, k, MovingAverageKind::Sma)
+}
+
+/// Calculates Bollinger Bands around the given type of moving average.
+///
+/// The standard deviation is the population standard deviation of the prices in each window, as
+/// in [`calculate_bollinger`]. Moving averages that start later than their window, such as the
+/// HMA, shorten all series to the length of the moving average.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `window` - The size of the window for calculating the bands.
+/// * `k` - The number of standard deviations between the middle line and each band, typically 2.
+/// * `kind` - The type of moving average used as the middle line.
+///
+/// # Returns
+///
+/// A Result containing the [`BollingerBands`] or an `IndicatorError`. All series end at the last
+/// price.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `k` is negative or `NaN`, and the errors of
+/// [`calculate_moving_average`] otherwise.
+pub fn calculate_bollinger_with(
+    prices: &[f64],
+    window: usize,
+    k: f64,
+    kind: MovingAverageKind,
+) -> Result<BollingerBands, IndicatorError> {
+    if k.is_nan() || k < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`k` must not be negative".to_string(),
+        ));
+    }
+    let middle = calculate_moving_average(prices, window, kind)?;
+
+    let mut variance = stats::RollingVariance::new(window);
+    let start = prices.len() - middle.len();
+    let mut result = BollingerBands {
+        upper: Vec::with_capacity(middle.len()),
+        middle: Vec::with_capacity(middle.len()),
+        lower: Vec::with_capacity(middle.len()),
+        percent_b: Vec::with_capacity(middle.len()),
+        bandwidth: Vec::with_capacity(middle.len()),
+    };
+    for (i, price) in prices.iter().enumerate() {
+        variance.push(*price);
+        if i < start {
+            continue;
+        }
+        let population = variance.variance().unwrap_or(0.0) * (window - 1) as f64 / window as f64;
+        let middle = middle[i - start];
+        let upper = middle + k * population.sqrt();
+        let lower = middle - k * population.sqrt();
+        result.upper.push(upper);
+        result.middle.push(middle);
+        result.lower.push(lower);
+        result.percent_b.push((price - lower) / (upper - lower));
+        result.bandwidth.push((upper - lower) / middle);
+    }
+
+    Ok(result)
+}
+
+/// Checks that a volume weighted indicator has one volume per price.
+fn require_volumes(prices: &[f64], volumes: &[f64]) -> Result<(), IndicatorError> {
+    if prices.len() != volumes.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`prices` and `volumes` must have the same length".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates a Volume Weighted Moving Average (VWMA), the moving average of `price * volume`
+/// divided by the moving average of `volume`, using the given type of moving average.
+///
+/// Windows without any volume have a VWMA of `NaN`.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `volumes` - A slice of traded volumes aligned with `prices`.
+/// * `window` - The size of the window for calculating the VWMA.
+/// * `kind` - The type of moving average to weight.
+///
+/// # Returns
+///
+/// A Result containing a vector of VWMA values or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `prices` and `volumes` differ in length, and
+/// the errors of [`calculate_moving_average`] otherwise.
+pub fn calculate_vwma(
+    prices: &[f64],
+    volumes: &[f64],
+    window: usize,
+    kind: MovingAverageKind,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_volumes(prices, volumes)?;
+
+    let notional = prices
+        .iter()
+        .zip(volumes)
+        .map(|(p, v)| p * v)
+        .collect::<Vec<f64>>();
+    let weighted = calculate_moving_average(&notional, window, kind)?;
+    let volume = calculate_moving_average(volumes, window, kind)?;
+    Ok(weighted.iter().zip(&volume).map(|(n, v)| n / v).collect())
+}
+
+/// Calculates the Volume Weighted MACD, the difference between a short and a long
+/// [`calculate_vwma`] with a signal line smoothing that difference.
+///
+/// Prices traded on heavy volume pull the moving averages more strongly, so the MACD reacts to
+/// moves confirmed by volume. Use [`calculate_anchored_vw_macd`] to restart the averages at a bar.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `volumes` - A slice of traded volumes aligned with `prices`.
+/// * `short_window` - The size of the short-term VWMA window.
+/// * `long_window` - The size of the long-term VWMA window.
+/// * `signal_window` - The size of the signal line window.
+/// * `kind` - The type of moving average used by both VWMAs and the signal line.
+///
+/// # Returns
+///
+/// A Result containing a tuple of MACD line, signal line, and histogram or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `prices` and `volumes` differ in length, and
+/// an `IndicatorError::NotEnoughData` if the length of `prices` is insufficient to calculate any
+/// of the moving averages.
+#[allow(clippy::type_complexity)]
+pub fn calculate_vw_macd(
+    prices: &[f64],
+    volumes: &[f64],
+    short_window: usize,
+    long_window: usize,
+    signal_window: usize,
+    kind: MovingAverageKind,
+) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>), IndicatorError> {
+    let vwma_short = calculate_vwma(prices, volumes, short_window, kind)?;
+    let vwma_long = calculate_vwma(prices, volumes, long_window, kind)?;
+
+    let len = vwma_short.len().min(vwma_long.len());
+    let mut macd_line = vwma_short[vwma_short.len() - len..]
+        .iter()
+        .zip(&vwma_long[vwma_long.len() - len..])
+        .map(|(a, b)| a - b)
+        .collect::<Vec<f64>>();
+    let signal_line = calculate_moving_average(&macd_line, signal_window, kind)?;
+    macd_line = macd_line[macd_line.len() - signal_line.len()..].to_owned();
+
+    let histogram = macd_line
+        .iter()
+        .zip(&signal_line)
+        .map(|(a, b)| a - b)
+        .collect::<Vec<f64>>();
+    Ok((macd_line, signal_line, histogram))
+}
+
+/// Calculates the Volume Weighted MACD anchored at a bar, restarting every volume weighted average
+/// and the signal line at `anchor`.
+///
+/// Bars before the anchor do not influence the result, so an anchor at an event such as an
+/// earnings release measures the volume confirmed momentum since the event alone. With an anchor
+/// of zero the result equals [`calculate_vw_macd`].
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `volumes` - A slice of traded volumes aligned with `prices`.
+/// * `anchor` - The index of the bar at which the averages restart.
+/// * `short_window` - The size of the short-term VWMA window.
+/// * `long_window` - The size of the long-term VWMA window.
+/// * `signal_window` - The size of the signal line window.
+/// * `kind` - The type of moving average used by both VWMAs and the signal line.
+///
+/// # Returns
+///
+/// A Result containing a tuple of MACD line, signal line, and histogram or an `IndicatorError`.
+/// The lines end at the last bar and only cover bars from `anchor` onwards.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `prices` and `volumes` differ in length, an
+/// `IndicatorError::InvalidInput` if `anchor` is not the index of a bar, and an
+/// `IndicatorError::NotEnoughData` if the bars from `anchor` onwards are insufficient to calculate
+/// any of the moving averages.
+#[allow(clippy::type_complexity)]
+pub fn calculate_anchored_vw_macd(
+    prices: &[f64],
+    volumes: &[f64],
+    anchor: usize,
+    short_window: usize,
+    long_window: usize,
+    signal_window: usize,
+    kind: MovingAverageKind,
+) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>), IndicatorError> {
+    require_volumes(prices, volumes)?;
+    if anchor >= prices.len() {
+        return Err(IndicatorError::InvalidInput(
+            "`anchor` must be the index of a bar".to_string(),
+        ));
+    }
+
+    calculate_vw_macd(
+        &prices[anchor..],
+        &volumes[anchor..],
+        short_window,
+        long_window,
+        signal_window,
+        kind,
+    )
+}
+
+/// Calculates the Volume Weighted RSI, an RSI whose gains and losses are each weighted by the
+/// volume traded on the bar that produced them.
+///
+/// With constant volume the result equals [`calculate_rsi`].
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price data.
+/// * `volumes` - A slice of traded volumes aligned with `prices`.
+/// * `window` - The size of the window for calculating RSI.
+///
+/// # Returns
+///
+/// A Result containing a vector of RSI values or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `prices` and `volumes` differ in length, and
+/// an `IndicatorError::NotEnoughData` if `window` is zero or the length of `prices` is less than or
+/// equal to `window`.
+pub fn calculate_vw_rsi(
+    prices: &[f64],
+    volumes: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_volumes(prices, volumes)?;
+    require_lookback(prices, window)?;
+
+    let weighted_changes = prices
+        .windows(2)
+        .zip(&volumes[1..])
+        .map(|(p, v)| (p[1] - p[0]) * v)
+        .collect::<Vec<f64>>();
+    Ok(rsi_from_changes(&weighted_changes, window))
+}
+
+/// Calculates On-Balance Volume (OBV), the running total of volume added on bars that close
+/// higher and subtracted on bars that close lower.
+///
+/// The running total starts at zero on the first bar, and bars closing unchanged leave it
+/// unchanged. Only the changes of the OBV are meaningful, not its level.
+///
+/// # Arguments
+///
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with `close`.
+///
+/// # Returns
+///
+/// A Result containing a vector with the OBV of every bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `close` and `volumes` differ in length, and
+/// an `IndicatorError::NotEnoughData` if `close` is empty.
+pub fn calculate_obv(close: &[f64], volumes: &[f64]) -> Result<Vec<f64>, IndicatorError> {
+    require_volumes(close, volumes)?;
+    if close.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "`close` must have at least one item".to_string(),
+        ));
+    }
+
+    let mut obv = 0.0;
+    let mut obv_values = Vec::with_capacity(close.len());
+    obv_values.push(obv);
+    for (pair, volume) in close.windows(2).zip(&volumes[1..]) {
+        if pair[1] > pair[0] {
+            obv += volume;
+        } else if pair[1] < pair[0] {
+            obv -= volume;
+        }
+        obv_values.push(obv);
+    }
+
+    Ok(obv_values)
+}
+
+/// Calculates the Volume Weighted Average Price (VWAP), the cumulative average of the typical
+/// price `(high + low + close) / 3` weighted by volume since the first bar.
+///
+/// Bars before the first traded volume have a VWAP of `NaN`. Use [`calculate_anchored_vwap`] to
+/// restart the average every session, or [`calculate_rolling_vwap`] to average a trailing window.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+///
+/// # Returns
+///
+/// A Result containing a vector with the VWAP of every bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price and volume slices differ in length.
+pub fn calculate_vwap(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_volumes(close, volumes)?;
+
+    let (mut value, mut volume) = (0.0, 0.0);
+    Ok(typical_price(high, low, close)
+        .iter()
+        .zip(volumes)
+        .map(|(price, bar_volume)| {
+            value += price * bar_volume;
+            volume += bar_volume;
+            value / volume
+        })
+        .collect())
+}
+
+/// Calculates a rolling VWAP, the typical price `(high + low + close) / 3` weighted by volume over
+/// the latest `window` bars.
+///
+/// Windows without any traded volume have a VWAP of `NaN`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+/// * `window` - The number of bars in every average.
+///
+/// # Returns
+///
+/// A Result containing a vector of VWAP values or an `IndicatorError`. The value at index `i`
+/// covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price and volume slices differ in length,
+/// and an `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less
+/// than `window`.
+pub fn calculate_rolling_vwap(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_volumes(close, volumes)?;
+    require_window(close, window)?;
+
+    let notional = typical_price(high, low, close)
+        .iter()
+        .zip(volumes)
+        .map(|(price, volume)| price * volume)
+        .collect::<Vec<f64>>();
+    Ok(notional
+        .windows(window)
+        .zip(volumes.windows(window))
+        .map(|(value, volume)| value.iter().sum::<f64>() / volume.iter().sum::<f64>())
+        .collect())
+}
+
+/// Calculates the VWAP restarted at the beginning of every calendar period, e.g. a daily VWAP of
+/// intraday bars with [`resample::Frequency::Daily`].
+///
+/// Periods are evaluated in UTC. Bars of a period before its first traded volume have a VWAP of
+/// `NaN`.
+///
+/// # Arguments
+///
+/// * `timestamps` - Unix timestamps of the bars in seconds.
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+/// * `anchor` - The calendar period at whose start the average restarts.
+///
+/// # Returns
+///
+/// A Result containing a vector with the VWAP of every bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the timestamp, price, and volume slices differ
+/// in length.
+pub fn calculate_anchored_vwap(
+    timestamps: &[i64],
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+    anchor: resample::Frequency,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_volumes(close, volumes)?;
+    if timestamps.len() != close.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`timestamps` and the prices must have the same length".to_string(),
+        ));
+    }
+
+    let (mut value, mut volume, mut period) = (0.0, 0.0, None);
+    Ok(typical_price(high, low, close)
+        .iter()
+        .enumerate()
+        .map(|(i, price)| {
+            let start = anchor.period_start(timestamps[i]);
+            if period != Some(start) {
+                (value, volume, period) = (0.0, 0.0, Some(start));
+            }
+            value += price * volumes[i];
+            volume += volumes[i];
+            value / volume
+        })
+        .collect())
+}
+
+/// Checks that the high, low, and closing prices of a bar indicator have the same length.
+fn require_hlc(high: &[f64], low: &[f64], close: &[f64]) -> Result<(), IndicatorError> {
+    if high.len() != close.len() || low.len() != close.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`high`, `low`, and `close` must have the same length".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates the typical price `(high + low + close) / 3` of every bar.
+fn typical_price(high: &[f64], low: &[f64], close: &[f64]) -> Vec<f64> {
+    (0..close.len())
+        .map(|i| (high[i] + low[i] + close[i]) / 3.0)
+        .collect()
+}
+
+/// Calculates the True Range of every bar, the largest of `high - low`, `|high - previous close|`,
+/// and `|low - previous close|`.
+///
+/// The first bar has no previous close, so its true range is `high - low`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+///
+/// # Returns
+///
+/// A Result containing a vector with the true range of every bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length.
+pub fn calculate_true_range(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+
+    Ok((0..close.len())
+        .map(|i| {
+            let range = high[i] - low[i];
+            match i.checked_sub(1).map(|p| close[p]) {
+                Some(previous) => range
+                    .max((high[i] - previous).abs())
+                    .max((low[i] - previous).abs()),
+                None => range,
+            }
+        })
+        .collect())
+}
+
+/// Calculates the Average True Range (ATR), the true range smoothed with Wilder's moving average.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The size of the window for calculating ATR.
+///
+/// # Returns
+///
+/// A Result containing a vector of ATR values or an `IndicatorError`. The value at index `i`
+/// covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than
+/// `window`.
+pub fn calculate_atr(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    let true_range = calculate_true_range(high, low, close)?;
+    calculate_rma(&true_range, window)
+}
+
+/// The lines of Keltner Channels, produced by [`calculate_keltner`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeltnerChannels {
+    /// Middle line plus `multiplier` average true ranges
+    pub upper: Vec<f64>,
+    /// Moving average of the closing prices
+    pub middle: Vec<f64>,
+    /// Middle line minus `multiplier` average true ranges
+    pub lower: Vec<f64>,
+}
+
+/// Calculates Keltner Channels, an exponential moving average of the closing prices enclosed by
+/// bands a multiple of the Average True Range above and below it.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The size of the window of the middle line, typically 20.
+/// * `atr_window` - The size of the window of the ATR, typically 10.
+/// * `multiplier` - The number of average true ranges between the middle line and each band,
+///   typically 2.
+///
+/// # Returns
+///
+/// A Result containing the [`KeltnerChannels`] or an `IndicatorError`. All lines have the same
+/// length, set by the longer of the two windows, and end at the last bar.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, an
+/// `IndicatorError::NotEnoughData` if a window is zero or longer than the prices, and an
+/// `IndicatorError::InvalidInput` if `multiplier` is negative or `NaN`.
+pub fn calculate_keltner(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+    atr_window: usize,
+    multiplier: f64,
+) -> Result<KeltnerChannels, IndicatorError> {
+    calculate_keltner_with(
+        high,
+        low,
+        close,
+        window,
+        atr_window,
+        multiplier,
+        MovingAverageKind::Ema,
+    )
+}
+
+/// Calculates Keltner Channels around the given type of moving average.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The size of the window of the middle line.
+/// * `atr_window` - The size of the window of the ATR.
+/// * `multiplier` - The number of average true ranges between the middle line and each band.
+/// * `kind` - The type of moving average of the middle line.
+///
+/// # Returns
+///
+/// A Result containing the [`KeltnerChannels`] or an `IndicatorError`. All lines have the same
+/// length and end at the last bar.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, an
+/// `IndicatorError::NotEnoughData` if a window is zero or the prices are too short for the
+/// windows, and an `IndicatorError::InvalidInput` if `multiplier` is negative or `NaN`.
+pub fn calculate_keltner_with(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+    atr_window: usize,
+    multiplier: f64,
+    kind: MovingAverageKind,
+) -> Result<KeltnerChannels, IndicatorError> {
+    if multiplier.is_nan() || multiplier < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`multiplier` must not be negative".to_string(),
+        ));
+    }
+    let atr = calculate_atr(high, low, close, atr_window)?;
+    let middle = calculate_moving_average(close, window, kind)?;
+
+    let len = atr.len().min(middle.len());
+    let middle = middle[middle.len() - len..].to_vec();
+    let atr = &atr[atr.len() - len..];
+    Ok(KeltnerChannels {
+        upper: middle
+            .iter()
+            .zip(atr)
+            .map(|(m, a)| m + multiplier * a)
+            .collect(),
+        lower: middle
+            .iter()
+            .zip(atr)
+            .map(|(m, a)| m - multiplier * a)
+            .collect(),
+        middle,
+    })
+}
+
+/// Calculates Larry Williams' Ultimate Oscillator, a weighted average of the buying pressure over
+/// three periods.
+///
+/// The buying pressure of a bar is `close - min(low, previous close)` and its true range is
+/// `max(high, previous close) - min(low, previous close)`. For each period the buying pressure is
+/// summed and divided by the summed true range, and the oscillator is
+/// `100 * (4 * short + 2 * medium + long) / 7`. Periods without any range make the oscillator
+/// `NaN`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `short` - The shortest period, typically 7.
+/// * `medium` - The medium period, typically 14.
+/// * `long` - The longest period, typically 28.
+///
+/// # Returns
+///
+/// A Result containing a vector of oscillator values between 0 and 100 or an `IndicatorError`.
+/// Every bar needs a previous close, so the value at index `i` covers the bars ending at index
+/// `i + long`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if a period is zero or the length of the prices is not greater
+/// than the longest period.
+pub fn calculate_ultimate_oscillator(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    short: usize,
+    medium: usize,
+    long: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    let true_range = calculate_true_range(high, low, close)?;
+    let longest = short.max(medium).max(long);
+    if short == 0 || medium == 0 || long == 0 {
+        return Err(IndicatorError::NotEnoughData(
+            "Every period must be positive".to_string(),
+        ));
+    }
+    require_lookback(close, longest)?;
+
+    // Running sums of the buying pressure and true range of every bar after the first
+    let (mut pressure, mut range) = (vec![0.0], vec![0.0]);
+    for i in 1..close.len() {
+        let buying = close[i] - low[i].min(close[i - 1]);
+        pressure.push(pressure[i - 1] + buying);
+        range.push(range[i - 1] + true_range[i]);
+    }
+    let average = |end: usize, period: usize| {
+        (pressure[end] - pressure[end - period]) / (range[end] - range[end - period])
+    };
+
+    Ok((longest..close.len())
+        .map(|end| {
+            100.0 * (4.0 * average(end, short) + 2.0 * average(end, medium) + average(end, long))
+                / 7.0
+        })
+        .collect())
+}
+
+/// The lines of the SuperTrend indicator, produced by [`calculate_supertrend`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuperTrend {
+    /// Upper band, which only falls while the close stays below it
+    pub upper: Vec<f64>,
+    /// Lower band, which only rises while the close stays above it
+    pub lower: Vec<f64>,
+    /// The lower band in an uptrend and the upper band in a downtrend, a trailing stop level
+    pub supertrend: Vec<f64>,
+    /// Direction of the trend, `1` for up and `-1` for down
+    pub direction: Vec<i8>,
+}
+
+/// Calculates the SuperTrend indicator, trailing bands `multiplier` Average True Ranges around the
+/// midpoint of every bar whose crossing by the close flips the trend.
+///
+/// A band only moves against the trend it guards: the upper band is lowered to the latest basic
+/// band, or reset to it after the previous close broke above it, and the lower band likewise.
+/// An uptrend turns down when the close falls below the lower band and a downtrend turns up when
+/// it rises above the upper band. The first trend is up if the first close is at or above the
+/// midpoint of its bar.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The size of the window of the ATR, typically 10.
+/// * `multiplier` - The number of ATRs between the midpoint and the bands, typically 3.
+///
+/// # Returns
+///
+/// A Result containing the [`SuperTrend`] or an `IndicatorError`. The value at index `i` covers
+/// the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than
+/// `window`, and an `IndicatorError::InvalidInput` if `multiplier` is negative.
+pub fn calculate_supertrend(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+    multiplier: f64,
+) -> Result<SuperTrend, IndicatorError> {
+    if multiplier.is_nan() || multiplier < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`multiplier` must not be negative".to_string(),
+        ));
+    }
+    let atr = calculate_atr(high, low, close, window)?;
+
+    let mut result = SuperTrend {
+        upper: Vec::with_capacity(atr.len()),
+        lower: Vec::with_capacity(atr.len()),
+        supertrend: Vec::with_capacity(atr.len()),
+        direction: Vec::with_capacity(atr.len()),
+    };
+    for (k, atr) in atr.iter().enumerate() {
+        let i = k + window - 1;
+        let midpoint = (high[i] + low[i]) / 2.0;
+        let (basic_upper, basic_lower) = (midpoint + multiplier * atr, midpoint - multiplier * atr);
+
+        let (upper, lower, direction) = match (
+            result.upper.last(),
+            result.lower.last(),
+            result.direction.last(),
+        ) {
+            (Some(&upper), Some(&lower), Some(&direction)) => {
+                let previous = close[i - 1];
+                let upper = if basic_upper < upper || previous > upper {
+                    basic_upper
+                } else {
+                    upper
+                };
+                let lower = if basic_lower > lower || previous < lower {
+                    basic_lower
+                } else {
+                    lower
+                };
+                let direction = if direction < 0 && close[i] > upper {
+                    1
+                } else if direction > 0 && close[i] < lower {
+                    -1
+                } else {
+                    direction
+                };
+                (upper, lower, direction)
+            }
+            _ => (
+                basic_upper,
+                basic_lower,
+                if close[i] >= midpoint { 1 } else { -1 },
+            ),
+        };
+        result.upper.push(upper);
+        result.lower.push(lower);
+        result
+            .supertrend
+            .push(if direction > 0 { lower } else { upper });
+        result.direction.push(direction);
+    }
+
+    Ok(result)
+}
+
+/// The lines of a stochastic oscillator, produced by [`calculate_stochastic`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stochastic {
+    /// The %K line, the smoothed position of the close within the range of the window
+    pub k: Vec<f64>,
+    /// The %D line, the moving average of %K
+    pub d: Vec<f64>,
+}
+
+/// Calculates the stochastic oscillator, the position of each close within the highest high and
+/// lowest low of the window, from 0 at the low to 100 at the high.
+///
+/// The raw %K of every window is smoothed with a simple moving average over `k_smoothing` values
+/// into %K, and %K is smoothed over `d_smoothing` values into %D. A `k_smoothing` of 1 gives the
+/// fast stochastic and a `k_smoothing` of 3 the common slow stochastic. Windows without any range
+/// have no position within it and a raw %K of 50, the middle of the scale, so flat stretches never
+/// leave the smoothed lines undefined.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `k_window` - The number of bars the range is taken over.
+/// * `k_smoothing` - The size of the window smoothing the raw %K.
+/// * `d_smoothing` - The size of the window smoothing %K into %D.
+///
+/// # Returns
+///
+/// A Result containing the [`Stochastic`] or an `IndicatorError`. Both lines have the same length
+/// and end at the last bar.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if a window is zero or the prices are too short for the
+/// windows.
+pub fn calculate_stochastic(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    k_window: usize,
+    k_smoothing: usize,
+    d_smoothing: usize,
+) -> Result<Stochastic, IndicatorError> {
+    calculate_stochastic_with(
+        high,
+        low,
+        close,
+        k_window,
+        k_smoothing,
+        d_smoothing,
+        MovingAverageKind::Sma,
+    )
+}
+
+/// Calculates the stochastic oscillator, smoothing %K and %D with the given type of moving
+/// average.
+///
+/// As in [`calculate_stochastic`], windows without any range have a raw %K of 50, which keeps the
+/// recursive smoothings such as [`MovingAverageKind::Ema`] defined after flat stretches.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `k_window` - The number of bars the range is taken over.
+/// * `k_smoothing` - The size of the window smoothing the raw %K.
+/// * `d_smoothing` - The size of the window smoothing %K into %D.
+/// * `kind` - The type of moving average used for both smoothings.
+///
+/// # Returns
+///
+/// A Result containing the [`Stochastic`] or an `IndicatorError`. Both lines have the same length
+/// and end at the last bar.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if a window is zero or the prices are too short for the
+/// windows.
+pub fn calculate_stochastic_with(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    k_window: usize,
+    k_smoothing: usize,
+    d_smoothing: usize,
+    kind: MovingAverageKind,
+) -> Result<Stochastic, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_window(close, k_window)?;
+
+    let raw_k = stats::rolling_argmax(high, k_window)
+        .iter()
+        .zip(stats::rolling_argmin(low, k_window))
+        .zip(&close[k_window - 1..])
+        .map(|((highest, lowest), close)| {
+            let (highest, lowest) = (high[*highest], low[lowest]);
+            if highest > lowest {
+                100.0 * (close - lowest) / (highest - lowest)
+            } else {
+                50.0
+            }
+        })
+        .collect::<Vec<f64>>();
+    let k = calculate_moving_average(&raw_k, k_smoothing, kind)?;
+    let d = calculate_moving_average(&k, d_smoothing, kind)?;
+
+    Ok(Stochastic {
+        k: k[k.len() - d.len()..].to_vec(),
+        d,
+    })
+}
+
+/// Calculates Williams %R, the distance of the close below the highest high of the window as a
+/// percentage of the window's range.
+///
+/// Values range from -100, a close at the lowest low, to 0, a close at the highest high. Windows
+/// without a range have a %R of `NaN`. The extremes are tracked with monotonic deques in linear
+/// time.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The number of bars the range is taken over.
+///
+/// # Returns
+///
+/// A Result containing a vector of %R values or an `IndicatorError`. The value at index `i`
+/// covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than
+/// `window`.
+pub fn calculate_williams_r(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_window(close, window)?;
+
+    Ok(stats::rolling_argmax(high, window)
+        .iter()
+        .zip(stats::rolling_argmin(low, window))
+        .zip(&close[window - 1..])
+        .map(|((highest, lowest), close)| {
+            let (highest, lowest) = (high[*highest], low[lowest]);
+            if highest > lowest {
+                -100.0 * (highest - close) / (highest - lowest)
+            } else {
+                f64::NAN
+            }
+        })
+        .collect())
+}
+
+/// The lines of the Aroon indicator, produced by [`calculate_aroon`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aroon {
+    /// Aroon Up, `100 * (window - bars since the highest high) / window`
+    pub up: Vec<f64>,
+    /// Aroon Down, `100 * (window - bars since the lowest low) / window`
+    pub down: Vec<f64>,
+    /// Aroon Oscillator, Aroon Up minus Aroon Down
+    pub oscillator: Vec<f64>,
+}
+
+/// Calculates the Aroon indicator, how recently the highest high and lowest low of each window
+/// occurred.
+///
+/// Each window spans the current bar and the `window` bars before it, so Aroon Up is 100 when the
+/// current bar makes the highest high and 0 when the highest high is `window` bars old. Ties are
+/// resolved in favour of the most recent bar. The extremes are tracked with monotonic deques in
+/// linear time.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `window` - The number of bars to look back.
+///
+/// # Returns
+///
+/// A Result containing the [`Aroon`] lines or an `IndicatorError`. The value at index `i` covers
+/// the bars from index `i` to index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `high` and `low` differ in length, and an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than or
+/// equal to `window`.
+pub fn calculate_aroon(high: &[f64], low: &[f64], window: usize) -> Result<Aroon, IndicatorError> {
+    if high.len() != low.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`high` and `low` must have the same length".to_string(),
+        ));
+    }
+    require_lookback(high, window)?;
+
+    let line = |positions: Vec<usize>| {
+        positions
+            .iter()
+            .enumerate()
+            .map(|(i, position)| 100.0 * (window - (i + window - position)) as f64 / window as f64)
+            .collect::<Vec<f64>>()
+    };
+    let up = line(stats::rolling_argmax(high, window + 1));
+    let down = line(stats::rolling_argmin(low, window + 1));
+    let oscillator = up.iter().zip(&down).map(|(up, down)| up - down).collect();
+
+    Ok(Aroon {
+        up,
+        down,
+        oscillator,
+    })
+}
+
+/// The lines of Donchian Channels, produced by [`calculate_donchian`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DonchianChannels {
+    /// Highest high of the window
+    pub upper: Vec<f64>,
+    /// Midpoint of the upper and lower lines
+    pub middle: Vec<f64>,
+    /// Lowest low of the window
+    pub lower: Vec<f64>,
+}
+
+/// Calculates Donchian Channels, the highest high and lowest low of every window and their
+/// midpoint.
+///
+/// The extremes are tracked with monotonic deques, so the channels take linear time regardless of
+/// the window.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `window` - The number of bars in each window, typically 20.
+///
+/// # Returns
+///
+/// A Result containing the [`DonchianChannels`] or an `IndicatorError`. The value at index `i`
+/// covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `high` and `low` differ in length, and an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than
+/// `window`.
+pub fn calculate_donchian(
+    high: &[f64],
+    low: &[f64],
+    window: usize,
+) -> Result<DonchianChannels, IndicatorError> {
+    if high.len() != low.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`high` and `low` must have the same length".to_string(),
+        ));
+    }
+    require_window(high, window)?;
+
+    let upper = stats::rolling_argmax(high, window)
+        .iter()
+        .map(|i| high[*i])
+        .collect::<Vec<f64>>();
+    let lower = stats::rolling_argmin(low, window)
+        .iter()
+        .map(|i| low[*i])
+        .collect::<Vec<f64>>();
+    Ok(DonchianChannels {
+        middle: upper
+            .iter()
+            .zip(&lower)
+            .map(|(u, l)| (u + l) / 2.0)
+            .collect(),
+        upper,
+        lower,
+    })
+}
+
+/// The lines of Wilder's directional movement system, produced by [`calculate_adx`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adx {
+    /// The Average Directional Index, the smoothed spread between the directional indicators
+    pub adx: Vec<f64>,
+    /// The positive directional indicator, the share of the true range made up by upward moves
+    pub plus_di: Vec<f64>,
+    /// The negative directional indicator, the share of the true range made up by downward moves
+    pub minus_di: Vec<f64>,
+}
+
+/// Calculates Wilder's directional movement system: the Average Directional Index (ADX) with the
+/// positive and negative directional indicators (+DI and -DI).
+///
+/// Every bar after the first has an upward move `high - previous high` and a downward move
+/// `previous low - low`, and only the larger of the two counts as directional movement, if
+/// positive. The directional movements and the true range are smoothed with Wilder's moving
+/// average, `+DI` and `-DI` are the smoothed movements as a percentage of the smoothed true range,
+/// and the ADX is Wilder's moving average of `100 * |+DI - -DI| / (+DI + -DI)`. Bars with a
+/// smoothed true range of zero have both indicators at zero, and bars where both indicators are
+/// zero have a spread of zero, so runs of flat bars never make the ADX `NaN`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The size of the window for the Wilder smoothings.
+///
+/// # Returns
+///
+/// A Result containing the [`Adx`] or an `IndicatorError`. All three series have the same length
+/// and end at the last bar, so the first value belongs to the bar at index `2 * window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than
+/// `2 * window`.
+pub fn calculate_adx(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+) -> Result<Adx, IndicatorError> {
+    let true_range = calculate_true_range(high, low, close)?;
+    if window == 0 || close.len() < 2 * window {
+        return Err(IndicatorError::NotEnoughData(
+            "The prices must have at least `2 * window` items and `window` must be positive"
+                .to_string(),
+        ));
+    }
+
+    let (plus_dm, minus_dm): (Vec<f64>, Vec<f64>) = high
+        .windows(2)
+        .zip(low.windows(2))
+        .map(|(high, low)| {
+            let up = high[1] - high[0];
+            let down = low[0] - low[1];
+            (
+                if up > down && up > 0.0 { up } else { 0.0 },
+                if down > up && down > 0.0 { down } else { 0.0 },
+            )
+        })
+        .unzip();
+    let true_range = calculate_rma(&true_range[1..], window)?;
+    let plus_di = calculate_rma(&plus_dm, window)?
+        .iter()
+        .zip(&true_range)
+        .map(|(dm, tr)| if *tr == 0.0 { 0.0 } else { 100.0 * dm / tr })
+        .collect::<Vec<f64>>();
+    let minus_di = calculate_rma(&minus_dm, window)?
+        .iter()
+        .zip(&true_range)
+        .map(|(dm, tr)| if *tr == 0.0 { 0.0 } else { 100.0 * dm / tr })
+        .collect::<Vec<f64>>();
+    let dx = plus_di
+        .iter()
+        .zip(&minus_di)
+        .map(|(plus, minus)| {
+            let sum = plus + minus;
+            if sum == 0.0 {
+                0.0
+            } else {
+                100.0 * (plus - minus).abs() / sum
+            }
+        })
+        .collect::<Vec<f64>>();
+    let adx = calculate_rma(&dx, window)?;
+
+    let start = plus_di.len() - adx.len();
+    Ok(Adx {
+        adx,
+        plus_di: plus_di[start..].to_vec(),
+        minus_di: minus_di[start..].to_vec(),
+    })
+}
+
+/// Calculates the Commodity Channel Index (CCI), the deviation of the typical price from its
+/// moving average in units of its mean absolute deviation.
+///
+/// The typical price of a bar is `(high + low + close) / 3`, and the CCI of a window is
+/// `(typical price - SMA) / (0.015 * mean deviation)`, where the constant scales most values into
+/// the range -100 to 100. Windows of equal typical prices have a mean deviation of zero and a CCI
+/// of `NaN`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `window` - The size of the window for calculating CCI.
+///
+/// # Returns
+///
+/// A Result containing a vector of CCI values or an `IndicatorError`. The value at index `i`
+/// covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less than
+/// `window`.
+pub fn calculate_cci(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_window(close, window)?;
+
+    Ok(typical_price(high, low, close)
+        .windows(window)
+        .map(|w| {
+            let mean = w.iter().sum::<f64>() / window as f64;
+            let deviation = w.iter().map(|p| (p - mean).abs()).sum::<f64>() / window as f64;
+            (w[window - 1] - mean) / (0.015 * deviation)
+        })
+        .collect())
+}
+
+/// Calculates the Money Flow Index (MFI), an RSI of the typical price weighted by volume.
+///
+/// The money flow of a bar is its typical price `(high + low + close) / 3` times its volume. It
+/// counts as positive when the typical price rose from the previous bar and as negative when it
+/// fell, and the MFI of a window is `100 - 100 / (1 + positive flow / negative flow)`. Windows
+/// without negative flow have an MFI of 100, and windows without any flow have an MFI of `NaN`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+/// * `window` - The number of money flows in each window.
+///
+/// # Returns
+///
+/// A Result containing a vector of MFI values or an `IndicatorError`. Each window compares
+/// `window` bars with their previous bars, so the value at index `i` covers the window ending at
+/// index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price and volume slices differ in length,
+/// and an `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is not
+/// greater than `window`.
+pub fn calculate_mfi(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_volumes(close, volumes)?;
+    if window == 0 || close.len() <= window {
+        return Err(IndicatorError::NotEnoughData(
+            "MFI needs more bars than the window".to_string(),
+        ));
+    }
+
+    let typical = typical_price(high, low, close);
+    let flows = typical
+        .windows(2)
+        .zip(&volumes[1..])
+        .map(|(pair, volume)| {
+            let flow = pair[1] * volume;
+            if pair[1] > pair[0] {
+                (flow, 0.0)
+            } else if pair[1] < pair[0] {
+                (0.0, flow)
+            } else {
+                (0.0, 0.0)
+            }
+        })
+        .collect::<Vec<(f64, f64)>>();
+    Ok(flows
+        .windows(window)
+        .map(|w| {
+            let positive = w.iter().map(|(positive, _)| positive).sum::<f64>();
+            let negative = w.iter().map(|(_, negative)| negative).sum::<f64>();
+            if negative > 0.0 {
+                100.0 - 100.0 / (1.0 + positive / negative)
+            } else if positive > 0.0 {
+                100.0
+            } else {
+                f64::NAN
+            }
+        })
+        .collect())
+}
+
+/// Calculates the money flow multiplier of a bar, the position of the close within the range
+/// from -1 at the low to 1 at the high, or 0 for a bar without range.
+fn money_flow_multiplier(high: f64, low: f64, close: f64) -> f64 {
+    if high > low {
+        ((close - low) - (high - close)) / (high - low)
+    } else {
+        0.0
+    }
+}
+
+/// Calculates the Chaikin Money Flow (CMF), the volume weighted average money flow multiplier of
+/// every window.
+///
+/// The money flow multiplier of a bar is `((close - low) - (high - close)) / (high - low)`, from
+/// -1 for a close at the low to 1 for a close at the high, and 0 for a bar without range. Windows
+/// without volume have a CMF of `NaN`.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+/// * `window` - The size of the window, typically 20.
+///
+/// # Returns
+///
+/// A Result containing a vector of CMF values between -1 and 1 or an `IndicatorError`. The value
+/// at index `i` covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price and volume slices differ in length,
+/// and an `IndicatorError::NotEnoughData` if `window` is zero or the length of the prices is less
+/// than `window`.
+pub fn calculate_cmf(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_volumes(close, volumes)?;
+    require_window(close, window)?;
+
+    let flows = (0..close.len())
+        .map(|i| money_flow_multiplier(high[i], low[i], close[i]) * volumes[i])
+        .collect::<Vec<f64>>();
+    Ok(flows
+        .windows(window)
+        .zip(volumes.windows(window))
+        .map(|(flows, volumes)| flows.iter().sum::<f64>() / volumes.iter().sum::<f64>())
+        .collect())
+}
+
+/// Calculates the Accumulation/Distribution Line (ADL), the running total of the money flow
+/// volume of every bar.
+///
+/// The money flow volume of a bar is its volume times its money flow multiplier
+/// `((close - low) - (high - close)) / (high - low)`, which is 0 for a bar without range.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+///
+/// # Returns
+///
+/// A Result containing a vector with the ADL of every bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price and volume slices differ in length.
+pub fn calculate_adl(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+) -> Result<Vec<f64>, IndicatorError> {
+    require_hlc(high, low, close)?;
+    require_volumes(close, volumes)?;
+
+    let mut total = 0.0;
+    Ok((0..close.len())
+        .map(|i| {
+            total += money_flow_multiplier(high[i], low[i], close[i]) * volumes[i];
+            total
+        })
+        .collect())
+}
+
+/// Calculates the Chaikin Oscillator, the difference between a fast and a slow EMA of the
+/// Accumulation/Distribution Line.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `volumes` - A slice of traded volumes aligned with the prices.
+/// * `fast_window` - The size of the window of the fast EMA, typically 3.
+/// * `slow_window` - The size of the window of the slow EMA, typically 10.
+///
+/// # Returns
+///
+/// A Result containing a vector of oscillator values or an `IndicatorError`. The values end at
+/// the last bar and start once both EMAs are defined.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price and volume slices differ in length,
+/// and an `IndicatorError::NotEnoughData` if a window is zero or the length of the prices is less
+/// than a window.
+pub fn calculate_chaikin_oscillator(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    volumes: &[f64],
+    fast_window: usize,
+    slow_window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    let adl = calculate_adl(high, low, close, volumes)?;
+    let fast = calculate_ema(&adl, fast_window)?;
+    let slow = calculate_ema(&adl, slow_window)?;
+
+    let len = fast.len().min(slow.len());
+    Ok(fast[fast.len() - len..]
+        .iter()
+        .zip(&slow[slow.len() - len..])
+        .map(|(fast, slow)| fast - slow)
+        .collect())
+}
+
+/// The lines of the Ichimoku Cloud, produced by [`calculate_ichimoku`].
+///
+/// Unlike the other indicators, every line is indexed by the bar it is plotted at, so that the
+/// displaced lines can share one axis with the prices. Index `i` of every line belongs to bar `i`
+/// of the input, bars where a line is not defined hold `NaN`, and the leading spans continue
+/// `displacement` bars past the last input bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ichimoku {
+    /// Tenkan-sen, the midpoint of the highest high and lowest low over the conversion period.
+    /// One value per input bar
+    pub tenkan: Vec<f64>,
+    /// Kijun-sen, the midpoint of the highest high and lowest low over the base period. One value
+    /// per input bar
+    pub kijun: Vec<f64>,
+    /// Senkou Span A, the average of Tenkan and Kijun, plotted `displacement` bars ahead of the bar
+    /// it was computed on. Holds `displacement` more values than there are input bars
+    pub senkou_a: Vec<f64>,
+    /// Senkou Span B, the midpoint over the span B period, plotted `displacement` bars ahead of
+    /// the bar it was computed on. Holds `displacement` more values than there are input bars
+    pub senkou_b: Vec<f64>,
+    /// Chikou Span, the close plotted `displacement` bars behind its own bar, so the value at bar
+    /// `i` is the later close of bar `i + displacement` and the last `displacement` values are
+    /// `NaN`. One value per input bar. This is a display-only lagging line: its values change from
+    /// `NaN` once the later bars arrive, so it repaints and must not feed signals
+    pub chikou: Vec<f64>,
+}
+
+/// Returns the midpoint of the highest high and lowest low of the window ending at every bar, or
+/// `NaN` before the first full window. The extremes are tracked with monotonic deques.
+fn rolling_midpoint(high: &[f64], low: &[f64], window: usize) -> Vec<f64> {
+    let mut midpoints = vec![f64::NAN; window - 1];
+    midpoints.extend(
+        stats::rolling_argmax(high, window)
+            .iter()
+            .zip(stats::rolling_argmin(low, window))
+            .map(|(highest, lowest)| (high[*highest] + low[lowest]) / 2.0),
+    );
+    midpoints
+}
+
+/// Calculates the Ichimoku Cloud with configurable periods, typically 9, 26, and 52 with a
+/// displacement of 26.
+///
+/// See [`Ichimoku`] for how the displaced lines are aligned with the input bars.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `close` - A slice of closing prices.
+/// * `conversion` - The period of the Tenkan-sen.
+/// * `base` - The period of the Kijun-sen.
+/// * `span_b` - The period of the Senkou Span B.
+/// * `displacement` - The number of bars the leading spans are shifted forward and the Chikou
+///   Span is shifted backward.
+///
+/// # Returns
+///
+/// A Result containing the [`Ichimoku`] lines or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the price slices differ in length, and an
+/// `IndicatorError::NotEnoughData` if a period is zero or the length of the prices is less than
+/// the longest period.
+pub fn calculate_ichimoku(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    conversion: usize,
+    base: usize,
+    span_b: usize,
+    displacement: usize,
+) -> Result<Ichimoku, IndicatorError> {
+    require_hlc(high, low, close)?;
+    for period in [conversion, base, span_b] {
+        require_window(close, period)?;
+    }
+
+    let tenkan = rolling_midpoint(high, low, conversion);
+    let kijun = rolling_midpoint(high, low, base);
+    let mut senkou_a = vec![f64::NAN; displacement];
+    senkou_a.extend(tenkan.iter().zip(&kijun).map(|(t, k)| (t + k) / 2.0));
+    let mut senkou_b = vec![f64::NAN; displacement];
+    senkou_b.extend(rolling_midpoint(high, low, span_b));
+    let chikou = (0..close.len())
+        .map(|i| close.get(i + displacement).copied().unwrap_or(f64::NAN))
+        .collect();
+
+    Ok(Ichimoku {
+        tenkan,
+        kijun,
+        senkou_a,
+        senkou_b,
+        chikou,
+    })
+}
+
+/// The lines of the Parabolic SAR, produced by [`calculate_psar`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParabolicSar {
+    /// Stop and reverse level
+    pub sar: Vec<f64>,
+    /// Direction of the trend the stop guards, `1` for long and `-1` for short
+    pub direction: Vec<i8>,
+}
+
+/// Calculates Wilder's Parabolic SAR (stop and reverse), a trailing stop that accelerates towards
+/// the price as a trend extends and flips to the other side of the price when it is hit.
+///
+/// The first trend is long if the second bar has a higher midpoint than the first and short
+/// otherwise, with the SAR starting at the extreme of the first bar opposite the trend. Every bar
+/// the SAR moves towards the extreme point of the trend by the acceleration factor, which starts
+/// at `af_start` and grows by `af_step` with every new extreme up to `af_max`. A long SAR never
+/// rises above the lows of the previous two bars and a short SAR never falls below their highs.
+/// When a bar crosses the SAR, the trend reverses and the SAR restarts at the extreme point of the
+/// finished trend. [`streaming::StreamingPsar`] produces the same values one bar at a time.
+///
+/// # Arguments
+///
+/// * `high` - A slice of high prices.
+/// * `low` - A slice of low prices.
+/// * `af_start` - The acceleration factor at the start of every trend, typically 0.02.
+/// * `af_step` - The increment of the acceleration factor, typically 0.02.
+/// * `af_max` - The largest acceleration factor, typically 0.2.
+///
+/// # Returns
+///
+/// A Result containing the [`ParabolicSar`] or an `IndicatorError`. The value at index `i` is the
+/// stop of the bar at index `i + 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `high` and `low` differ in length, an
+/// `IndicatorError::NotEnoughData` if there are fewer than two bars, and an
+/// `IndicatorError::InvalidInput` if `af_start` or `af_step` is not positive or `af_max` is less
+/// than `af_start`.
+pub fn calculate_psar(
+    high: &[f64],
+    low: &[f64],
+    af_start: f64,
+    af_step: f64,
+    af_max: f64,
+) -> Result<ParabolicSar, IndicatorError> {
+    if high.len() != low.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`high` and `low` must have the same length".to_string(),
+        ));
+    }
+    if high.len() < 2 {
+        return Err(IndicatorError::NotEnoughData(
+            "At least two bars are required".to_string(),
+        ));
+    }
+    if af_start.is_nan()
+        || af_start <= 0.0
+        || af_step.is_nan()
+        || af_step <= 0.0
+        || af_max.is_nan()
+        || af_max < af_start
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`af_start` and `af_step` must be positive and `af_max` must not be less than `af_start`"
+                .to_string(),
+        ));
+    }
+
+    let mut state = streaming::StreamingPsar::new(af_start, af_step, af_max);
+    let mut result = ParabolicSar {
+        sar: Vec::with_capacity(high.len() - 1),
+        direction: Vec::with_capacity(high.len() - 1),
+    };
+    for (high, low) in high.iter().zip(low) {
+        if let Some((sar, direction)) = state.update(*high, *low) {
+            result.sar.push(sar);
+            result.direction.push(direction);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_rsi() {
+        // Test case with enough data
+        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let window = 3;
+        let result = calculate_rsi(prices.as_slice(), window).unwrap();
+        assert_eq!(result, vec![100.0, 100.0]);
+
+        // Test case with not enough data
+        let prices = vec![1.0, 2.0];
+        let window = 3;
+        let result = calculate_rsi(prices.as_slice(), window);
+        assert!(result.is_err());
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+        let result = calculate_rsi(&[1.0, 2.0, 3.0], 0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_ema() {
+        // Test case with enough data
+        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let window = 3;
+        let result = calculate_ema(prices.as_slice(), window).unwrap();
+        assert_eq!(result, vec![2.0, 3.0, 4.0]);
+
+        // Test case with not enough data
+        let prices = vec![1.0, 2.0];
+        let window = 3;
+        let result = calculate_ema(prices.as_slice(), window);
+        assert!(result.is_err());
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+        match calculate_ema(&prices, 0) {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_sma() {
+        // Test case with enough data
+        let prices = vec![1.0, 2.0, 3.0, 4.0, 6.0];
+        let window = 3;
+        let result = calculate_sma(prices.as_slice(), window).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!((result[0] - 2.0).abs() < 1e-12);
+        assert!((result[1] - 3.0).abs() < 1e-12);
+        assert!((result[2] - 13.0 / 3.0).abs() < 1e-12);
+
+        // Test case with not enough data
+        let prices = vec![1.0, 2.0];
+        let window = 3;
+        let result = calculate_sma(prices.as_slice(), window);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+        let result = calculate_sma(prices.as_slice(), 0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_macd() {
+        // Test case with enough data
+        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let short_window = 2;
+        let long_window = 4;
+        let signal_window = 2;
+        let result =
+            calculate_macd(prices.as_slice(), short_window, long_window, signal_window).unwrap();
+        assert_eq!(result, (vec![1.0], vec![1.0], vec![0.0]));
+
+        // Test case with not enough data
+        let prices = vec![1.0, 2.0];
+        let short_window = 2;
+        let long_window = 4;
+        let signal_window = 2;
+        let result = calculate_macd(prices.as_slice(), short_window, long_window, signal_window);
+        assert!(result.is_err());
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_median_channel() {
+        // Test case with enough data
+        let high = [11.0, 12.0, 50.0, 13.0, 12.5];
+        let low = [9.0, 10.0, 10.5, 11.0, 1.0];
+        let close = [10.0, 11.5, 12.0, 12.5, 11.0];
+        let result = calculate_median_channel(&high, &low, &close, 3).unwrap();
+        assert_eq!(result.upper, vec![12.0, 13.0, 13.0]);
+        assert_eq!(result.middle, vec![11.5, 12.0, 12.0]);
+        assert_eq!(result.lower, vec![10.0, 10.5, 10.5]);
+
+        // Test case with mismatched lengths
+        let result = calculate_median_channel(&high, &low[1..], &close, 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_percentile_bands() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 9.0, 12.0, 30.0, 11.5];
+        let result = calculate_percentile_bands(&prices, 5, 0.25, 0.75).unwrap();
+        assert_eq!(result.upper, vec![12.0, 12.0]);
+        assert_eq!(result.middle, vec![11.0, 11.5]);
+        assert_eq!(result.lower, vec![10.0, 11.0]);
+
+        // Test case with inverted quantiles
+        let result = calculate_percentile_bands(&prices, 5, 0.75, 0.25);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_iqr_bands() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 9.0, 12.0, 30.0, 11.5];
+        let result = calculate_iqr_bands(&prices, 5, 1.5).unwrap();
+        assert_eq!(result.upper, vec![15.0, 13.5]);
+        assert_eq!(result.middle, vec![11.0, 11.5]);
+        assert_eq!(result.lower, vec![7.0, 9.5]);
+
+        // Test case with not enough data
+        let result = calculate_iqr_bands(&prices, 7, 1.5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_percent_rank() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 9.0, 12.0, 11.0, 8.0];
+        let result = calculate_percent_rank(&prices, 4).unwrap();
+        assert_eq!(result, vec![75.0, 0.0]);
+
+        // Test case with not enough data
+        let result = calculate_percent_rank(&prices, 6);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_roc() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 9.0, 12.0, 11.0];
+        let result = calculate_roc(&prices, 2).unwrap();
+        let expected = [-10.0, 100.0 / 11.0, 100.0 * 2.0 / 9.0];
+        assert_eq!(result.len(), 3);
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_roc(&prices, 5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_momentum() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 9.0, 12.0, 11.0];
+        let result = calculate_momentum(&prices, 2).unwrap();
+        assert_eq!(result, vec![-1.0, 1.0, 2.0]);
+
+        // Test case with not enough data
+        let result = calculate_momentum(&prices, 0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_indicator_spec() {
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0];
+        let spec = IndicatorSpec::MovingAverage {
+            kind: MovingAverageKind::Wma,
+            window: 4,
+        };
+        assert_eq!(
+            spec.calculate(&prices).unwrap(),
+            calculate_wma(&prices, 4).unwrap()
+        );
+        let spec = IndicatorSpec::Momentum { window: 2 };
+        assert_eq!(spec.calculate(&prices).unwrap(), vec![2.0, 3.0, 5.0, 8.0]);
+
+        // Test case with not enough data
+        let result = IndicatorSpec::Rsi { window: 6 }.calculate(&prices);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_trix() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 9.0, 12.0, 11.0, 13.0, 14.0, 12.0, 15.0, 16.0];
+        let result = calculate_trix(&prices, 2, None).unwrap();
+        let expected = [
+            3.683_897_801_544_855_4,
+            6.914_995_224_450_804,
+            8.182_955_154_547_077,
+            0.928_525_552_803_027_8,
+            5.710_199_208_499_223,
+            7.603_267_880_177_733,
+        ];
+        assert_eq!(result.signal, None);
+        assert_eq!(result.trix.len(), 6);
+        for (value, expected) in result.trix.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+        let result = calculate_trix(&prices, 2, Some(3)).unwrap();
+        let signal = [
+            6.260_616_060_180_912,
+            3.594_570_806_491_97,
+            4.652_385_007_495_596,
+            6.127_826_443_836_664,
+        ];
+        assert_eq!(result.trix.len(), 4);
+        assert!((result.trix[0] - expected[2]).abs() < 1e-9);
+        for (value, expected) in result.signal.unwrap().iter().zip(&signal) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_trix(&prices, 4, None);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_wma() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0];
+        let result = calculate_wma(&prices, 4).unwrap();
+        for (actual, expected) in result.iter().zip([3.4, 5.5, 8.9]) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with not enough data
+        let result = calculate_wma(&prices, 0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_tma() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0];
+        let result = calculate_tma(&prices, 4).unwrap();
+        let expected = [2.6666666666666665, 4.333333333333333, 7.0];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with not enough data
+        let result = calculate_tma(&prices, 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_swma() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0];
+        let result = calculate_swma(&prices, 4).unwrap();
+        let expected = [2.690983005625053, 4.381966011250106, 7.072949016875158];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with not enough data
+        let result = calculate_swma(&prices, 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_moving_average() {
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0];
+        for kind in [
+            MovingAverageKind::Sma,
+            MovingAverageKind::Ema,
+            MovingAverageKind::Wma,
+            MovingAverageKind::Tma,
+            MovingAverageKind::Swma,
+            MovingAverageKind::Rma,
+        ] {
+            assert_eq!(calculate_moving_average(&prices, 3, kind).unwrap().len(), 4);
+        }
+        let result = calculate_moving_average(&prices, 2, MovingAverageKind::Sma).unwrap();
+        assert_eq!(result, vec![1.5, 2.5, 4.0, 6.5, 10.5]);
+    }
+
+    #[test]
+    fn test_calculate_macd_with() {
+        // Test case with simple moving averages
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0];
+        let (macd, signal, histogram) =
+            calculate_macd_with(&prices, 2, 4, 2, MovingAverageKind::Sma).unwrap();
+        assert_eq!(macd, vec![2.0, 3.25, 5.25, 8.5]);
+        assert_eq!(signal, vec![1.625, 2.625, 4.25, 6.875]);
+        assert_eq!(histogram, vec![0.375, 0.625, 1.0, 1.625]);
+
+        // The EMA variant matches `calculate_macd`
+        let result = calculate_macd_with(&prices, 2, 4, 2, MovingAverageKind::Ema).unwrap();
+        assert_eq!(result, calculate_macd(&prices, 2, 4, 2).unwrap());
+
+        // Test case with not enough data
+        let result = calculate_macd_with(&prices, 2, 9, 2, MovingAverageKind::Wma);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_rma() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0];
+        let result = calculate_rma(&prices, 3).unwrap();
+        let expected = [
+            2.0,
+            3.0,
+            4.666666666666667,
+            7.444444444444445,
+            11.962962962962962,
+            19.308641975308642,
+        ];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with not enough data
+        let result = calculate_rma(&prices, 9);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_hma() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0];
+        let result = calculate_hma(&prices, 4).unwrap();
+        let expected = [
+            7.422222222222222,
+            12.011111111111111,
+            19.433333333333334,
+            31.444444444444443,
+        ];
+        assert_eq!(result.len(), expected.len());
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with not enough data
+        let result = calculate_hma(&prices[..4], 4);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_kama() {
+        // Test case with enough data
+        let prices = [10.0, 10.5, 10.2, 11.0, 11.4, 11.1, 12.0, 12.5, 12.2, 13.0];
+        let result = calculate_kama(&prices, 3, 2, 30).unwrap();
+        let expected = [
+            10.355486183373799,
+            10.544868168190604,
+            10.645519750266013,
+            10.908773455873925,
+            11.23695727412603,
+            11.435580807703907,
+            11.73963776946246,
+        ];
+        assert_eq!(result.len(), expected.len());
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with not enough data
+        let result = calculate_kama(&prices[..3], 3, 2, 30);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid input
+        let result = calculate_kama(&prices, 3, 30, 2);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_dema() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0];
+        let result = calculate_dema(&prices, 3).unwrap();
+        assert_eq!(result, vec![7.75, 12.1875, 19.5, 31.453125]);
+
+        // Test case with not enough data
+        let result = calculate_dema(&prices[..4], 3);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_tema() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0];
+        let result = calculate_tema(&prices, 3).unwrap();
+        assert_eq!(result, vec![20.0, 32.9765625]);
+
+        // Test case with not enough data
+        let result = calculate_tema(&prices[..6], 3);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_bollinger() {
+        // Test case with enough data
+        let prices = [1.0, 2.0, 3.0, 4.0, 6.0];
+        let result = calculate_bollinger(&prices, 3, 2.0).unwrap();
+        let expected = [
+            (
+                3.632_993_161_855_452,
+                2.0,
+                0.367_006_838_144_547_93,
+                0.806_186_217_847_897_2,
+                1.632_993_161_855_452,
+            ),
+            (
+                4.632_993_161_855_452_5,
+                3.0,
+                1.367_006_838_144_548,
+                0.806_186_217_847_897_1,
+                1.088_662_107_903_635,
+            ),
+            (
+                6.827_771_591_182_627,
+                4.333_333_333_333_333,
+                1.838_895_075_484_039,
+                0.834_076_552_390_530_6,
+                1.151_279_195_930_443_4,
+            ),
+        ];
+        assert_eq!(result.middle.len(), 3);
+        for (i, (upper, middle, lower, percent_b, bandwidth)) in expected.iter().enumerate() {
+            assert!((result.upper[i] - upper).abs() < 1e-9);
+            assert!((result.middle[i] - middle).abs() < 1e-9);
+            assert!((result.lower[i] - lower).abs() < 1e-9);
+            assert!((result.percent_b[i] - percent_b).abs() < 1e-9);
+            assert!((result.bandwidth[i] - bandwidth).abs() < 1e-9);
+        }
+
+        // Flat prices have coinciding bands
+        let result = calculate_bollinger_with(&[5.0; 4], 2, 2.0, MovingAverageKind::Ema).unwrap();
+        assert_eq!(result.upper, vec![5.0; 3]);
+        assert!(result.percent_b.iter().all(|b| b.is_nan()));
+
+        // Test case with not enough data
+        let result = calculate_bollinger(&prices, 6, 2.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid input
+        let result = calculate_bollinger(&prices, 3, -1.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_vwma() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 10.5, 12.0, 13.0, 12.5, 14.0];
+        let volumes = [100.0, 200.0, 100.0, 300.0, 100.0, 200.0, 400.0];
+        let result = calculate_vwma(&prices, &volumes, 2, MovingAverageKind::Sma).unwrap();
+        let expected = [
+            10.666666666666666,
+            10.833333333333334,
+            11.625,
+            12.25,
+            12.666666666666666,
+            13.5,
+        ];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_vwma(&prices, &volumes[1..], 2, MovingAverageKind::Sma);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_vw_macd() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 10.5, 12.0, 13.0, 12.5, 14.0];
+        let volumes = [100.0, 200.0, 100.0, 300.0, 100.0, 200.0, 400.0];
+        let (macd, signal, histogram) =
+            calculate_vw_macd(&prices, &volumes, 2, 3, 2, MovingAverageKind::Sma).unwrap();
+        let expected = [
+            (0.20833333333333215, 0.20833333333333304),
+            (0.34999999999999964, 0.2791666666666659),
+            (0.3333333333333339, 0.3416666666666668),
+            (0.07142857142857117, 0.20238095238095255),
+        ];
+        assert_eq!(macd.len(), expected.len());
+        for (i, (m, s)) in expected.iter().enumerate() {
+            assert!((macd[i] - m).abs() < 1e-9);
+            assert!((signal[i] - s).abs() < 1e-9);
+            assert!((histogram[i] - (m - s)).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_vw_macd(&prices, &volumes, 2, 8, 2, MovingAverageKind::Ema);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_anchored_vw_macd() {
+        let prices = [20.0, 18.0, 10.0, 11.0, 10.5, 12.0, 13.0, 12.5, 14.0];
+        let volumes = [
+            900.0, 800.0, 100.0, 200.0, 100.0, 300.0, 100.0, 200.0, 400.0,
+        ];
+
+        // Test case with an anchor, which ignores the bars before it
+        let anchored =
+            calculate_anchored_vw_macd(&prices, &volumes, 2, 2, 3, 2, MovingAverageKind::Ema)
+                .unwrap();
+        let expected =
+            calculate_vw_macd(&prices[2..], &volumes[2..], 2, 3, 2, MovingAverageKind::Ema)
+                .unwrap();
+        assert_eq!(anchored, expected);
+        let unanchored =
+            calculate_vw_macd(&prices, &volumes, 2, 3, 2, MovingAverageKind::Ema).unwrap();
+        assert_ne!(anchored.0.last().unwrap(), unanchored.0.last().unwrap());
+
+        // Test case with an anchor at the first bar
+        let result =
+            calculate_anchored_vw_macd(&prices, &volumes, 0, 2, 3, 2, MovingAverageKind::Ema)
+                .unwrap();
+        assert_eq!(result, unanchored);
+
+        // Test case with too few bars after the anchor
+        let result =
+            calculate_anchored_vw_macd(&prices, &volumes, 6, 2, 3, 2, MovingAverageKind::Sma);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with an anchor past the last bar
+        let result =
+            calculate_anchored_vw_macd(&prices, &volumes, 9, 2, 3, 2, MovingAverageKind::Sma);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_vw_rsi() {
+        // Test case with enough data
+        let prices = [10.0, 11.0, 10.5, 12.0, 13.0, 12.5, 14.0];
+        let volumes = [100.0, 200.0, 100.0, 300.0, 100.0, 200.0, 400.0];
+        let result = calculate_vw_rsi(&prices, &volumes, 3).unwrap();
+        let expected = [96.36363636363636, 96.875, 80.0, 92.21105527638191];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+
+        // Constant volume gives the plain RSI
+        let result = calculate_vw_rsi(&prices, &[5.0; 7], 3).unwrap();
+        let expected = calculate_rsi(&prices, 3).unwrap();
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_vw_rsi(&prices, &volumes, 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+        let result = calculate_vw_rsi(&prices, &volumes, 0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_obv() {
+        // Test case with enough data
+        let close = [10.0, 11.0, 10.5, 10.5, 12.0];
+        let volumes = [100.0, 200.0, 150.0, 300.0, 250.0];
+        let result = calculate_obv(&close, &volumes).unwrap();
+        assert_eq!(result, vec![0.0, 200.0, 50.0, 50.0, 300.0]);
+
+        // Test case with not enough data
+        let result = calculate_obv(&[], &[]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_obv(&close, &volumes[1..]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_vwap() {
+        // Test case with enough data
+        let high = [11.0, 12.0, 13.0];
+        let low = [9.0, 10.0, 11.0];
+        let close = [10.0, 11.0, 12.0];
+        let volumes = [100.0, 300.0, 0.0];
+        let result = calculate_vwap(&high, &low, &close, &volumes).unwrap();
+        assert_eq!(result, vec![10.0, 10.75, 10.75]);
+
+        // Test case with mismatched lengths
+        let result = calculate_vwap(&high, &low, &close, &volumes[1..]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_rolling_vwap() {
+        // Test case with enough data
+        let high = [11.0, 12.0, 13.0, 14.0, 15.0];
+        let low = [9.0, 10.0, 11.0, 12.0, 13.0];
+        let close = [10.0, 11.0, 12.0, 13.0, 14.0];
+        let volumes = [100.0, 300.0, 100.0, 0.0, 0.0];
+        let result = calculate_rolling_vwap(&high, &low, &close, &volumes, 2).unwrap();
+        assert_eq!(result[..3], [10.75, 11.25, 12.0]);
+        assert!(result[3].is_nan());
+
+        // Test case with not enough data
+        let result = calculate_rolling_vwap(&high, &low, &close, &volumes, 6);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+        let result = calculate_rolling_vwap(&high, &low, &close, &volumes, 0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_rolling_vwap(&high, &low, &close, &volumes[1..], 2);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_anchored_vwap() {
+        // Test case with enough data
+        let timestamps = [86_400 - 60, 86_400, 86_400 + 60];
+        let high = [11.0, 12.0, 13.0];
+        let low = [9.0, 10.0, 11.0];
+        let close = [10.0, 11.0, 12.0];
+        let volumes = [100.0, 300.0, 100.0];
+        let result = calculate_anchored_vwap(
+            &timestamps,
+            &high,
+            &low,
+            &close,
+            &volumes,
+            resample::Frequency::Daily,
+        )
+        .unwrap();
+        assert_eq!(result, vec![10.0, 11.0, 11.25]);
+
+        // Test case with mismatched lengths
+        let result = calculate_anchored_vwap(
+            &timestamps[1..],
+            &high,
+            &low,
+            &close,
+            &volumes,
+            resample::Frequency::Daily,
+        );
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_true_range() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.5, 11.0];
+        let low = [9.0, 10.5, 11.0, 8.0];
+        let close = [9.5, 10.8, 12.0, 8.5];
+        let result = calculate_true_range(&high, &low, &close).unwrap();
+        let expected = [1.0, 1.5, 1.7, 4.0];
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        assert_eq!(result.len(), 4);
+
+        // Test case with mismatched lengths
+        let result = calculate_true_range(&high, &low[1..], &close);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_atr() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.5, 11.0];
+        let low = [9.0, 10.5, 11.0, 8.0];
+        let close = [9.5, 10.8, 12.0, 8.5];
+        let result = calculate_atr(&high, &low, &close, 3).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!((result[0] - 4.2 / 3.0).abs() < 1e-12);
+        assert!((result[1] - (4.2 / 3.0 * 2.0 + 4.0) / 3.0).abs() < 1e-12);
+
+        // Test case with not enough data
+        let result = calculate_atr(&high, &low, &close, 5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_keltner() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.5, 11.0, 12.0];
+        let low = [9.0, 10.5, 11.0, 8.0, 10.0];
+        let close = [9.5, 10.8, 12.0, 8.5, 11.5];
+        let result = calculate_keltner(&high, &low, &close, 2, 3, 2.0).unwrap();
+        let ema = calculate_ema(&close, 2).unwrap();
+        let atr = calculate_atr(&high, &low, &close, 3).unwrap();
+        assert_eq!(result.middle, ema[1..]);
+        assert_eq!(result.upper.len(), 3);
+        for i in 0..3 {
+            assert!((result.upper[i] - (ema[i + 1] + 2.0 * atr[i])).abs() < 1e-12);
+            assert!((result.lower[i] - (ema[i + 1] - 2.0 * atr[i])).abs() < 1e-12);
+        }
+        let flat = calculate_keltner(&[11.0; 3], &[9.0; 3], &[10.0; 3], 2, 2, 1.5).unwrap();
+        assert_eq!(flat.upper, [13.0, 13.0]);
+        assert_eq!(flat.lower, [7.0, 7.0]);
+        let sma =
+            calculate_keltner_with(&high, &low, &close, 4, 2, 1.0, MovingAverageKind::Sma).unwrap();
+        assert_eq!(sma.middle, calculate_sma(&close, 4).unwrap());
+
+        // Test case with not enough data
+        let result = calculate_keltner(&high, &low, &close, 2, 6, 2.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid input
+        let result = calculate_keltner(&high, &low, &close, 2, 3, -1.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_ultimate_oscillator() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 13.0, 12.0];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.0, 11.0];
+        let close = [9.5, 10.5, 11.5, 10.5, 12.0, 12.5, 11.5];
+        let result = calculate_ultimate_oscillator(&high, &low, &close, 1, 2, 4).unwrap();
+        let expected = [
+            67.974_882_260_596_53,
+            55.952_380_952_380_95,
+            37.619_047_619_047_61,
+        ];
+        assert_eq!(result.len(), 3);
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_ultimate_oscillator(&high, &low, &close, 1, 2, 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_ultimate_oscillator(&high, &low, &close[1..], 1, 2, 4);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_supertrend() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 11.0, 10.0, 9.5, 10.5, 11.5, 12.5];
+        let low = [9.0, 10.0, 11.0, 10.5, 10.0, 9.0, 8.5, 9.5, 10.5, 11.5];
+        let close = [9.5, 10.8, 11.7, 10.8, 10.2, 9.2, 9.0, 10.4, 11.3, 12.2];
+        let result = calculate_supertrend(&high, &low, &close, 3, 1.0).unwrap();
+        let expected_upper = [
+            12.733333333333333,
+            12.222222222222221,
+            11.648148148148149,
+            10.665432098765432,
+            10.110288065843621,
+            10.110288065843621,
+            12.193461362597166,
+            12.193461362597166,
+        ];
+        let expected_lower = [
+            10.266666666666667,
+            10.266666666666667,
+            10.266666666666667,
+            8.334567901234568,
+            8.334567901234568,
+            8.759807956104252,
+            9.806538637402834,
+            10.80435909160189,
+        ];
+        let expected_supertrend = [
+            10.266666666666667,
+            10.266666666666667,
+            11.648148148148149,
+            10.665432098765432,
+            10.110288065843621,
+            8.759807956104252,
+            9.806538637402834,
+            10.80435909160189,
+        ];
+        assert_eq!(result.upper.len(), expected_upper.len());
+        for i in 0..expected_upper.len() {
+            assert!((result.upper[i] - expected_upper[i]).abs() < 1e-9);
+            assert!((result.lower[i] - expected_lower[i]).abs() < 1e-9);
+            assert!((result.supertrend[i] - expected_supertrend[i]).abs() < 1e-9);
+        }
+        assert_eq!(result.direction, vec![1, 1, -1, -1, -1, 1, 1, 1]);
+
+        // Test case with not enough data
+        let result = calculate_supertrend(&high[..2], &low[..2], &close[..2], 3, 1.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_supertrend(&high, &low[..9], &close, 3, 1.0);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let result = calculate_supertrend(&high, &low, &close, 3, -1.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_stochastic() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 13.0];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.0];
+        let close = [9.5, 10.5, 11.5, 10.5, 12.0, 12.5];
+        let fast = calculate_stochastic(&high, &low, &close, 3, 1, 2).unwrap();
+        let raw_k = [250.0 / 3.0, 40.0, 80.0, 250.0 / 3.0];
+        assert_eq!(fast.k.len(), 3);
+        for i in 0..3 {
+            assert!((fast.k[i] - raw_k[i + 1]).abs() < 1e-9);
+            assert!((fast.d[i] - (raw_k[i] + raw_k[i + 1]) / 2.0).abs() < 1e-9);
+        }
+        let slow = calculate_stochastic(&high, &low, &close, 3, 2, 2).unwrap();
+        assert_eq!(slow.k.len(), 2);
+        assert!((slow.k[1] - (80.0 + 250.0 / 3.0) / 2.0).abs() < 1e-9);
+        assert!((slow.d[0] - fast.d[0] / 2.0 - fast.d[1] / 2.0).abs() < 1e-9);
+
+        // Test case with a flat window
+        let high = [10.0, 10.0, 10.0, 11.0, 12.0, 13.0];
+        let low = [10.0, 10.0, 10.0, 10.0, 11.0, 12.0];
+        let close = [10.0, 10.0, 10.0, 11.0, 11.5, 12.5];
+        let flat = calculate_stochastic(&high, &low, &close, 3, 1, 1).unwrap();
+        assert!((flat.k[0] - 50.0).abs() < 1e-9);
+        for kind in [MovingAverageKind::Ema, MovingAverageKind::Rma] {
+            let result = calculate_stochastic_with(&high, &low, &close, 3, 2, 2, kind).unwrap();
+            assert!(result.k.iter().chain(&result.d).all(|v| v.is_finite()));
+        }
+
+        // Test case with not enough data
+        let result = calculate_stochastic(&[10.0; 6], &[9.0; 6], &[9.5; 6], 3, 3, 3);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_stochastic(&high[1..], &low, &close, 3, 1, 1);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_williams_r() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 12.5];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.5];
+        let close = [9.5, 10.5, 11.5, 10.0, 12.5, 12.5];
+        let result = calculate_williams_r(&high, &low, &close, 3).unwrap();
+        assert_eq!(result.len(), 4);
+        assert!((result[0] - -100.0 / 6.0).abs() < 1e-9);
+        assert!((result[1] - -80.0).abs() < 1e-9);
+        assert!(result[2].abs() < 1e-9);
+        assert!(result[3].abs() < 1e-9);
+        let flat = calculate_williams_r(&[1.0; 2], &[1.0; 2], &[1.0; 2], 2).unwrap();
+        assert!(flat[0].is_nan());
+
+        // Test case with not enough data
+        let result = calculate_williams_r(&high, &low, &close, 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_williams_r(&high, &low[1..], &close, 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_aroon() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 13.0, 12.0];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.0, 11.0];
+        let result = calculate_aroon(&high, &low, 3).unwrap();
+        let third = 100.0 / 3.0;
+        let expected_up = [2.0 * third, 100.0, 100.0, 2.0 * third];
+        let expected_down = [0.0, 0.0, third, 0.0];
+        assert_eq!(result.up.len(), 4);
+        for i in 0..4 {
+            assert!((result.up[i] - expected_up[i]).abs() < 1e-9);
+            assert!((result.down[i] - expected_down[i]).abs() < 1e-9);
+            assert!((result.oscillator[i] - (expected_up[i] - expected_down[i])).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_aroon(&high, &low, 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_aroon(&high, &low[1..], 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_donchian() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 13.0, 12.0];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.0, 11.0];
+        let result = calculate_donchian(&high, &low, 3).unwrap();
+        assert_eq!(result.upper, [12.0, 12.0, 12.5, 13.0, 13.0]);
+        assert_eq!(result.lower, [9.0, 9.5, 10.0, 10.0, 11.0]);
+        assert_eq!(result.middle, [10.5, 10.75, 11.25, 11.5, 12.0]);
+
+        // Test case with not enough data
+        let result = calculate_donchian(&high, &low, 8);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_donchian(&high[1..], &low, 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_adx() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 13.0, 12.0];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.0, 11.0];
+        let close = [9.5, 10.5, 11.5, 10.5, 12.0, 12.5, 11.5];
+        let result = calculate_adx(&high, &low, &close, 3).unwrap();
+        let expected = [
+            (
+                71.829_268_292_682_93,
+                47.435_897_435_897_44,
+                5.128_205_128_205_128,
+            ),
+            (
+                50.827_355_332_376_854,
+                31.223_628_691_983_123,
+                26.160_337_552_742_61,
+            ),
+        ];
+        assert_eq!(result.adx.len(), 2);
+        for (i, (adx, plus_di, minus_di)) in expected.iter().enumerate() {
+            assert!((result.adx[i] - adx).abs() < 1e-9);
+            assert!((result.plus_di[i] - plus_di).abs() < 1e-9);
+            assert!((result.minus_di[i] - minus_di).abs() < 1e-9);
+        }
+
+        // Test case with a run of identical bars
+        let high = [10.0, 11.0, 11.0, 11.0, 11.0, 11.0, 11.0, 12.0, 13.0];
+        let low = [9.0, 11.0, 11.0, 11.0, 11.0, 11.0, 11.0, 11.0, 12.0];
+        let close = [9.5, 11.0, 11.0, 11.0, 11.0, 11.0, 11.0, 11.5, 12.5];
+        let result = calculate_adx(&high[1..7], &low[1..7], &close[1..7], 2).unwrap();
+        assert!(result.adx.iter().all(|adx| *adx == 0.0));
+        assert!(result.plus_di.iter().all(|di| *di == 0.0));
+        assert!(result.minus_di.iter().all(|di| *di == 0.0));
+        let result = calculate_adx(&high, &low, &close, 2).unwrap();
+        assert!(result.adx.iter().all(|adx| adx.is_finite()));
+        assert!(result.plus_di.iter().all(|di| di.is_finite()));
+        assert!(*result.adx.last().unwrap() > 0.0);
+
+        // Test case with not enough data
+        let result = calculate_adx(&high[..5], &low[..5], &close[..5], 3);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_adx(&high, &low, &close[1..], 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_cci() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0];
+        let close = [9.5, 10.5, 11.5, 10.5, 12.0];
+        let result = calculate_cci(&high, &low, &close, 3).unwrap();
+        let expected = [100.0, -20.0, 1_000.0 / 11.0];
+        assert_eq!(result.len(), 3);
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_cci(&high, &low, &close, 6);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_cci(&high, &low[1..], &close, 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_mfi() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0];
+        let close = [9.5, 10.5, 11.5, 10.5, 12.0];
+        let volumes = [100.0, 200.0, 150.0, 300.0, 250.0];
+        let result = calculate_mfi(&high, &low, &close, &volumes, 3).unwrap();
+        let expected = [54.066_985_645_933_016, 59.278_897_136_797_46];
+        assert_eq!(result.len(), 2);
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+        let rising = calculate_mfi(&high[..3], &low[..3], &close[..3], &volumes[..3], 2).unwrap();
+        assert_eq!(rising, [100.0]);
+        let flat = calculate_mfi(&[1.0; 3], &[1.0; 3], &[1.0; 3], &volumes[..3], 2).unwrap();
+        assert!(flat[0].is_nan());
+
+        // Test case with not enough data
+        let result = calculate_mfi(&high, &low, &close, &volumes, 5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_mfi(&high, &low, &close, &volumes[1..], 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_cmf() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0];
+        let close = [9.5, 10.5, 12.0, 10.0, 12.5];
+        let volumes = [100.0, 200.0, 150.0, 300.0, 0.0];
+        let result = calculate_cmf(&high, &low, &close, &volumes, 3).unwrap();
+        let expected = [
+            (200.0 / 3.0 + 150.0) / 450.0,
+            (200.0 / 3.0 + 150.0 - 300.0) / 650.0,
+            (150.0 - 300.0) / 450.0,
+        ];
+        assert_eq!(result.len(), 3);
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+        let flat = calculate_cmf(&[1.0; 2], &[1.0; 2], &[1.0; 2], &[5.0; 2], 2).unwrap();
+        assert_eq!(flat, [0.0]);
+
+        // Test case with not enough data
+        let result = calculate_cmf(&high, &low, &close, &volumes, 6);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_cmf(&high, &low, &close, &volumes[1..], 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_adl() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0];
+        let close = [9.5, 10.5, 12.0, 10.0, 12.5];
+        let volumes = [100.0, 300.0, 150.0, 300.0, 50.0];
+        let result = calculate_adl(&high, &low, &close, &volumes).unwrap();
+        assert_eq!(result, [0.0, 100.0, 250.0, -50.0, 0.0]);
+
+        // Test case with mismatched lengths
+        let result = calculate_adl(&high, &low, &close, &volumes[1..]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_chaikin_oscillator() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0];
+        let close = [9.5, 10.5, 12.0, 10.0, 12.5];
+        let volumes = [100.0, 300.0, 150.0, 300.0, 50.0];
+        let result = calculate_chaikin_oscillator(&high, &low, &close, &volumes, 2, 3).unwrap();
+        let adl = [0.0, 100.0, 250.0, -50.0, 0.0];
+        let fast = calculate_ema(&adl, 2).unwrap();
+        let slow = calculate_ema(&adl, 3).unwrap();
+        assert_eq!(result.len(), 3);
+        for i in 0..3 {
+            assert!((result[i] - (fast[i + 1] - slow[i])).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_chaikin_oscillator(&high, &low, &close, &volumes, 3, 10);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_ichimoku() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 11.5, 12.5, 13.0];
+        let low = [9.0, 9.5, 10.5, 10.0, 11.0, 12.0];
+        let close = [9.5, 10.5, 11.5, 10.5, 12.0, 12.5];
+        let result = calculate_ichimoku(&high, &low, &close, 2, 3, 4, 2).unwrap();
+        assert_eq!(result.tenkan.len(), 6);
+        assert!(result.tenkan[0].is_nan());
+        assert_eq!(result.tenkan[1..], [10.0, 10.75, 11.0, 11.25, 12.0]);
+        assert!(result.kijun[1].is_nan());
+        assert_eq!(result.kijun[2..], [10.5, 10.75, 11.25, 11.5]);
+
+        // The leading spans belong to the bars two ahead of the bars they are computed on
+        assert_eq!(result.senkou_a.len(), 8);
+        assert!(result.senkou_a[3].is_nan());
+        assert_eq!(result.senkou_a[4..], [10.625, 10.875, 11.25, 11.75]);
+        assert_eq!(result.senkou_b.len(), 8);
+        assert!(result.senkou_b[4].is_nan());
+        assert_eq!(result.senkou_b[5..], [10.5, 11.0, 11.5]);
+
+        // The Chikou Span belongs to the bar two behind the close
+        assert_eq!(result.chikou[..4], [11.5, 10.5, 12.0, 12.5]);
+        assert!(result.chikou[4..].iter().all(|c| c.is_nan()));
+
+        // Test case with not enough data
+        let result = calculate_ichimoku(&high, &low, &close, 2, 3, 7, 2);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_ichimoku(&high, &low[1..], &close, 2, 3, 4, 2);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_psar() {
+        // Test case with enough data
+        let high = [10.0, 11.0, 12.0, 12.5, 12.2, 11.0, 10.5];
+        let low = [9.0, 10.0, 11.0, 11.5, 11.0, 9.5, 9.2];
+        let result = calculate_psar(&high, &low, 0.02, 0.02, 0.2).unwrap();
+        let expected = [9.0, 9.0, 9.12, 9.3228, 12.5, 12.44];
+        assert_eq!(result.sar.len(), 6);
+        for (value, expected) in result.sar.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+        assert_eq!(result.direction, vec![1, 1, 1, 1, -1, -1]);
+
+        // A larger starting factor accelerates every trend from its first bar
+        let result = calculate_psar(&high, &low, 0.04, 0.02, 0.2).unwrap();
+        let expected = [9.0, 9.0, 9.18, 9.4456, 12.5, 12.38];
+        for (value, expected) in result.sar.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_psar(&high[..1], &low[..1], 0.02, 0.02, 0.2);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid input
+        let result = calculate_psar(&high, &low, 0.02, 0.02, 0.01);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
             }
         }
     }
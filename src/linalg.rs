@@ -0,0 +1,78 @@
+//! Minimal dense linear algebra helpers shared by the regression based analytics.
+
+/// Solves the square linear system `a * x = b` using Gaussian elimination with partial pivoting.
+///
+/// # Arguments
+///
+/// * `a` - The coefficient matrix, stored as a vector of rows.
+/// * `b` - The right hand side of the system.
+///
+/// # Returns
+///
+/// The solution vector `x`, or `None` if `a` is singular or too close to singular to be solved
+/// reliably.
+pub(crate) fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    let scale = a.iter().flatten().fold(0.0_f64, |max, x| max.max(x.abs()));
+    if scale == 0.0 {
+        return None;
+    }
+
+    // Forward elimination
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= scale * 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (x, p) in row.iter_mut().zip(pivot_row).skip(col) {
+                *x -= factor * p;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+
+    // Back substitution
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let known = a[row][row + 1..]
+            .iter()
+            .zip(&x[row + 1..])
+            .map(|(a, x)| a * x)
+            .sum::<f64>();
+        x[row] = (b[row] - known) / a[row][row];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        // Test case with a well conditioned system
+        let a = vec![
+            vec![2.0, 1.0, -1.0],
+            vec![-3.0, -1.0, 2.0],
+            vec![-2.0, 1.0, 2.0],
+        ];
+        let b = vec![8.0, -11.0, -3.0];
+        let x = solve(a, b).unwrap();
+        for (actual, expected) in x.iter().zip([2.0, 3.0, -1.0]) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Test case with a singular system
+        let a = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        let b = vec![1.0, 2.0];
+        assert!(solve(a, b).is_none());
+    }
+}
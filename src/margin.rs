@@ -0,0 +1,266 @@
+//! Calculators for leveraged positions in linear (quote currency margined) contracts.
+//!
+//! Both isolated and cross margin positions are supported through the `margin` argument: for an
+//! isolated position it is the collateral assigned to the position, while for a cross margin
+//! position it is the account equity available to back the position.
+
+use crate::IndicatorError;
+
+/// Direction of a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// A position that profits when the price rises
+    Long,
+    /// A position that profits when the price falls
+    Short,
+}
+
+/// Checks that every named value is strictly positive.
+fn require_positive(values: &[(&str, f64)]) -> Result<(), IndicatorError> {
+    match values
+        .iter()
+        .find(|(_, value)| value.is_nan() || *value <= 0.0)
+    {
+        Some((name, _)) => Err(IndicatorError::InvalidInput(format!(
+            "`{}` must be positive",
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Calculates the price at which a margin position is liquidated.
+///
+/// Liquidation occurs once the position equity, `margin` plus unrealized profit, falls to the
+/// maintenance margin plus the fee due on closing the position, both charged on the notional
+/// value at the liquidation price.
+///
+/// # Arguments
+///
+/// * `side` - The direction of the position.
+/// * `entry_price` - The average entry price of the position.
+/// * `quantity` - The size of the position in contracts.
+/// * `margin` - The collateral backing the position.
+/// * `maintenance_rate` - The maintenance margin as a fraction of notional value.
+/// * `fee_rate` - The closing fee as a fraction of notional value.
+///
+/// # Returns
+///
+/// A Result containing the liquidation price or an `IndicatorError`. A long position whose
+/// margin covers its whole notional value cannot be liquidated and yields `0.0`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `entry_price` or `quantity` is not positive,
+/// `margin`, `maintenance_rate` or `fee_rate` is negative or `NaN`, or `maintenance_rate` and
+/// `fee_rate` add up to 1 or more, which leaves no equity to liquidate a long at.
+pub fn calculate_liquidation_price(
+    side: Side,
+    entry_price: f64,
+    quantity: f64,
+    margin: f64,
+    maintenance_rate: f64,
+    fee_rate: f64,
+) -> Result<f64, IndicatorError> {
+    require_positive(&[("entry_price", entry_price), ("quantity", quantity)])?;
+    if [margin, maintenance_rate, fee_rate]
+        .iter()
+        .any(|v| v.is_nan() || *v < 0.0)
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`margin`, `maintenance_rate`, and `fee_rate` must not be negative".to_string(),
+        ));
+    }
+    if maintenance_rate + fee_rate >= 1.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`maintenance_rate` and `fee_rate` must add up to less than 1".to_string(),
+        ));
+    }
+
+    let margin_per_contract = margin / quantity;
+    let price = match side {
+        Side::Long => (entry_price - margin_per_contract) / (1.0 - maintenance_rate - fee_rate),
+        Side::Short => (entry_price + margin_per_contract) / (1.0 + maintenance_rate + fee_rate),
+    };
+    Ok(price.max(0.0))
+}
+
+/// Calculates the margin ratio of a position, the maintenance margin divided by the position
+/// equity at the current mark price.
+///
+/// A ratio of `1.0` or more means the position is due for liquidation.
+///
+/// # Arguments
+///
+/// * `side` - The direction of the position.
+/// * `entry_price` - The average entry price of the position.
+/// * `quantity` - The size of the position in contracts.
+/// * `margin` - The collateral backing the position.
+/// * `mark_price` - The current mark price.
+/// * `maintenance_rate` - The maintenance margin as a fraction of notional value.
+///
+/// # Returns
+///
+/// A Result containing the margin ratio or an `IndicatorError`. The ratio is `f64::INFINITY` once
+/// the position equity is exhausted.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `entry_price`, `quantity`, `margin` or
+/// `mark_price` is not positive, or `maintenance_rate` is not within `[0, 1)`.
+pub fn calculate_margin_ratio(
+    side: Side,
+    entry_price: f64,
+    quantity: f64,
+    margin: f64,
+    mark_price: f64,
+    maintenance_rate: f64,
+) -> Result<f64, IndicatorError> {
+    require_positive(&[
+        ("entry_price", entry_price),
+        ("quantity", quantity),
+        ("margin", margin),
+        ("mark_price", mark_price),
+    ])?;
+    if !(0.0..1.0).contains(&maintenance_rate) {
+        return Err(IndicatorError::InvalidInput(
+            "`maintenance_rate` must be within [0, 1)".to_string(),
+        ));
+    }
+
+    let unrealized = match side {
+        Side::Long => quantity * (mark_price - entry_price),
+        Side::Short => quantity * (entry_price - mark_price),
+    };
+    let equity = margin + unrealized;
+    let maintenance = maintenance_rate * quantity * mark_price;
+    Ok(if equity > 0.0 {
+        maintenance / equity
+    } else {
+        f64::INFINITY
+    })
+}
+
+/// Calculates the largest position that can be opened with the available balance.
+///
+/// The balance has to cover both the initial margin, the notional value divided by `leverage`, and
+/// the opening fee charged on the notional value.
+///
+/// # Arguments
+///
+/// * `balance` - The balance available for the position.
+/// * `price` - The expected entry price.
+/// * `leverage` - The leverage applied to the position.
+/// * `fee_rate` - The opening fee as a fraction of notional value.
+///
+/// # Returns
+///
+/// A Result containing the maximum position size in contracts or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `price` or `leverage` is not positive, or
+/// `balance` or `fee_rate` is negative.
+pub fn calculate_max_position_size(
+    balance: f64,
+    price: f64,
+    leverage: f64,
+    fee_rate: f64,
+) -> Result<f64, IndicatorError> {
+    require_positive(&[("price", price), ("leverage", leverage)])?;
+    if balance < 0.0 || fee_rate < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`balance` and `fee_rate` must not be negative".to_string(),
+        ));
+    }
+
+    Ok(balance / (price * (1.0 / leverage + fee_rate)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_liquidation_price() {
+        // Test case with a 10x isolated long and short
+        let result = calculate_liquidation_price(Side::Long, 100.0, 2.0, 20.0, 0.005, 0.0005);
+        assert!((result.unwrap() - 90.49773755656108).abs() < 1e-9);
+        let result = calculate_liquidation_price(Side::Short, 100.0, 2.0, 20.0, 0.005, 0.0005);
+        assert!((result.unwrap() - 109.3983092988563).abs() < 1e-9);
+
+        // Test case with a fully collateralized long
+        let result = calculate_liquidation_price(Side::Long, 100.0, 2.0, 200.0, 0.005, 0.0);
+        assert_eq!(result.unwrap(), 0.0);
+
+        // Test case with invalid inputs
+        for (quantity, margin, maintenance_rate, fee_rate) in [
+            (0.0, 20.0, 0.005, 0.0),
+            (2.0, f64::NAN, 0.005, 0.0),
+            (2.0, 20.0, f64::NAN, 0.0),
+            (2.0, 20.0, 0.995, 0.005),
+            (2.0, 20.0, 1.5, 0.0),
+        ] {
+            let result = calculate_liquidation_price(
+                Side::Long,
+                100.0,
+                quantity,
+                margin,
+                maintenance_rate,
+                fee_rate,
+            );
+            match result {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_margin_ratio() {
+        // Test case with a losing long
+        let result = calculate_margin_ratio(Side::Long, 100.0, 2.0, 20.0, 95.0, 0.005);
+        assert!((result.unwrap() - 0.095).abs() < 1e-12);
+
+        // Test case with exhausted equity
+        let result = calculate_margin_ratio(Side::Short, 100.0, 2.0, 20.0, 110.0, 0.005);
+        assert_eq!(result.unwrap(), f64::INFINITY);
+
+        // Test case with invalid inputs
+        for (margin, maintenance_rate) in [
+            (0.0, 0.005),
+            (-20.0, 0.005),
+            (f64::NAN, 0.005),
+            (20.0, -0.005),
+            (20.0, 1.0),
+            (20.0, f64::NAN),
+        ] {
+            let result =
+                calculate_margin_ratio(Side::Long, 100.0, 2.0, margin, 95.0, maintenance_rate);
+            match result {
+                Err(IndicatorError::InvalidInput(_)) => (),
+                _ => panic!(
+                    "Expected `IndicatorError::InvalidInput`, found different `IndicatorError`"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_max_position_size() {
+        // Test case with enough data
+        let result = calculate_max_position_size(1_000.0, 50.0, 10.0, 0.0005);
+        assert!((result.unwrap() - 199.0049751243781).abs() < 1e-9);
+
+        // Test case with invalid leverage
+        let result = calculate_max_position_size(1_000.0, 50.0, 0.0, 0.0005);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
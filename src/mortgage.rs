@@ -0,0 +1,219 @@
+//! Comparison of fixed rate mortgage scenarios, including refinancing an existing loan.
+//!
+//! Loans amortize monthly with payments at the end of every month, priced with the annuity
+//! functions of [`crate::excel`]. Costs are summed without discounting, as in the break-even
+//! figures quoted by lenders.
+
+use crate::excel;
+use crate::IndicatorError;
+
+/// A fixed rate loan amortized in equal monthly payments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loan {
+    principal: f64,
+    annual_rate: f64,
+    months: usize,
+    upfront_cost: f64,
+    payment: f64,
+}
+
+impl Loan {
+    /// Creates a loan, or the remainder of an existing loan with its balance as `principal` and
+    /// no upfront costs.
+    ///
+    /// # Arguments
+    ///
+    /// * `principal` - The amount borrowed.
+    /// * `annual_rate` - The nominal annual interest rate, e.g. `0.06` for 6%, compounded monthly.
+    /// * `months` - The number of monthly payments.
+    /// * `points` - The discount points paid upfront as a fraction of `principal`, e.g. `0.01` for
+    ///   one point.
+    /// * `closing_costs` - The other costs paid upfront to take out the loan.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the `Loan` or an `IndicatorError`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `principal` or `months` is not positive, or
+    /// `annual_rate`, `points`, or `closing_costs` is negative.
+    pub fn new(
+        principal: f64,
+        annual_rate: f64,
+        months: usize,
+        points: f64,
+        closing_costs: f64,
+    ) -> Result<Self, IndicatorError> {
+        if principal.is_nan() || principal <= 0.0 || months == 0 {
+            return Err(IndicatorError::InvalidInput(
+                "`principal` and `months` must be positive".to_string(),
+            ));
+        }
+        if [annual_rate, points, closing_costs]
+            .iter()
+            .any(|v| v.is_nan() || *v < 0.0)
+        {
+            return Err(IndicatorError::InvalidInput(
+                "`annual_rate`, `points`, and `closing_costs` must not be negative".to_string(),
+            ));
+        }
+        let payment = -excel::pmt(annual_rate / 12.0, months as f64, principal, 0.0, 0)?;
+        Ok(Loan {
+            principal,
+            annual_rate,
+            months,
+            upfront_cost: principal * points + closing_costs,
+            payment,
+        })
+    }
+
+    /// Returns the amount borrowed.
+    pub fn principal(&self) -> f64 {
+        self.principal
+    }
+
+    /// Returns the number of monthly payments.
+    pub fn months(&self) -> usize {
+        self.months
+    }
+
+    /// Returns the points and closing costs paid upfront.
+    pub fn upfront_cost(&self) -> f64 {
+        self.upfront_cost
+    }
+
+    /// Returns the monthly payment.
+    pub fn monthly_payment(&self) -> f64 {
+        self.payment
+    }
+
+    /// Returns the balance left after `month` payments, which is zero once the loan is repaid.
+    pub fn balance_after(&self, month: usize) -> f64 {
+        if month >= self.months {
+            return 0.0;
+        }
+        // `fv` only fails for a payment type other than 0 or 1 or a balance that is not finite
+        excel::fv(
+            self.annual_rate / 12.0,
+            month as f64,
+            -self.payment,
+            self.principal,
+            0,
+        )
+        .map_or(f64::NAN, |fv| -fv)
+    }
+
+    /// Returns the cost of the loan if it is paid off after `month` payments: the upfront costs,
+    /// the payments made, and the balance left.
+    pub fn cost_through(&self, month: usize) -> f64 {
+        self.upfront_cost + self.payment * month.min(self.months) as f64 + self.balance_after(month)
+    }
+
+    /// Returns the upfront costs and all payments over the life of the loan.
+    pub fn lifetime_cost(&self) -> f64 {
+        self.cost_through(self.months)
+    }
+
+    /// Returns the interest paid over the life of the loan.
+    pub fn total_interest(&self) -> f64 {
+        self.payment * self.months as f64 - self.principal
+    }
+}
+
+/// How an alternative loan compares with the current one, produced by [`compare_loans`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoanComparison {
+    /// Monthly payment of the current minus that of the alternative loan
+    pub monthly_savings: f64,
+    /// Savings of the alternative loan if both are paid off after each month, from month 0 to
+    /// the end of the longer loan
+    pub cumulative_savings: Vec<f64>,
+    /// First month from which the alternative loan stays no more expensive, if any
+    pub break_even_month: Option<usize>,
+    /// Lifetime cost of the current minus that of the alternative loan
+    pub lifetime_savings: f64,
+}
+
+/// Compares an alternative loan with the current one, such as a refinance with points and closing
+/// costs against the remainder of an existing mortgage.
+///
+/// The savings after a month compare the costs of paying off both loans then, including their
+/// remaining balances. An alternative with a lower rate repays principal faster, so it breaks
+/// even earlier than the upfront costs divided by the monthly savings, and a shorter term can
+/// break even despite a higher payment.
+///
+/// # Arguments
+///
+/// * `current` - The current loan, or the first scenario.
+/// * `alternative` - The alternative loan, or the second scenario.
+///
+/// # Returns
+///
+/// A [`LoanComparison`] of the two loans.
+pub fn compare_loans(current: &Loan, alternative: &Loan) -> LoanComparison {
+    let horizon = current.months.max(alternative.months);
+    let cumulative_savings = (0..=horizon)
+        .map(|month| current.cost_through(month) - alternative.cost_through(month))
+        .collect::<Vec<f64>>();
+    LoanComparison {
+        monthly_savings: current.payment - alternative.payment,
+        break_even_month: match cumulative_savings.iter().rposition(|s| *s < 0.0) {
+            Some(month) if month == horizon => None,
+            Some(month) => Some(month + 1),
+            None => Some(0),
+        },
+        lifetime_savings: current.lifetime_cost() - alternative.lifetime_cost(),
+        cumulative_savings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loan() {
+        // Test case with enough data
+        let loan = Loan::new(200_000.0, 0.05, 360, 0.01, 3_000.0).unwrap();
+        assert!((loan.monthly_payment() - 1_073.643_246_024_279_7).abs() < 1e-9);
+        assert_eq!(loan.upfront_cost(), 5_000.0);
+        assert!((loan.balance_after(0) - 200_000.0).abs() < 1e-9);
+        assert_eq!(loan.balance_after(360), 0.0);
+        assert!((loan.lifetime_cost() - 391_511.568_568_740_7).abs() < 1e-6);
+        assert!((loan.total_interest() - 186_511.568_568_740_7).abs() < 1e-6);
+
+        // Test case with invalid input
+        let result = Loan::new(200_000.0, 0.05, 0, 0.01, 3_000.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_compare_loans() {
+        let current = Loan::new(200_000.0, 0.06, 360, 0.0, 0.0).unwrap();
+        let refinance = Loan::new(200_000.0, 0.05, 360, 0.01, 3_000.0).unwrap();
+        let result = compare_loans(&current, &refinance);
+        assert!((result.monthly_savings - 125.457_804_281_234_1).abs() < 1e-9);
+        assert_eq!(result.cumulative_savings.len(), 361);
+        assert!((result.cumulative_savings[0] - -5_000.0).abs() < 1e-9);
+        assert_eq!(result.break_even_month, Some(31));
+        assert!((result.cumulative_savings[31] - 165.491_357_769_380_56).abs() < 1e-6);
+        assert!((result.lifetime_savings - 40_164.809_541_244_29).abs() < 1e-6);
+
+        // A shorter term breaks even despite the higher payment
+        let shorter = Loan::new(200_000.0, 0.05, 180, 0.01, 3_000.0).unwrap();
+        let result = compare_loans(&current, &shorter);
+        assert!(result.monthly_savings < 0.0);
+        assert_eq!(result.break_even_month, Some(26));
+        assert!((result.lifetime_savings - 141_990.672_483_028_26).abs() < 1e-6);
+
+        // An alternative that ends up more expensive has no break-even month
+        let result = compare_loans(&refinance, &current);
+        assert_eq!(result.break_even_month, None);
+    }
+}
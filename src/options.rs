@@ -0,0 +1,755 @@
+//! European option pricing with Black-Scholes and payoff analysis of multi-leg option strategies.
+
+use crate::special::normal_cdf;
+use crate::IndicatorError;
+
+/// The right an option grants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    /// The right to buy the underlying at the strike
+    Call,
+    /// The right to sell the underlying at the strike
+    Put,
+}
+
+impl OptionKind {
+    /// Returns the value of exercising the option at `spot`.
+    pub fn intrinsic(&self, spot: f64, strike: f64) -> f64 {
+        match self {
+            OptionKind::Call => (spot - strike).max(0.0),
+            OptionKind::Put => (strike - spot).max(0.0),
+        }
+    }
+}
+
+/// Calculates the Black-Scholes value of a European option on a non-dividend paying underlying.
+///
+/// Options at expiry or on an underlying without volatility are worth the intrinsic value of the
+/// forward, discounted to today.
+///
+/// # Arguments
+///
+/// * `kind` - Whether the option is a call or a put.
+/// * `spot` - The current price of the underlying.
+/// * `strike` - The strike price.
+/// * `years` - The time to expiry in years.
+/// * `rate` - The continuously compounded risk-free rate.
+/// * `volatility` - The annualized volatility of the underlying.
+///
+/// # Returns
+///
+/// A Result containing the value of one option or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `spot` or `strike` is not positive, or `years` or
+/// `volatility` is negative.
+pub fn black_scholes(
+    kind: OptionKind,
+    spot: f64,
+    strike: f64,
+    years: f64,
+    rate: f64,
+    volatility: f64,
+) -> Result<f64, IndicatorError> {
+    if !(spot > 0.0 && strike > 0.0 && years >= 0.0 && volatility >= 0.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`spot` and `strike` must be positive, `years` and `volatility` must not be negative"
+                .to_string(),
+        ));
+    }
+
+    let discount = (-rate * years).exp();
+    let deviation = volatility * years.sqrt();
+    if deviation == 0.0 {
+        return Ok(discount * kind.intrinsic(spot / discount, strike));
+    }
+    let d1 = ((spot / strike).ln() + (rate + volatility * volatility / 2.0) * years) / deviation;
+    let d2 = d1 - deviation;
+    Ok(match kind {
+        OptionKind::Call => spot * normal_cdf(d1) - strike * discount * normal_cdf(d2),
+        OptionKind::Put => strike * discount * normal_cdf(-d2) - spot * normal_cdf(-d1),
+    })
+}
+
+/// A distribution of the price of the underlying at expiry, used by [`OptionStrategy::outlook`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TerminalDistribution<'a> {
+    /// A lognormal distribution, as assumed by Black-Scholes
+    Lognormal {
+        /// Current price of the underlying
+        spot: f64,
+        /// Time to expiry in years
+        years: f64,
+        /// Expected continuously compounded growth rate of the underlying, the risk-free rate for
+        /// risk-neutral probabilities
+        drift: f64,
+        /// Annualized volatility of the underlying, e.g. an implied volatility
+        volatility: f64,
+    },
+    /// Equally likely prices at expiry, e.g. from a simulation or historical scenarios
+    Samples(&'a [f64]),
+}
+
+/// Returns the probability that a lognormal price at expiry is below `price`, where `deviation`
+/// is the volatility times the square root of the time to expiry and must be positive.
+fn lognormal_cdf(spot: f64, years: f64, drift: f64, deviation: f64, price: f64) -> f64 {
+    if price <= 0.0 {
+        return 0.0;
+    }
+    let mean = spot.ln() + drift * years - deviation * deviation / 2.0;
+    normal_cdf((price.ln() - mean) / deviation)
+}
+
+/// Probability of profit and expected value of an [`OptionStrategy`] at expiry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrategyOutlook {
+    /// Probability that the strategy ends with a profit
+    pub probability_of_profit: f64,
+    /// Expected profit or loss at expiry, not discounted
+    pub expected_value: f64,
+}
+
+/// One option position of an [`OptionStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionLeg {
+    /// Whether the option is a call or a put
+    pub kind: OptionKind,
+    /// Strike price
+    pub strike: f64,
+    /// Number of options, positive when bought and negative when sold
+    pub quantity: f64,
+    /// Price per option the position was opened at
+    pub premium: f64,
+}
+
+impl OptionLeg {
+    /// Creates a new option leg.
+    pub fn new(kind: OptionKind, strike: f64, quantity: f64, premium: f64) -> Self {
+        OptionLeg {
+            kind,
+            strike,
+            quantity,
+            premium,
+        }
+    }
+
+    /// Returns the profit or loss of the leg at expiry with the underlying at `spot`.
+    pub fn payoff(&self, spot: f64) -> f64 {
+        self.quantity * (self.kind.intrinsic(spot, self.strike) - self.premium)
+    }
+}
+
+/// A strategy of option legs on one underlying sharing one expiry, such as a vertical spread, a
+/// straddle, or an iron condor.
+///
+/// All values are profits and losses including the premiums paid and received, per unit of the
+/// underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionStrategy {
+    legs: Vec<OptionLeg>,
+}
+
+impl OptionStrategy {
+    /// Creates a strategy from its legs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `legs` is empty, or a strike is not positive
+    /// or a quantity or premium is not finite.
+    pub fn new(legs: Vec<OptionLeg>) -> Result<Self, IndicatorError> {
+        if legs.is_empty()
+            || legs.iter().any(|leg| {
+                !(leg.strike.is_finite()
+                    && leg.strike > 0.0
+                    && leg.quantity.is_finite()
+                    && leg.premium.is_finite())
+            })
+        {
+            return Err(IndicatorError::InvalidInput(
+                "A strategy needs legs with positive strikes and finite quantities and premiums"
+                    .to_string(),
+            ));
+        }
+        Ok(OptionStrategy { legs })
+    }
+
+    /// Creates a vertical spread, buying one option at `long_strike` and selling one of the same
+    /// kind at `short_strike`, e.g. a bull call spread with a call `long_strike` below
+    /// `short_strike`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if a strike is not positive or a premium is not
+    /// finite.
+    pub fn vertical_spread(
+        kind: OptionKind,
+        long_strike: f64,
+        short_strike: f64,
+        long_premium: f64,
+        short_premium: f64,
+    ) -> Result<Self, IndicatorError> {
+        OptionStrategy::new(vec![
+            OptionLeg::new(kind, long_strike, 1.0, long_premium),
+            OptionLeg::new(kind, short_strike, -1.0, short_premium),
+        ])
+    }
+
+    /// Creates a long straddle, buying a call and a put at the same strike.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `strike` is not positive or a premium is not
+    /// finite.
+    pub fn straddle(
+        strike: f64,
+        call_premium: f64,
+        put_premium: f64,
+    ) -> Result<Self, IndicatorError> {
+        OptionStrategy::new(vec![
+            OptionLeg::new(OptionKind::Call, strike, 1.0, call_premium),
+            OptionLeg::new(OptionKind::Put, strike, 1.0, put_premium),
+        ])
+    }
+
+    /// Creates a short iron condor from four ascending strikes: buying a put at the first,
+    /// selling a put at the second, selling a call at the third, and buying a call at the fourth.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if the strikes are not positive and ascending,
+    /// with the two short strikes allowed to be equal, or a premium is not finite.
+    pub fn iron_condor(strikes: [f64; 4], premiums: [f64; 4]) -> Result<Self, IndicatorError> {
+        if !(strikes[0] < strikes[1] && strikes[1] <= strikes[2] && strikes[2] < strikes[3]) {
+            return Err(IndicatorError::InvalidInput(
+                "Iron condor strikes must be ascending".to_string(),
+            ));
+        }
+        OptionStrategy::new(vec![
+            OptionLeg::new(OptionKind::Put, strikes[0], 1.0, premiums[0]),
+            OptionLeg::new(OptionKind::Put, strikes[1], -1.0, premiums[1]),
+            OptionLeg::new(OptionKind::Call, strikes[2], -1.0, premiums[2]),
+            OptionLeg::new(OptionKind::Call, strikes[3], 1.0, premiums[3]),
+        ])
+    }
+
+    /// Returns the legs of the strategy.
+    pub fn legs(&self) -> &[OptionLeg] {
+        &self.legs
+    }
+
+    /// Returns the profit or loss at expiry with the underlying at `spot`.
+    pub fn payoff(&self, spot: f64) -> f64 {
+        self.legs.iter().map(|leg| leg.payoff(spot)).sum()
+    }
+
+    /// Samples the payoff at expiry at `points` evenly spaced prices from `low` to `high`, e.g.
+    /// for plotting a payoff diagram.
+    pub fn payoff_diagram(&self, low: f64, high: f64, points: usize) -> Vec<(f64, f64)> {
+        let steps = points.saturating_sub(1).max(1) as f64;
+        (0..points)
+            .map(|i| {
+                let spot = low + (high - low) * i as f64 / steps;
+                (spot, self.payoff(spot))
+            })
+            .collect()
+    }
+
+    /// Calculates the current theoretical profit or loss with Black-Scholes values of the legs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `spot` is not positive, or `years` or
+    /// `volatility` is negative.
+    pub fn value(
+        &self,
+        spot: f64,
+        years: f64,
+        rate: f64,
+        volatility: f64,
+    ) -> Result<f64, IndicatorError> {
+        self.legs.iter().try_fold(0.0, |total, leg| {
+            let price = black_scholes(leg.kind, spot, leg.strike, years, rate, volatility)?;
+            Ok(total + leg.quantity * (price - leg.premium))
+        })
+    }
+
+    /// Returns the prices where the payoff at expiry can change slope: zero and every strike, in
+    /// ascending order.
+    fn kinks(&self) -> Vec<f64> {
+        let mut kinks = self.legs.iter().map(|leg| leg.strike).collect::<Vec<f64>>();
+        kinks.push(0.0);
+        kinks.sort_by(f64::total_cmp);
+        kinks.dedup();
+        kinks
+    }
+
+    /// Returns the slope of the payoff at expiry above the highest strike.
+    fn upside_slope(&self) -> f64 {
+        self.legs
+            .iter()
+            .filter(|leg| leg.kind == OptionKind::Call)
+            .map(|leg| leg.quantity)
+            .sum()
+    }
+
+    /// Returns the prices of the underlying at which the payoff at expiry is zero, in ascending
+    /// order.
+    ///
+    /// The payoff is linear between strikes, so the breakevens are found exactly. Both ends of a
+    /// range with a payoff of exactly zero are included.
+    pub fn breakevens(&self) -> Vec<f64> {
+        let kinks = self.kinks();
+        let payoffs = kinks.iter().map(|k| self.payoff(*k)).collect::<Vec<f64>>();
+
+        let mut breakevens = Vec::new();
+        for i in 0..kinks.len() {
+            if payoffs[i] == 0.0 {
+                breakevens.push(kinks[i]);
+            }
+            let crossing = match kinks.get(i + 1) {
+                Some(next) if payoffs[i] * payoffs[i + 1] < 0.0 => {
+                    Some(kinks[i] + (next - kinks[i]) * payoffs[i] / (payoffs[i] - payoffs[i + 1]))
+                }
+                None if payoffs[i] * self.upside_slope() < 0.0 => {
+                    Some(kinks[i] - payoffs[i] / self.upside_slope())
+                }
+                _ => None,
+            };
+            breakevens.extend(crossing);
+        }
+        breakevens
+    }
+
+    /// Returns the largest profit at expiry, `f64::INFINITY` if the profit is unbounded as the
+    /// underlying rises.
+    pub fn max_profit(&self) -> f64 {
+        if self.upside_slope() > 0.0 {
+            return f64::INFINITY;
+        }
+        self.kinks()
+            .iter()
+            .map(|k| self.payoff(*k))
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    /// Returns the largest loss at expiry as a negative profit, `f64::NEG_INFINITY` if the loss is
+    /// unbounded as the underlying rises.
+    pub fn max_loss(&self) -> f64 {
+        if self.upside_slope() < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        self.kinks()
+            .iter()
+            .map(|k| self.payoff(*k))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Estimates the probability of profit and the expected value of the strategy at expiry.
+    ///
+    /// Lognormal distributions are evaluated exactly. The payoff is linear between its
+    /// breakevens, so the probability of profit sums the probabilities of the ranges between
+    /// breakevens that end in a profit, and the expected value of every leg follows from the
+    /// closed form expectation of its intrinsic value. Samples are evaluated by averaging the
+    /// payoff over them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::NotEnoughData` if no samples are provided, and an
+    /// `IndicatorError::InvalidInput` if the spot is not positive, or the time to expiry or
+    /// volatility is negative.
+    pub fn outlook(
+        &self,
+        distribution: TerminalDistribution,
+    ) -> Result<StrategyOutlook, IndicatorError> {
+        let (spot, years, drift, volatility) = match distribution {
+            TerminalDistribution::Samples(samples) => {
+                if samples.is_empty() {
+                    return Err(IndicatorError::NotEnoughData(
+                        "At least one sample is required".to_string(),
+                    ));
+                }
+                let payoffs = samples
+                    .iter()
+                    .map(|s| self.payoff(*s))
+                    .collect::<Vec<f64>>();
+                let profitable = payoffs.iter().filter(|p| **p > 0.0).count();
+                return Ok(StrategyOutlook {
+                    probability_of_profit: profitable as f64 / samples.len() as f64,
+                    expected_value: payoffs.iter().sum::<f64>() / samples.len() as f64,
+                });
+            }
+            TerminalDistribution::Lognormal {
+                spot,
+                years,
+                drift,
+                volatility,
+            } => (spot, years, drift, volatility),
+        };
+        if !(spot > 0.0 && years >= 0.0 && volatility >= 0.0) {
+            return Err(IndicatorError::InvalidInput(
+                "`spot` must be positive, `years` and `volatility` must not be negative"
+                    .to_string(),
+            ));
+        }
+
+        let forward = spot * (drift * years).exp();
+        let deviation = volatility * years.sqrt();
+        if deviation == 0.0 {
+            let payoff = self.payoff(forward);
+            return Ok(StrategyOutlook {
+                probability_of_profit: if payoff > 0.0 { 1.0 } else { 0.0 },
+                expected_value: payoff,
+            });
+        }
+
+        let cdf = |price: f64| lognormal_cdf(spot, years, drift, deviation, price);
+        let mut bounds = vec![0.0];
+        bounds.extend(self.breakevens());
+        let mut probability_of_profit = 0.0;
+        for (i, low) in bounds.iter().enumerate() {
+            let (inside, high_cdf) = match bounds.get(i + 1) {
+                Some(high) => ((low + high) / 2.0, cdf(*high)),
+                None => (low + 1.0, 1.0),
+            };
+            if self.payoff(inside) > 0.0 {
+                probability_of_profit += high_cdf - cdf(*low);
+            }
+        }
+
+        // The expectation of max(S - K, 0) under a lognormal with the given forward is
+        // F N(d1) - K N(d2), the undiscounted Black-Scholes formula
+        let expected_value = self
+            .legs
+            .iter()
+            .map(|leg| {
+                let d1 = ((forward / leg.strike).ln() + deviation * deviation / 2.0) / deviation;
+                let d2 = d1 - deviation;
+                let intrinsic = match leg.kind {
+                    OptionKind::Call => forward * normal_cdf(d1) - leg.strike * normal_cdf(d2),
+                    OptionKind::Put => leg.strike * normal_cdf(-d2) - forward * normal_cdf(-d1),
+                };
+                leg.quantity * (intrinsic - leg.premium)
+            })
+            .sum();
+
+        Ok(StrategyOutlook {
+            probability_of_profit,
+            expected_value,
+        })
+    }
+}
+
+/// A cash dividend paid by the underlying of an [`AmericanOption`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CashDividend {
+    /// Time of the ex-date in years from today
+    pub years: f64,
+    /// Cash paid per share
+    pub amount: f64,
+}
+
+/// An American option, exercisable at any time until expiry, on an underlying paying discrete
+/// cash dividends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmericanOption {
+    /// Whether the option is a call or a put
+    pub kind: OptionKind,
+    /// Strike price
+    pub strike: f64,
+    /// Time to expiry in years
+    pub years: f64,
+    /// Dividends going ex before expiry, in any order
+    pub dividends: Vec<CashDividend>,
+}
+
+/// The value of an American option and where exercising it early is optimal, produced by
+/// [`AmericanOption::price`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmericanPrice {
+    /// Value of one option
+    pub value: f64,
+    /// Time in years and critical price of the underlying of every step of the tree at which early
+    /// exercise is optimal for some prices. Puts are exercised at and below the critical price,
+    /// calls at and above it
+    pub exercise_boundary: Vec<(f64, f64)>,
+}
+
+impl AmericanOption {
+    /// Returns the present value at time `t` of the dividends going ex after `t`.
+    fn dividends_after(&self, t: f64, rate: f64) -> f64 {
+        self.dividends
+            .iter()
+            .filter(|dividend| dividend.years > t && dividend.years < self.years)
+            .map(|dividend| dividend.amount * (-rate * (dividend.years - t)).exp())
+            .sum()
+    }
+
+    /// Prices the option on a Cox-Ross-Rubinstein binomial tree and extracts its early exercise
+    /// boundary.
+    ///
+    /// Dividends are handled with the escrowed dividend model: the tree models the price of the
+    /// underlying less the present value of the dividends before expiry, and the price at every
+    /// node adds back the dividends still to come. The price drops by a dividend at its ex-date,
+    /// so calls are only exercised early just before an ex-date.
+    ///
+    /// # Arguments
+    ///
+    /// * `spot` - The current price of the underlying.
+    /// * `rate` - The continuously compounded risk-free rate.
+    /// * `volatility` - The annualized volatility of the underlying less its dividends.
+    /// * `steps` - The number of time steps of the tree, e.g. 500.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the [`AmericanPrice`] or an `IndicatorError`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if the strike or the time to expiry is not
+    /// positive, a dividend amount is negative, the dividends are worth as much as the spot, or
+    /// the tree has too few steps for the rate and volatility to give probabilities between zero
+    /// and one.
+    pub fn price(
+        &self,
+        spot: f64,
+        rate: f64,
+        volatility: f64,
+        steps: usize,
+    ) -> Result<AmericanPrice, IndicatorError> {
+        if !(self.strike > 0.0 && self.years > 0.0 && volatility >= 0.0)
+            || self
+                .dividends
+                .iter()
+                .any(|d| d.amount.is_nan() || d.amount < 0.0)
+        {
+            return Err(IndicatorError::InvalidInput(
+                "The strike and expiry must be positive, the volatility and dividends must not be \
+                 negative"
+                    .to_string(),
+            ));
+        }
+        let escrowed = spot - self.dividends_after(0.0, rate);
+        if escrowed.is_nan() || escrowed <= 0.0 {
+            return Err(IndicatorError::InvalidInput(
+                "`spot` must exceed the present value of the dividends".to_string(),
+            ));
+        }
+
+        let dt = self.years / steps as f64;
+        let up = (volatility * dt.sqrt()).exp();
+        let growth = (rate * dt).exp();
+        let probability = (growth - 1.0 / up) / (up - 1.0 / up);
+        if !(probability > 0.0 && probability < 1.0) {
+            return Err(IndicatorError::InvalidInput(
+                "The tree needs more steps or volatility for valid probabilities".to_string(),
+            ));
+        }
+
+        let node = |step: usize, down_moves: usize| {
+            escrowed * up.powi(step as i32 - 2 * down_moves as i32)
+        };
+        let mut values = (0..=steps)
+            .map(|j| self.kind.intrinsic(node(steps, j), self.strike))
+            .collect::<Vec<f64>>();
+        let mut exercise_boundary = Vec::new();
+        for step in (0..steps).rev() {
+            let t = step as f64 * dt;
+            let dividends = self.dividends_after(t, rate);
+            let mut critical: Option<f64> = None;
+            for j in 0..=step {
+                let price = node(step, j) + dividends;
+                let continuation =
+                    (probability * values[j] + (1.0 - probability) * values[j + 1]) / growth;
+                let exercise = self.kind.intrinsic(price, self.strike);
+                if exercise > 0.0 && exercise > continuation {
+                    values[j] = exercise;
+                    critical = Some(match (self.kind, critical) {
+                        (_, None) => price,
+                        (OptionKind::Put, Some(c)) => c.max(price),
+                        (OptionKind::Call, Some(c)) => c.min(price),
+                    });
+                } else {
+                    values[j] = continuation;
+                }
+            }
+            exercise_boundary.extend(critical.map(|c| (t, c)));
+        }
+        exercise_boundary.reverse();
+
+        Ok(AmericanPrice {
+            value: values[0],
+            exercise_boundary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_black_scholes() {
+        let call = black_scholes(OptionKind::Call, 100.0, 100.0, 1.0, 0.05, 0.2).unwrap();
+        let put = black_scholes(OptionKind::Put, 100.0, 100.0, 1.0, 0.05, 0.2).unwrap();
+        assert!((call - 10.450_583_572_185_565).abs() < 1e-9);
+        assert!((put - 5.573_526_022_256_971).abs() < 1e-9);
+        let expired = black_scholes(OptionKind::Put, 90.0, 100.0, 0.0, 0.05, 0.2).unwrap();
+        assert_eq!(expired, 10.0);
+
+        // Test case with invalid input
+        let result = black_scholes(OptionKind::Call, 100.0, 0.0, 1.0, 0.05, 0.2);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_option_strategy() {
+        // Bull call spread
+        let spread =
+            OptionStrategy::vertical_spread(OptionKind::Call, 95.0, 105.0, 7.5, 3.0).unwrap();
+        assert_eq!(spread.payoff(90.0), -4.5);
+        assert_eq!(spread.payoff(110.0), 5.5);
+        assert_eq!(spread.breakevens(), vec![99.5]);
+        assert_eq!(spread.max_profit(), 5.5);
+        assert_eq!(spread.max_loss(), -4.5);
+        let value = spread.value(100.0, 0.5, 0.01, 0.25).unwrap();
+        assert!((value - 0.248_888_891_122_966_53).abs() < 1e-9);
+        let diagram = spread.payoff_diagram(90.0, 110.0, 5);
+        assert_eq!(diagram[2], (100.0, 0.5));
+        assert_eq!(diagram.len(), 5);
+
+        // Long straddle
+        let straddle = OptionStrategy::straddle(100.0, 6.0, 4.0).unwrap();
+        assert_eq!(straddle.breakevens(), vec![90.0, 110.0]);
+        assert_eq!(straddle.max_profit(), f64::INFINITY);
+        assert_eq!(straddle.max_loss(), -10.0);
+
+        // Short iron condor collecting a net credit of 2
+        let condor =
+            OptionStrategy::iron_condor([80.0, 90.0, 110.0, 120.0], [0.5, 1.5, 1.5, 0.5]).unwrap();
+        assert_eq!(condor.breakevens(), vec![88.0, 112.0]);
+        assert_eq!(condor.max_profit(), 2.0);
+        assert_eq!(condor.max_loss(), -8.0);
+
+        // Naked short call
+        let short_call =
+            OptionStrategy::new(vec![OptionLeg::new(OptionKind::Call, 100.0, -1.0, 2.0)]).unwrap();
+        assert_eq!(short_call.breakevens(), vec![102.0]);
+        assert_eq!(short_call.max_loss(), f64::NEG_INFINITY);
+
+        // Test case with invalid input
+        let result = OptionStrategy::iron_condor([90.0, 80.0, 110.0, 120.0], [1.0; 4]);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_outlook() {
+        // Long call under a lognormal distribution
+        let call =
+            OptionStrategy::new(vec![OptionLeg::new(OptionKind::Call, 100.0, 1.0, 10.0)]).unwrap();
+        let distribution = TerminalDistribution::Lognormal {
+            spot: 100.0,
+            years: 1.0,
+            drift: 0.05,
+            volatility: 0.2,
+        };
+        let outlook = call.outlook(distribution).unwrap();
+        assert!((outlook.probability_of_profit - 0.372_003_793_018_840_23).abs() < 1e-12);
+        assert!((outlook.expected_value - 0.986_396_449_700_786).abs() < 1e-9);
+
+        // Short iron condor under a driftless lognormal distribution
+        let condor =
+            OptionStrategy::iron_condor([80.0, 90.0, 110.0, 120.0], [0.5, 1.5, 1.5, 0.5]).unwrap();
+        let distribution = TerminalDistribution::Lognormal {
+            spot: 100.0,
+            years: 0.25,
+            drift: 0.0,
+            volatility: 0.2,
+        };
+        let outlook = condor.outlook(distribution).unwrap();
+        assert!((outlook.probability_of_profit - 0.771_991_399_543_967_8).abs() < 1e-12);
+        assert!((outlook.expected_value - 0.520_918_318_747_909_8).abs() < 1e-9);
+
+        // Long straddle over samples
+        let straddle = OptionStrategy::straddle(100.0, 6.0, 4.0).unwrap();
+        let samples = [80.0, 95.0, 100.0, 115.0];
+        let outlook = straddle
+            .outlook(TerminalDistribution::Samples(&samples))
+            .unwrap();
+        assert_eq!(outlook.probability_of_profit, 0.5);
+        assert_eq!(outlook.expected_value, (10.0 - 5.0 - 10.0 + 5.0) / 4.0);
+
+        // Test case with not enough data
+        let result = straddle.outlook(TerminalDistribution::Samples(&[]));
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_american_option() {
+        // Put without dividends
+        let put = AmericanOption {
+            kind: OptionKind::Put,
+            strike: 100.0,
+            years: 1.0,
+            dividends: Vec::new(),
+        };
+        let result = put.price(100.0, 0.05, 0.2, 4).unwrap();
+        assert!((result.value - 5.882_800_351_329_434).abs() < 1e-9);
+        assert_eq!(result.exercise_boundary.len(), 2);
+        assert!((result.exercise_boundary[0].0 - 0.5).abs() < 1e-12);
+        assert!((result.exercise_boundary[0].1 - 81.873_075_307_798_17).abs() < 1e-9);
+        assert!((result.exercise_boundary[1].1 - 90.483_741_803_595_95).abs() < 1e-9);
+        let result = put.price(100.0, 0.05, 0.2, 500).unwrap();
+        assert!((result.value - 6.088_810_110_703_283).abs() < 1e-9);
+
+        // Calls without dividends are never exercised early
+        let call = AmericanOption {
+            kind: OptionKind::Call,
+            ..put.clone()
+        };
+        let result = call.price(100.0, 0.05, 0.2, 500).unwrap();
+        let european = black_scholes(OptionKind::Call, 100.0, 100.0, 1.0, 0.05, 0.2).unwrap();
+        assert!((result.value - european).abs() < 1e-2);
+        assert!(result.exercise_boundary.is_empty());
+
+        // Calls are exercised just before a large dividend
+        let call = AmericanOption {
+            kind: OptionKind::Call,
+            strike: 90.0,
+            years: 1.0,
+            dividends: vec![CashDividend {
+                years: 0.5,
+                amount: 8.0,
+            }],
+        };
+        let result = call.price(100.0, 0.05, 0.2, 200).unwrap();
+        assert!((result.value - 13.508_403_397_742_192).abs() < 1e-9);
+        assert_eq!(result.exercise_boundary.len(), 1);
+        assert!((result.exercise_boundary[0].0 - 0.495).abs() < 1e-12);
+        assert!((result.exercise_boundary[0].1 - 96.365_728_055_428_73).abs() < 1e-9);
+
+        // Test case with invalid input
+        let result = put.price(100.0, 0.05, 0.0, 10);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! A lightweight level 2 order book and the microstructure metrics derived from it.
+
+/// Aggregated resting quantity at a single price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Level {
+    /// Price of the level
+    pub price: f64,
+    /// Total quantity resting at `price`
+    pub quantity: f64,
+}
+
+impl Level {
+    /// Creates a new price level.
+    pub fn new(price: f64, quantity: f64) -> Self {
+        Level { price, quantity }
+    }
+}
+
+/// Side of the order book a level belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    /// Resting buy orders
+    Bid,
+    /// Resting sell orders
+    Ask,
+}
+
+/// A level 2 order book maintained from snapshots and incremental updates.
+///
+/// Bids are kept in descending and asks in ascending price order, so the best price of each side
+/// is always the first level.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBook {
+    bids: Vec<Level>,
+    asks: Vec<Level>,
+}
+
+impl OrderBook {
+    /// Creates an empty order book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an order book from a full snapshot of both sides in any order.
+    pub fn from_snapshot(bids: &[Level], asks: &[Level]) -> Self {
+        let mut book = Self::new();
+        book.apply_snapshot(bids, asks);
+        book
+    }
+
+    /// Replaces the contents of the book with a full snapshot of both sides in any order.
+    ///
+    /// Levels without a positive quantity are ignored, and levels repeating a price on the same
+    /// side are merged into one level holding their total quantity.
+    pub fn apply_snapshot(&mut self, bids: &[Level], asks: &[Level]) {
+        let keep = |levels: &[Level], side: BookSide| {
+            let mut levels = levels
+                .iter()
+                .filter(|l| l.quantity > 0.0)
+                .copied()
+                .collect::<Vec<Level>>();
+            match side {
+                BookSide::Bid => levels.sort_by(|a, b| b.price.total_cmp(&a.price)),
+                BookSide::Ask => levels.sort_by(|a, b| a.price.total_cmp(&b.price)),
+            }
+            let mut merged: Vec<Level> = Vec::with_capacity(levels.len());
+            for level in levels {
+                match merged.last_mut() {
+                    Some(last) if last.price == level.price => last.quantity += level.quantity,
+                    _ => merged.push(level),
+                }
+            }
+            merged
+        };
+        self.bids = keep(bids, BookSide::Bid);
+        self.asks = keep(asks, BookSide::Ask);
+    }
+
+    /// Sets the quantity resting at `price` on one side of the book.
+    ///
+    /// A quantity of zero or less removes the level.
+    pub fn apply_delta(&mut self, side: BookSide, price: f64, quantity: f64) {
+        let levels = match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        };
+        let position = levels.binary_search_by(|level| match side {
+            BookSide::Bid => price.total_cmp(&level.price),
+            BookSide::Ask => level.price.total_cmp(&price),
+        });
+        match (position, quantity > 0.0) {
+            (Ok(i), true) => levels[i].quantity = quantity,
+            (Ok(i), false) => {
+                levels.remove(i);
+            }
+            (Err(i), true) => levels.insert(i, Level::new(price, quantity)),
+            (Err(_), false) => (),
+        }
+    }
+
+    /// Returns the bid levels, best price first.
+    pub fn bids(&self) -> &[Level] {
+        &self.bids
+    }
+
+    /// Returns the ask levels, best price first.
+    pub fn asks(&self) -> &[Level] {
+        &self.asks
+    }
+
+    /// Returns the highest bid, if any.
+    pub fn best_bid(&self) -> Option<Level> {
+        self.bids.first().copied()
+    }
+
+    /// Returns the lowest ask, if any.
+    pub fn best_ask(&self) -> Option<Level> {
+        self.asks.first().copied()
+    }
+
+    /// Returns the best bid and ask when both sides of the book are populated.
+    fn top(&self) -> Option<(Level, Level)> {
+        Some((self.best_bid()?, self.best_ask()?))
+    }
+
+    /// Returns the difference between the best ask and the best bid.
+    pub fn spread(&self) -> Option<f64> {
+        self.top().map(|(bid, ask)| ask.price - bid.price)
+    }
+
+    /// Returns the midpoint between the best bid and the best ask.
+    pub fn mid_price(&self) -> Option<f64> {
+        self.top().map(|(bid, ask)| (bid.price + ask.price) / 2.0)
+    }
+
+    /// Returns the microprice, the midpoint weighted towards the side with less resting quantity.
+    ///
+    /// The microprice is `(bid * ask_quantity + ask * bid_quantity) / (bid_quantity +
+    /// ask_quantity)` at the top of the book.
+    pub fn microprice(&self) -> Option<f64> {
+        self.top().map(|(bid, ask)| {
+            (bid.price * ask.quantity + ask.price * bid.quantity) / (bid.quantity + ask.quantity)
+        })
+    }
+
+    /// Returns the order book imbalance over the best `depth` levels of each side.
+    ///
+    /// The imbalance is `(bid_quantity - ask_quantity) / (bid_quantity + ask_quantity)`, ranging
+    /// from `-1.0` when only asks rest to `1.0` when only bids rest.
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        let quantity =
+            |levels: &[Level]| levels.iter().take(depth).map(|l| l.quantity).sum::<f64>();
+        let bids = quantity(&self.bids);
+        let asks = quantity(&self.asks);
+        if bids + asks > 0.0 {
+            Some((bids - asks) / (bids + asks))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the depth weighted mid price, the average of the quantity weighted bid and ask
+    /// prices over the best `depth` levels of each side.
+    pub fn depth_weighted_mid(&self, depth: usize) -> Option<f64> {
+        let weighted = |levels: &[Level]| {
+            let quantity = levels.iter().take(depth).map(|l| l.quantity).sum::<f64>();
+            let notional = levels
+                .iter()
+                .take(depth)
+                .map(|l| l.price * l.quantity)
+                .sum::<f64>();
+            if quantity > 0.0 {
+                Some(notional / quantity)
+            } else {
+                None
+            }
+        };
+        Some((weighted(&self.bids)? + weighted(&self.asks)?) / 2.0)
+    }
+}
+
+/// Running statistics of the bid-ask spread, updated after every book change.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpreadStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl SpreadStats {
+    /// Creates empty spread statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the current spread of `book`. Books missing either side are skipped.
+    pub fn update(&mut self, book: &OrderBook) {
+        if let Some(spread) = book.spread() {
+            self.push(spread);
+        }
+    }
+
+    /// Records a single spread observation.
+    pub fn push(&mut self, spread: f64) {
+        if self.count == 0 {
+            self.min = spread;
+            self.max = spread;
+        } else {
+            self.min = self.min.min(spread);
+            self.max = self.max.max(spread);
+        }
+        self.count += 1;
+        let delta = spread - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (spread - self.mean);
+    }
+
+    /// Returns the number of recorded spreads.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the mean spread, if any spread was recorded.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Returns the sample standard deviation of the spread, if at least two spreads were recorded.
+    pub fn std_dev(&self) -> Option<f64> {
+        (self.count > 1).then(|| (self.m2 / (self.count - 1) as f64).sqrt())
+    }
+
+    /// Returns the narrowest recorded spread.
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Returns the widest recorded spread.
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> OrderBook {
+        OrderBook::from_snapshot(
+            &[Level::new(99.0, 3.0), Level::new(100.0, 1.0)],
+            &[Level::new(102.0, 4.0), Level::new(101.0, 3.0)],
+        )
+    }
+
+    #[test]
+    fn test_apply_snapshot() {
+        // Test case with repeated prices and empty levels
+        let mut book = OrderBook::from_snapshot(
+            &[
+                Level::new(100.0, 1.0),
+                Level::new(99.0, 3.0),
+                Level::new(100.0, 2.0),
+                Level::new(98.0, 0.0),
+            ],
+            &[Level::new(101.0, 3.0), Level::new(101.0, 0.0)],
+        );
+        assert_eq!(
+            book.bids(),
+            &[Level::new(100.0, 3.0), Level::new(99.0, 3.0)]
+        );
+        assert_eq!(book.asks(), &[Level::new(101.0, 3.0)]);
+
+        // A delta replaces the merged level as a whole
+        book.apply_delta(BookSide::Bid, 100.0, 0.5);
+        assert_eq!(book.best_bid(), Some(Level::new(100.0, 0.5)));
+        book.apply_delta(BookSide::Bid, 100.0, 0.0);
+        assert_eq!(book.bids(), &[Level::new(99.0, 3.0)]);
+    }
+
+    #[test]
+    fn test_apply_delta() {
+        let mut book = book();
+        assert_eq!(book.best_bid(), Some(Level::new(100.0, 1.0)));
+        assert_eq!(book.best_ask(), Some(Level::new(101.0, 3.0)));
+
+        // Insert a better bid, update an ask, and remove the best ask
+        book.apply_delta(BookSide::Bid, 100.5, 2.0);
+        book.apply_delta(BookSide::Ask, 102.0, 5.0);
+        book.apply_delta(BookSide::Ask, 101.0, 0.0);
+        assert_eq!(
+            book.bids(),
+            &[
+                Level::new(100.5, 2.0),
+                Level::new(100.0, 1.0),
+                Level::new(99.0, 3.0)
+            ]
+        );
+        assert_eq!(book.asks(), &[Level::new(102.0, 5.0)]);
+
+        // Removing a missing level is a no-op
+        book.apply_delta(BookSide::Bid, 50.0, 0.0);
+        assert_eq!(book.bids().len(), 3);
+    }
+
+    #[test]
+    fn test_book_metrics() {
+        let book = book();
+        assert_eq!(book.spread(), Some(1.0));
+        assert_eq!(book.mid_price(), Some(100.5));
+        assert_eq!(book.microprice(), Some(100.25));
+        assert_eq!(book.imbalance(1), Some(-0.5));
+        assert_eq!(book.imbalance(2), Some(-3.0 / 11.0));
+        let expected = (99.25 + (101.0 * 3.0 + 102.0 * 4.0) / 7.0) / 2.0;
+        assert!((book.depth_weighted_mid(2).unwrap() - expected).abs() < 1e-12);
+
+        // Metrics are undefined without both sides
+        let empty = OrderBook::new();
+        assert_eq!(empty.spread(), None);
+        assert_eq!(empty.microprice(), None);
+        assert_eq!(empty.imbalance(5), None);
+    }
+
+    #[test]
+    fn test_spread_stats() {
+        let mut stats = SpreadStats::new();
+        assert_eq!(stats.mean(), None);
+
+        let mut book = book();
+        stats.update(&book);
+        book.apply_delta(BookSide::Ask, 101.0, 0.0);
+        stats.update(&book);
+        book.apply_delta(BookSide::Bid, 100.0, 0.0);
+        stats.update(&book);
+        assert_eq!(stats.count(), 3);
+        assert_eq!(stats.mean(), Some(2.0));
+        assert_eq!(stats.std_dev(), Some(1.0));
+        assert_eq!(stats.min(), Some(1.0));
+        assert_eq!(stats.max(), Some(3.0));
+    }
+}
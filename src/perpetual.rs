@@ -0,0 +1,230 @@
+//! Analytics for perpetual swaps and other derivatives quoted against a spot market.
+//!
+//! Rates and bases are expressed as fractions, so a funding rate of `0.0001` is 0.01% per funding
+//! interval.
+
+use crate::IndicatorError;
+
+/// Funding cash flows produced by [`calculate_funding_payments`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundingAccrual {
+    /// Funding received in each interval. Negative values are funding paid.
+    pub payments: Vec<f64>,
+    /// Running total of `payments`.
+    pub cumulative: Vec<f64>,
+}
+
+/// Checks that two parallel input slices have the same length.
+fn require_same_length(a: &[f64], b: &[f64], message: &str) -> Result<(), IndicatorError> {
+    if a.len() != b.len() {
+        return Err(IndicatorError::MismatchedLength(message.to_string()));
+    }
+    Ok(())
+}
+
+/// Calculates the funding payments accrued by a perpetual swap position.
+///
+/// When the funding rate is positive longs pay shorts, so a position with notional `n` receives
+/// `-n * rate` in each funding interval.
+///
+/// # Arguments
+///
+/// * `funding_rates` - The funding rate of each funding interval.
+/// * `position_notional` - The signed notional value of the position in each interval, positive
+///   for longs and negative for shorts.
+///
+/// # Returns
+///
+/// A Result containing the [`FundingAccrual`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `funding_rates` and `position_notional`
+/// differ in length.
+pub fn calculate_funding_payments(
+    funding_rates: &[f64],
+    position_notional: &[f64],
+) -> Result<FundingAccrual, IndicatorError> {
+    require_same_length(
+        funding_rates,
+        position_notional,
+        "`funding_rates` and `position_notional` must have the same length",
+    )?;
+
+    let payments = funding_rates
+        .iter()
+        .zip(position_notional)
+        .map(|(rate, notional)| -rate * notional)
+        .collect::<Vec<f64>>();
+    let cumulative = payments
+        .iter()
+        .scan(0.0, |total, payment| {
+            *total += payment;
+            Some(*total)
+        })
+        .collect();
+
+    Ok(FundingAccrual {
+        payments,
+        cumulative,
+    })
+}
+
+/// Annualizes a series of funding rates by simple multiplication.
+///
+/// # Arguments
+///
+/// * `funding_rates` - The funding rate of each funding interval.
+/// * `intervals_per_year` - The number of funding intervals in a year, for example `1095.0` for
+///   eight hour funding.
+///
+/// # Returns
+///
+/// A vector of annualized funding rates.
+pub fn calculate_annualized_funding(funding_rates: &[f64], intervals_per_year: f64) -> Vec<f64> {
+    funding_rates
+        .iter()
+        .map(|rate| rate * intervals_per_year)
+        .collect()
+}
+
+/// Calculates the basis of a derivative relative to its spot market, as a fraction of the spot
+/// price.
+///
+/// # Arguments
+///
+/// * `derivative_prices` - A slice of perpetual swap or futures prices.
+/// * `spot_prices` - A slice of spot prices aligned with `derivative_prices`.
+///
+/// # Returns
+///
+/// A Result containing a vector of basis values or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `derivative_prices` and `spot_prices` differ
+/// in length.
+pub fn calculate_basis(
+    derivative_prices: &[f64],
+    spot_prices: &[f64],
+) -> Result<Vec<f64>, IndicatorError> {
+    require_same_length(
+        derivative_prices,
+        spot_prices,
+        "`derivative_prices` and `spot_prices` must have the same length",
+    )?;
+
+    Ok(derivative_prices
+        .iter()
+        .zip(spot_prices)
+        .map(|(derivative, spot)| (derivative - spot) / spot)
+        .collect())
+}
+
+/// Calculates the annualized basis of a dated derivative relative to its spot market.
+///
+/// The basis is annualized by simple division by the time remaining until expiry.
+///
+/// # Arguments
+///
+/// * `derivative_prices` - A slice of futures prices.
+/// * `spot_prices` - A slice of spot prices aligned with `derivative_prices`.
+/// * `years_to_expiry` - The time to expiry in years at each observation.
+///
+/// # Returns
+///
+/// A Result containing a vector of annualized basis values or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the inputs differ in length, and an
+/// `IndicatorError::InvalidInput` if any time to expiry is not positive.
+pub fn calculate_annualized_basis(
+    derivative_prices: &[f64],
+    spot_prices: &[f64],
+    years_to_expiry: &[f64],
+) -> Result<Vec<f64>, IndicatorError> {
+    require_same_length(
+        derivative_prices,
+        years_to_expiry,
+        "`derivative_prices` and `years_to_expiry` must have the same length",
+    )?;
+    if years_to_expiry.iter().any(|t| *t <= 0.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`years_to_expiry` must be positive".to_string(),
+        ));
+    }
+
+    Ok(calculate_basis(derivative_prices, spot_prices)?
+        .iter()
+        .zip(years_to_expiry)
+        .map(|(basis, years)| basis / years)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_funding_payments() {
+        // Test case with enough data
+        let rates = [0.0001, -0.0002, 0.0003];
+        let notional = [10_000.0, 10_000.0, -5_000.0];
+        let result = calculate_funding_payments(&rates, &notional).unwrap();
+        let expected = [(-1.0, -1.0), (2.0, 1.0), (1.5, 2.5)];
+        for (i, (payment, cumulative)) in expected.iter().enumerate() {
+            assert!((result.payments[i] - payment).abs() < 1e-12);
+            assert!((result.cumulative[i] - cumulative).abs() < 1e-12);
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_funding_payments(&rates, &notional[1..]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_annualized_funding() {
+        let result = calculate_annualized_funding(&[0.0001, -0.0002], 1095.0);
+        assert!((result[0] - 0.1095).abs() < 1e-12);
+        assert!((result[1] + 0.219).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_calculate_basis() {
+        // Test case with enough data
+        let result = calculate_basis(&[101.0, 99.0], &[100.0, 100.0]).unwrap();
+        assert!((result[0] - 0.01).abs() < 1e-12);
+        assert!((result[1] + 0.01).abs() < 1e-12);
+
+        // Test case with mismatched lengths
+        let result = calculate_basis(&[101.0, 99.0], &[100.0]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_annualized_basis() {
+        // Test case with enough data
+        let result = calculate_annualized_basis(&[102.0], &[100.0], &[0.25]).unwrap();
+        assert!((result[0] - 0.08).abs() < 1e-12);
+
+        // Test case with an expired contract
+        let result = calculate_annualized_basis(&[102.0], &[100.0], &[0.0]);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,736 @@
+//! Portfolio level return and allocation calculations.
+
+use crate::stats::RollingVariance;
+use crate::IndicatorError;
+
+/// Per period results of [`calculate_portfolio_returns`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioReturns {
+    /// Return of the whole portfolio, including the cash balance.
+    pub returns: Vec<f64>,
+    /// Fraction of the portfolio held in cash. Negative values indicate borrowing.
+    pub cash_weights: Vec<f64>,
+    /// Return lost to holding cash: the return the invested positions would have earned if they
+    /// made up the whole portfolio, minus `returns`. `NaN` in periods without invested positions.
+    pub cash_drag: Vec<f64>,
+}
+
+/// Checks that every panel holds the same number of assets, each with the same number of periods.
+///
+/// Returns the number of periods.
+fn validate_panels(panels: &[&[&[f64]]]) -> Result<usize, IndicatorError> {
+    let Some(periods) = panels.first().and_then(|p| p.first()).map(|s| s.len()) else {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one asset is required".to_string(),
+        ));
+    };
+    let assets = panels[0].len();
+    if panels
+        .iter()
+        .any(|p| p.len() != assets || p.iter().any(|s| s.len() != periods))
+    {
+        return Err(IndicatorError::MismatchedLength(
+            "Every input must cover the same assets and periods".to_string(),
+        ));
+    }
+    Ok(periods)
+}
+
+/// Calculates the returns of a partially invested portfolio, accounting for the cash balance.
+///
+/// In every period the portfolio holds `exposures[asset][period]` of its value in each asset and
+/// the remainder in cash earning `cash_yield`. Exposures summing to more than one imply a negative
+/// cash balance that is financed at `cash_yield`.
+///
+/// # Arguments
+///
+/// * `asset_returns` - One slice of periodic returns per asset.
+/// * `exposures` - One slice of portfolio weights per asset, aligned with `asset_returns`.
+/// * `cash_yield` - The return earned on cash per period.
+///
+/// # Returns
+///
+/// A Result containing the [`PortfolioReturns`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if no assets are provided, and an
+/// `IndicatorError::MismatchedLength` if `asset_returns` and `exposures` do not describe the same
+/// number of assets and periods.
+pub fn calculate_portfolio_returns(
+    asset_returns: &[&[f64]],
+    exposures: &[&[f64]],
+    cash_yield: f64,
+) -> Result<PortfolioReturns, IndicatorError> {
+    let periods = validate_panels(&[asset_returns, exposures])?;
+
+    let mut result = PortfolioReturns {
+        returns: Vec::with_capacity(periods),
+        cash_weights: Vec::with_capacity(periods),
+        cash_drag: Vec::with_capacity(periods),
+    };
+    for period in 0..periods {
+        let invested = exposures.iter().map(|e| e[period]).sum::<f64>();
+        let invested_return = asset_returns
+            .iter()
+            .zip(exposures)
+            .map(|(r, e)| r[period] * e[period])
+            .sum::<f64>();
+        let cash_weight = 1.0 - invested;
+        let cash_drag = if invested != 0.0 {
+            cash_weight * (invested_return / invested - cash_yield)
+        } else {
+            f64::NAN
+        };
+
+        result
+            .returns
+            .push(invested_return + cash_weight * cash_yield);
+        result.cash_weights.push(cash_weight);
+        result.cash_drag.push(cash_drag);
+    }
+
+    Ok(result)
+}
+
+/// Per period results of [`calculate_fx_attribution`], expressed in the base currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxAttribution {
+    /// Contribution of the asset returns in their local currencies.
+    pub local: Vec<f64>,
+    /// Contribution of the exchange rate moves.
+    pub currency: Vec<f64>,
+    /// Contribution of the interaction between asset and exchange rate moves.
+    pub interaction: Vec<f64>,
+    /// Portfolio return in the base currency, the sum of the three contributions.
+    pub total: Vec<f64>,
+}
+
+/// Decomposes the base currency return of a multi-currency portfolio into local asset returns and
+/// currency returns.
+///
+/// A position with local return `r` and currency return `f` earns `(1 + r) * (1 + f) - 1` in the
+/// base currency, which splits into the local term `r`, the currency term `f` and the interaction
+/// term `r * f`. Each term is weighted by the position's exposure and summed over the portfolio.
+///
+/// # Arguments
+///
+/// * `local_returns` - One slice of periodic returns per asset, in the asset's own currency.
+/// * `fx_returns` - One slice of periodic returns per asset of the base currency value of one unit
+///   of the asset's currency. Use zeros for assets denominated in the base currency.
+/// * `exposures` - One slice of portfolio weights per asset, aligned with `local_returns`.
+///
+/// # Returns
+///
+/// A Result containing the [`FxAttribution`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if no assets are provided, and an
+/// `IndicatorError::MismatchedLength` if the inputs do not describe the same number of assets and
+/// periods.
+pub fn calculate_fx_attribution(
+    local_returns: &[&[f64]],
+    fx_returns: &[&[f64]],
+    exposures: &[&[f64]],
+) -> Result<FxAttribution, IndicatorError> {
+    let periods = validate_panels(&[local_returns, fx_returns, exposures])?;
+
+    let mut result = FxAttribution {
+        local: vec![0.0; periods],
+        currency: vec![0.0; periods],
+        interaction: vec![0.0; periods],
+        total: vec![0.0; periods],
+    };
+    for ((local, fx), weights) in local_returns.iter().zip(fx_returns).zip(exposures) {
+        for period in 0..periods {
+            let (r, f, w) = (local[period], fx[period], weights[period]);
+            result.local[period] += w * r;
+            result.currency[period] += w * f;
+            result.interaction[period] += w * r * f;
+            result.total[period] += w * ((1.0 + r) * (1.0 + f) - 1.0);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Scales positions so that a strategy runs at a target annualized volatility.
+///
+/// The volatility is estimated from the latest `window` returns of the unscaled strategy, so the
+/// same overlay sizes positions bar by bar in live trading and over a whole history in
+/// [`apply_volatility_target`]. The leverage is capped at `max_leverage`, which also applies when
+/// the estimated volatility is zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolatilityTarget {
+    target: f64,
+    periods_per_year: f64,
+    max_leverage: f64,
+    variance: RollingVariance,
+}
+
+impl VolatilityTarget {
+    /// Creates an overlay targeting the annualized volatility `target`, e.g. `0.1` for 10%, with
+    /// returns sampled `periods_per_year` times a year.
+    pub fn new(target: f64, window: usize, periods_per_year: f64, max_leverage: f64) -> Self {
+        VolatilityTarget {
+            target,
+            periods_per_year,
+            max_leverage,
+            variance: RollingVariance::new(window),
+        }
+    }
+
+    /// Adds the latest return of the unscaled strategy.
+    pub fn push(&mut self, strategy_return: f64) {
+        self.variance.push(strategy_return);
+    }
+
+    /// Returns the annualized volatility of the unscaled strategy, once the window is full.
+    pub fn volatility(&self) -> Option<f64> {
+        if !self.variance.is_full() {
+            return None;
+        }
+        self.variance
+            .std_dev()
+            .map(|std_dev| std_dev * self.periods_per_year.sqrt())
+    }
+
+    /// Returns the factor positions are scaled by, once the window is full.
+    pub fn leverage(&self) -> Option<f64> {
+        self.volatility()
+            .map(|volatility| (self.target / volatility).min(self.max_leverage))
+    }
+
+    /// Returns `position` scaled to the target volatility, once the window is full.
+    pub fn size(&self, position: f64) -> Option<f64> {
+        self.leverage().map(|leverage| position * leverage)
+    }
+}
+
+/// Scales the positions of a strategy to a target annualized volatility.
+///
+/// The position of every period is scaled by the leverage of a [`VolatilityTarget`] that has seen
+/// the strategy returns of the previous `window` periods only, so the sizing does not look ahead.
+///
+/// # Arguments
+///
+/// * `positions` - The unscaled position of every period.
+/// * `strategy_returns` - The return of the unscaled strategy in every period, aligned with
+///   `positions`.
+/// * `target` - The annualized volatility to target.
+/// * `window` - The number of returns the volatility is estimated from.
+/// * `periods_per_year` - The number of periods in a year, e.g. `252.0` for daily returns.
+/// * `max_leverage` - The largest factor a position is scaled by.
+///
+/// # Returns
+///
+/// A Result containing the scaled positions, starting at period `window`, or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `positions` and `strategy_returns` differ in
+/// length, an `IndicatorError::InvalidInput` if `window` is smaller than two or `target`,
+/// `periods_per_year`, or `max_leverage` is not positive, and an `IndicatorError::NotEnoughData` if
+/// there are not more than `window` periods.
+pub fn apply_volatility_target(
+    positions: &[f64],
+    strategy_returns: &[f64],
+    target: f64,
+    window: usize,
+    periods_per_year: f64,
+    max_leverage: f64,
+) -> Result<Vec<f64>, IndicatorError> {
+    if positions.len() != strategy_returns.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`positions` and `strategy_returns` must have the same length".to_string(),
+        ));
+    }
+    if window < 2
+        || [target, periods_per_year, max_leverage]
+            .iter()
+            .any(|v| v.is_nan() || *v <= 0.0)
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`window` must be at least two and `target`, `periods_per_year`, and `max_leverage` must be positive"
+                .to_string(),
+        ));
+    }
+    if positions.len() <= window {
+        return Err(IndicatorError::NotEnoughData(format!(
+            "More than {} periods are required",
+            window
+        )));
+    }
+
+    let mut overlay = VolatilityTarget::new(target, window, periods_per_year, max_leverage);
+    strategy_returns[..window]
+        .iter()
+        .for_each(|r| overlay.push(*r));
+    let mut result = Vec::with_capacity(positions.len() - window);
+    for (position, r) in positions[window..].iter().zip(&strategy_returns[window..]) {
+        result.extend(overlay.size(*position));
+        overlay.push(*r);
+    }
+
+    Ok(result)
+}
+
+/// Limits on how far a rebalance moves the portfolio toward its target, used by
+/// [`plan_rebalance`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurnoverLimit {
+    /// Cost charged per unit of turnover against the squared distance to the target weights.
+    /// Every weight change is shrunk by half the penalty, and changes smaller than that are not
+    /// traded at all.
+    pub penalty: f64,
+    /// Largest turnover allowed in one rebalance, if any. Larger rebalances are scaled down
+    /// proportionally.
+    pub max_turnover: Option<f64>,
+}
+
+/// A rebalance produced by [`plan_rebalance`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rebalance {
+    /// Signed number of shares to trade in every asset, a whole number of lots.
+    pub shares: Vec<f64>,
+    /// Weights of the portfolio after the trades.
+    pub weights: Vec<f64>,
+    /// Sum of the absolute weight changes of the trades.
+    pub turnover: f64,
+}
+
+/// Plans the trades that move a portfolio from its current weights toward target weights, such
+/// as those of an optimizer or of factor scores, under a turnover penalty and limit.
+///
+/// The weight changes minimize the squared distance to the target plus `penalty` times the
+/// turnover, and are then scaled down to `max_turnover`. Each change is converted into shares at
+/// `prices` and rounded toward zero to whole lots, so the rounded rebalance never exceeds the
+/// limit. Weights are fractions of `equity`, and whatever the trades do not invest stays in cash.
+///
+/// # Arguments
+///
+/// * `current_weights` - The current weight of every asset.
+/// * `target_weights` - The target weight of every asset.
+/// * `prices` - The price of one share of every asset.
+/// * `lot_sizes` - The number of shares of one tradable lot of every asset.
+/// * `equity` - The value of the portfolio.
+/// * `limit` - The turnover penalty and limit.
+///
+/// # Returns
+///
+/// A Result containing the [`Rebalance`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if no assets are provided, an
+/// `IndicatorError::MismatchedLength` if the inputs differ in length, and an
+/// `IndicatorError::InvalidInput` if `equity`, a price, or a lot size is not positive, or the
+/// penalty or turnover limit is negative.
+pub fn plan_rebalance(
+    current_weights: &[f64],
+    target_weights: &[f64],
+    prices: &[f64],
+    lot_sizes: &[f64],
+    equity: f64,
+    limit: TurnoverLimit,
+) -> Result<Rebalance, IndicatorError> {
+    if current_weights.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one asset is required".to_string(),
+        ));
+    }
+    let assets = current_weights.len();
+    if [target_weights, prices, lot_sizes]
+        .iter()
+        .any(|s| s.len() != assets)
+    {
+        return Err(IndicatorError::MismatchedLength(
+            "Every input must cover the same assets".to_string(),
+        ));
+    }
+    if prices
+        .iter()
+        .chain(lot_sizes)
+        .chain([&equity])
+        .any(|v| v.is_nan() || *v <= 0.0)
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`equity`, `prices`, and `lot_sizes` must be positive".to_string(),
+        ));
+    }
+    let max_turnover = limit.max_turnover.unwrap_or(f64::INFINITY);
+    if limit.penalty.is_nan() || limit.penalty < 0.0 || max_turnover.is_nan() || max_turnover < 0.0
+    {
+        return Err(IndicatorError::InvalidInput(
+            "The turnover penalty and limit must not be negative".to_string(),
+        ));
+    }
+
+    let mut changes = current_weights
+        .iter()
+        .zip(target_weights)
+        .map(|(current, target)| {
+            let change = target - current;
+            change.signum() * (change.abs() - limit.penalty / 2.0).max(0.0)
+        })
+        .collect::<Vec<f64>>();
+    let turnover = changes.iter().map(|c| c.abs()).sum::<f64>();
+    if turnover > max_turnover {
+        changes
+            .iter_mut()
+            .for_each(|c| *c *= max_turnover / turnover);
+    }
+
+    let mut result = Rebalance {
+        shares: Vec::with_capacity(assets),
+        weights: Vec::with_capacity(assets),
+        turnover: 0.0,
+    };
+    for i in 0..assets {
+        let lots = changes[i] * equity / prices[i] / lot_sizes[i];
+        // Tolerate the rounding error of changes that are exactly a whole number of lots
+        let shares = lots.signum() * (lots.abs() + 1e-9).floor() * lot_sizes[i];
+        let change = shares * prices[i] / equity;
+        result.shares.push(shares);
+        result.weights.push(current_weights[i] + change);
+        result.turnover += change.abs();
+    }
+
+    Ok(result)
+}
+
+/// Whole lot holdings produced by [`allocate_lots`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    /// Number of shares held in every asset, a whole number of lots.
+    pub shares: Vec<f64>,
+    /// Weight of every asset at the allocated shares.
+    pub weights: Vec<f64>,
+    /// Equity left uninvested.
+    pub cash: f64,
+}
+
+/// Converts target weights into holdings of whole lots that track the targets as closely as the
+/// equity allows.
+///
+/// Every holding starts at the largest number of lots that does not exceed its target. The
+/// residual cash then buys, one lot at a time, the lot that most reduces the sum of squared
+/// deviations from the target weights, until no affordable lot reduces it further. Holdings never
+/// cost more than `equity`, so the allocation needs no borrowing.
+///
+/// # Arguments
+///
+/// * `target_weights` - The target weight of every asset.
+/// * `prices` - The price of one share of every asset.
+/// * `lot_sizes` - The number of shares of one tradable lot of every asset, e.g. `1.0` for whole
+///   shares.
+/// * `equity` - The value to allocate.
+///
+/// # Returns
+///
+/// A Result containing the [`Allocation`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if no assets are provided, an
+/// `IndicatorError::MismatchedLength` if the inputs differ in length, and an
+/// `IndicatorError::InvalidInput` if `equity`, a price, or a lot size is not positive, or a target
+/// weight is negative or the targets sum to more than one.
+pub fn allocate_lots(
+    target_weights: &[f64],
+    prices: &[f64],
+    lot_sizes: &[f64],
+    equity: f64,
+) -> Result<Allocation, IndicatorError> {
+    if target_weights.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one asset is required".to_string(),
+        ));
+    }
+    let assets = target_weights.len();
+    if prices.len() != assets || lot_sizes.len() != assets {
+        return Err(IndicatorError::MismatchedLength(
+            "Every input must cover the same assets".to_string(),
+        ));
+    }
+    if prices
+        .iter()
+        .chain(lot_sizes)
+        .chain([&equity])
+        .any(|v| v.is_nan() || *v <= 0.0)
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`equity`, `prices`, and `lot_sizes` must be positive".to_string(),
+        ));
+    }
+    if target_weights.iter().any(|w| w.is_nan() || *w < 0.0)
+        || target_weights.iter().sum::<f64>() > 1.0 + 1e-9
+    {
+        return Err(IndicatorError::InvalidInput(
+            "`target_weights` must not be negative and must not sum to more than one".to_string(),
+        ));
+    }
+
+    let lot_values = prices
+        .iter()
+        .zip(lot_sizes)
+        .map(|(price, lot)| price * lot)
+        .collect::<Vec<f64>>();
+    let mut lots = target_weights
+        .iter()
+        .zip(&lot_values)
+        .map(|(weight, value)| (weight * equity / value + 1e-9).floor())
+        .collect::<Vec<f64>>();
+    let mut cash = equity
+        - lots
+            .iter()
+            .zip(&lot_values)
+            .map(|(n, v)| n * v)
+            .sum::<f64>();
+
+    loop {
+        // Change of the squared deviation from adding one lot of each affordable asset
+        let best = (0..assets)
+            .filter(|i| lot_values[*i] <= cash + 1e-9)
+            .map(|i| {
+                let step = lot_values[i] / equity;
+                let gap = lots[i] * step - target_weights[i];
+                (i, step * (2.0 * gap + step))
+            })
+            .filter(|(_, improvement)| *improvement < 0.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((i, _)) = best else {
+            break;
+        };
+        lots[i] += 1.0;
+        cash -= lot_values[i];
+    }
+
+    Ok(Allocation {
+        shares: lots.iter().zip(lot_sizes).map(|(n, lot)| n * lot).collect(),
+        weights: lots
+            .iter()
+            .zip(&lot_values)
+            .map(|(n, value)| n * value / equity)
+            .collect(),
+        cash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_portfolio_returns() {
+        // Test case with enough data
+        let stocks = [0.01, -0.02, 0.03];
+        let bonds = [0.02, 0.01, -0.01];
+        let stock_weights = [0.5, 0.4, 0.6];
+        let bond_weights = [0.3, 0.3, 0.2];
+        let result = calculate_portfolio_returns(
+            &[&stocks, &bonds],
+            &[&stock_weights, &bond_weights],
+            0.001,
+        )
+        .unwrap();
+        let expected = [
+            (0.0112, 0.2, 0.00255),
+            (-0.0047, 0.3, -0.0024428571428571433),
+            (0.0162, 0.2, 0.0038),
+        ];
+        for (i, (returns, cash, drag)) in expected.iter().enumerate() {
+            assert!((result.returns[i] - returns).abs() < 1e-12);
+            assert!((result.cash_weights[i] - cash).abs() < 1e-12);
+            assert!((result.cash_drag[i] - drag).abs() < 1e-12);
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_portfolio_returns(&[&stocks, &bonds], &[&stock_weights], 0.001);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_fx_attribution() {
+        // Test case with enough data
+        let foreign_local = [0.02, -0.01];
+        let foreign_fx = [0.01, 0.02];
+        let domestic_local = [0.01, 0.03];
+        let domestic_fx = [0.0, 0.0];
+        let foreign_weights = [0.6, 0.5];
+        let domestic_weights = [0.4, 0.5];
+        let result = calculate_fx_attribution(
+            &[&foreign_local, &domestic_local],
+            &[&foreign_fx, &domestic_fx],
+            &[&foreign_weights, &domestic_weights],
+        )
+        .unwrap();
+        let expected = [
+            (0.016, 0.006, 0.00012, 0.02212),
+            (0.01, 0.01, -0.0001, 0.0199),
+        ];
+        for (i, (local, currency, interaction, total)) in expected.iter().enumerate() {
+            assert!((result.local[i] - local).abs() < 1e-12);
+            assert!((result.currency[i] - currency).abs() < 1e-12);
+            assert!((result.interaction[i] - interaction).abs() < 1e-12);
+            assert!((result.total[i] - total).abs() < 1e-12);
+        }
+
+        // Test case with mismatched lengths
+        let result =
+            calculate_fx_attribution(&[&foreign_local], &[&foreign_fx[1..]], &[&foreign_weights]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_apply_volatility_target() {
+        // Test case with enough data
+        let positions = [1.0, 1.0, 1.0, -1.0, 1.0];
+        let returns = [0.01, -0.01, 0.02, 0.03, 0.01];
+        let result = apply_volatility_target(&positions, &returns, 0.1, 3, 252.0, 2.0).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!((result[0] + 0.412_393_049_421_161_3).abs() < 1e-12);
+        assert!((result[1] - 0.302_613_766_334_401_24).abs() < 1e-12);
+
+        // The leverage is capped when the volatility is zero
+        let mut overlay = VolatilityTarget::new(0.1, 2, 252.0, 3.0);
+        overlay.push(0.01);
+        assert_eq!(overlay.size(1.0), None);
+        overlay.push(0.01);
+        assert_eq!(overlay.volatility(), Some(0.0));
+        assert_eq!(overlay.size(-0.5), Some(-1.5));
+
+        // Test case with not enough data
+        let result = apply_volatility_target(&positions, &returns, 0.1, 5, 252.0, 2.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = apply_volatility_target(&positions[1..], &returns, 0.1, 2, 252.0, 2.0);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_plan_rebalance() {
+        // Test case with enough data
+        let current = [0.5, 0.3, 0.2];
+        let target = [0.3, 0.3, 0.4];
+        let prices = [50.0, 20.0, 100.0];
+        let lots = [1.0, 1.0, 30.0];
+        let limit = TurnoverLimit {
+            penalty: 0.02,
+            max_turnover: Some(0.2),
+        };
+        let result = plan_rebalance(&current, &target, &prices, &lots, 100_000.0, limit).unwrap();
+        assert_eq!(result.shares, vec![-200.0, 0.0, 90.0]);
+        let expected = [0.4, 0.3, 0.29];
+        for (weight, expected) in result.weights.iter().zip(expected) {
+            assert!((weight - expected).abs() < 1e-12);
+        }
+        assert!((result.turnover - 0.19).abs() < 1e-12);
+
+        // Without limits the portfolio reaches the targets that are whole lots
+        let limit = TurnoverLimit {
+            penalty: 0.0,
+            max_turnover: None,
+        };
+        let result = plan_rebalance(&current, &target, &prices, &lots, 100_000.0, limit).unwrap();
+        assert_eq!(result.shares, vec![-400.0, 0.0, 180.0]);
+
+        // Test case with not enough data
+        let result = plan_rebalance(&[], &[], &[], &[], 100_000.0, limit);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = plan_rebalance(&current, &target[..2], &prices, &lots, 100_000.0, limit);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let result = plan_rebalance(
+            &current,
+            &target,
+            &prices,
+            &[1.0, 0.0, 1.0],
+            100_000.0,
+            limit,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_allocate_lots() {
+        // Test case with enough data
+        let targets = [0.5, 0.3, 0.2];
+        let prices = [33.0, 120.0, 7.5];
+        let lots = [1.0, 1.0, 100.0];
+        let result = allocate_lots(&targets, &prices, &lots, 10_000.0).unwrap();
+        assert_eq!(result.shares, vec![152.0, 25.0, 200.0]);
+        let expected = [0.5016, 0.3, 0.15];
+        for (weight, expected) in result.weights.iter().zip(expected) {
+            assert!((weight - expected).abs() < 1e-12);
+        }
+        assert!((result.cash - 484.0).abs() < 1e-9);
+
+        // Test case with not enough data
+        let result = allocate_lots(&[], &[], &[], 10_000.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = allocate_lots(&targets, &prices[..2], &lots, 10_000.0);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let result = allocate_lots(&[0.6, 0.3, 0.2], &prices, &lots, 10_000.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,393 @@
+//! Regression based analytics such as rolling factor models.
+
+use crate::{linalg, IndicatorError};
+
+/// Time-varying coefficients produced by [`calculate_rolling_ols`].
+///
+/// Every field holds one entry per window. The entry at index `i` describes the window that ends
+/// at index `i + window - 1` of the input series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollingOls {
+    /// Intercept (alpha) of each window's regression.
+    pub alpha: Vec<f64>,
+    /// Factor loadings (betas) of each window's regression, ordered like the input factors.
+    pub betas: Vec<Vec<f64>>,
+    /// Coefficient of determination (R²) of each window's regression. `NaN` if the returns are
+    /// constant over the window.
+    pub r_squared: Vec<f64>,
+}
+
+/// Result of a single ordinary least squares fit.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OlsFit {
+    pub(crate) alpha: f64,
+    pub(crate) betas: Vec<f64>,
+    pub(crate) r_squared: f64,
+}
+
+/// Fits `returns = alpha + betas · factors` by ordinary least squares.
+///
+/// All slices are expected to have the same length, which must exceed the number of factors.
+pub(crate) fn fit_ols(returns: &[f64], factors: &[&[f64]]) -> Result<OlsFit, IndicatorError> {
+    let k = factors.len() + 1;
+
+    // Build the normal equations X'X b = X'y, where the first column of X is the intercept
+    let regressors = |row: usize| {
+        std::iter::once(1.0)
+            .chain(factors.iter().map(|f| f[row]))
+            .collect::<Vec<f64>>()
+    };
+    let mut xtx = vec![vec![0.0; k]; k];
+    let mut xty = vec![0.0; k];
+    for (row, y) in returns.iter().enumerate() {
+        let x = regressors(row);
+        for ((xi, sums), xty) in x.iter().zip(xtx.iter_mut()).zip(xty.iter_mut()) {
+            *xty += xi * y;
+            for (xj, sum) in x.iter().zip(sums.iter_mut()) {
+                *sum += xi * xj;
+            }
+        }
+    }
+
+    let coefficients = linalg::solve(xtx, xty).ok_or_else(|| {
+        IndicatorError::SingularMatrix(
+            "Factors are collinear over the regression window".to_string(),
+        )
+    })?;
+
+    // Compare the residual and total sums of squares
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let mut sse = 0.0;
+    let mut sst = 0.0;
+    for (row, y) in returns.iter().enumerate() {
+        let fitted = regressors(row)
+            .iter()
+            .zip(&coefficients)
+            .map(|(x, b)| x * b)
+            .sum::<f64>();
+        sse += (y - fitted).powi(2);
+        sst += (y - mean).powi(2);
+    }
+    let r_squared = if sst > 0.0 { 1.0 - sse / sst } else { f64::NAN };
+
+    Ok(OlsFit {
+        alpha: coefficients[0],
+        betas: coefficients[1..].to_vec(),
+        r_squared,
+    })
+}
+
+/// Calculates a rolling multiple linear regression of returns against a set of factor returns.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of asset returns.
+/// * `factors` - One slice of factor returns per factor, each aligned with `returns`.
+/// * `window` - The number of observations in each regression.
+///
+/// # Returns
+///
+/// A Result containing the [`RollingOls`] coefficients or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if any factor does not have the same length as
+/// `returns`, an `IndicatorError::NotEnoughData` if `window` is not greater than the number of
+/// factors or the length of `returns` is less than `window`, and an
+/// `IndicatorError::SingularMatrix` if the factors are collinear within any window.
+pub fn calculate_rolling_ols(
+    returns: &[f64],
+    factors: &[&[f64]],
+    window: usize,
+) -> Result<RollingOls, IndicatorError> {
+    if factors.iter().any(|f| f.len() != returns.len()) {
+        return Err(IndicatorError::MismatchedLength(
+            "Every factor must have the same length as `returns`".to_string(),
+        ));
+    }
+    if window <= factors.len() || returns.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "Not enough data points to calculate rolling OLS".to_string(),
+        ));
+    }
+
+    let count = returns.len() - window + 1;
+    let mut result = RollingOls {
+        alpha: Vec::with_capacity(count),
+        betas: Vec::with_capacity(count),
+        r_squared: Vec::with_capacity(count),
+    };
+    for start in 0..count {
+        let end = start + window;
+        let window_factors = factors.iter().map(|f| &f[start..end]).collect::<Vec<_>>();
+        let fit = fit_ols(&returns[start..end], &window_factors)?;
+        result.alpha.push(fit.alpha);
+        result.betas.push(fit.betas);
+        result.r_squared.push(fit.r_squared);
+    }
+
+    Ok(result)
+}
+
+/// Style exposures produced by [`calculate_style_analysis`].
+///
+/// Every field holds one entry per window. The entry at index `i` describes the window that ends
+/// at index `i + window - 1` of the input series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleAnalysis {
+    /// Exposure to each style benchmark, ordered like the input benchmarks. The weights of every
+    /// window are non-negative and sum to one.
+    pub weights: Vec<Vec<f64>>,
+    /// Share of the return variance explained by the style portfolio (R²). `NaN` if the returns
+    /// are constant over the window.
+    pub r_squared: Vec<f64>,
+}
+
+/// Maximum number of gradient steps used to fit the style weights of a single window.
+const STYLE_MAX_ITERATIONS: usize = 10_000;
+
+/// Projects `v` onto the probability simplex, the set of non-negative vectors summing to one.
+fn project_to_simplex(v: &[f64]) -> Vec<f64> {
+    let mut sorted = v.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+
+    let mut cumulative = 0.0;
+    let mut theta = 0.0;
+    for (j, u) in sorted.iter().enumerate() {
+        cumulative += u;
+        let candidate = (cumulative - 1.0) / (j + 1) as f64;
+        if u - candidate > 0.0 {
+            theta = candidate;
+        }
+    }
+
+    v.iter().map(|x| (x - theta).max(0.0)).collect()
+}
+
+/// Finds the simplex constrained weights minimizing the variance of `returns - weights ·
+/// benchmarks` using accelerated projected gradient descent.
+fn fit_style(returns: &[f64], benchmarks: &[&[f64]]) -> (Vec<f64>, f64) {
+    let n = returns.len() as f64;
+    let k = benchmarks.len();
+    let demean = |x: &[f64]| {
+        let mean = x.iter().sum::<f64>() / n;
+        x.iter().map(|v| v - mean).collect::<Vec<f64>>()
+    };
+    let y = demean(returns);
+    let x = benchmarks.iter().map(|b| demean(b)).collect::<Vec<_>>();
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f64>();
+
+    // Objective is w'Qw - 2c'w, up to a constant
+    let q = x
+        .iter()
+        .map(|xi| x.iter().map(|xj| dot(xi, xj)).collect::<Vec<f64>>())
+        .collect::<Vec<_>>();
+    let c = x.iter().map(|xi| dot(xi, &y)).collect::<Vec<f64>>();
+    let q_times = |w: &[f64]| q.iter().map(|row| dot(row, w)).collect::<Vec<f64>>();
+
+    // The trace of Q bounds its largest eigenvalue, giving a safe step size
+    let trace = (0..k).map(|i| q[i][i]).sum::<f64>();
+    let step = if trace > 0.0 { 0.5 / trace } else { 0.0 };
+
+    let mut weights = vec![1.0 / k as f64; k];
+    let mut momentum = weights.clone();
+    let mut t = 1.0_f64;
+    for _ in 0..STYLE_MAX_ITERATIONS {
+        let gradient = q_times(&momentum)
+            .iter()
+            .zip(&c)
+            .map(|(qw, c)| 2.0 * (qw - c))
+            .collect::<Vec<f64>>();
+        let next = project_to_simplex(
+            &momentum
+                .iter()
+                .zip(&gradient)
+                .map(|(w, g)| w - step * g)
+                .collect::<Vec<f64>>(),
+        );
+
+        let t_next = (1.0 + (1.0 + 4.0 * t * t).sqrt()) / 2.0;
+        let change = next
+            .iter()
+            .zip(&weights)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        momentum = next
+            .iter()
+            .zip(&weights)
+            .map(|(a, b)| a + (t - 1.0) / t_next * (a - b))
+            .collect();
+        weights = next;
+        t = t_next;
+        if change < 1e-14 {
+            break;
+        }
+    }
+
+    let sst = dot(&y, &y);
+    let residual = y
+        .iter()
+        .enumerate()
+        .map(|(row, y)| y - x.iter().zip(&weights).map(|(x, w)| x[row] * w).sum::<f64>())
+        .collect::<Vec<f64>>();
+    let r_squared = if sst > 0.0 {
+        1.0 - dot(&residual, &residual) / sst
+    } else {
+        f64::NAN
+    };
+
+    (weights, r_squared)
+}
+
+/// Calculates a rolling returns-based style analysis of a fund against a set of style benchmarks.
+///
+/// For every window the style weights are chosen to minimize the variance of the difference
+/// between the fund returns and the weighted benchmark returns, subject to the weights being
+/// non-negative and summing to one.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of fund returns.
+/// * `benchmarks` - One slice of returns per style benchmark, each aligned with `returns`.
+/// * `window` - The number of observations in each analysis window.
+///
+/// # Returns
+///
+/// A Result containing the [`StyleAnalysis`] exposures or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if any benchmark does not have the same length
+/// as `returns`, and an `IndicatorError::NotEnoughData` if no benchmarks are provided, `window` is
+/// less than two, or the length of `returns` is less than `window`.
+pub fn calculate_style_analysis(
+    returns: &[f64],
+    benchmarks: &[&[f64]],
+    window: usize,
+) -> Result<StyleAnalysis, IndicatorError> {
+    if benchmarks.iter().any(|b| b.len() != returns.len()) {
+        return Err(IndicatorError::MismatchedLength(
+            "Every benchmark must have the same length as `returns`".to_string(),
+        ));
+    }
+    if benchmarks.is_empty() || window < 2 || returns.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "Not enough data points to calculate style analysis".to_string(),
+        ));
+    }
+
+    let count = returns.len() - window + 1;
+    let mut result = StyleAnalysis {
+        weights: Vec::with_capacity(count),
+        r_squared: Vec::with_capacity(count),
+    };
+    for start in 0..count {
+        let end = start + window;
+        let window_benchmarks = benchmarks
+            .iter()
+            .map(|b| &b[start..end])
+            .collect::<Vec<_>>();
+        let (weights, r_squared) = fit_style(&returns[start..end], &window_benchmarks);
+        result.weights.push(weights);
+        result.r_squared.push(r_squared);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_rolling_ols() {
+        // Test case with enough data
+        let market = [0.01, -0.02, 0.015, 0.03, -0.01, 0.005, 0.02];
+        let size = [0.002, 0.01, -0.004, 0.006, 0.001, -0.008, 0.003];
+        let returns = market
+            .iter()
+            .zip(&size)
+            .map(|(m, s)| 0.001 + 1.5 * m - 0.5 * s)
+            .collect::<Vec<f64>>();
+        let result = calculate_rolling_ols(&returns, &[&market, &size], 5).unwrap();
+        assert_eq!(result.alpha.len(), 3);
+        for i in 0..3 {
+            assert!((result.alpha[i] - 0.001).abs() < 1e-9);
+            assert!((result.betas[i][0] - 1.5).abs() < 1e-9);
+            assert!((result.betas[i][1] + 0.5).abs() < 1e-9);
+            assert!((result.r_squared[i] - 1.0).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_rolling_ols(&returns, &[&market, &size], 2);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_rolling_ols(&returns, &[&market[1..]], 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with collinear factors
+        let doubled = market.iter().map(|m| m * 2.0).collect::<Vec<f64>>();
+        let result = calculate_rolling_ols(&returns, &[&market, &doubled], 5);
+        match result {
+            Err(IndicatorError::SingularMatrix(_)) => (),
+            _ => {
+                panic!(
+                    "Expected `IndicatorError::SingularMatrix`, found different `IndicatorError`"
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_style_analysis() {
+        // Test case with enough data
+        let growth = [0.02, -0.01, 0.03, 0.01, -0.02, 0.015, 0.005, -0.01];
+        let value = [0.01, 0.005, -0.01, 0.02, 0.0, -0.005, 0.01, 0.02];
+        let bonds = [0.001, 0.002, 0.0, -0.001, 0.003, 0.001, -0.002, 0.0];
+        let returns = growth
+            .iter()
+            .zip(&value)
+            .map(|(g, v)| 0.6 * g + 0.4 * v)
+            .collect::<Vec<f64>>();
+        let result = calculate_style_analysis(&returns, &[&growth, &value, &bonds], 6).unwrap();
+        assert_eq!(result.weights.len(), 3);
+        for (weights, r_squared) in result.weights.iter().zip(&result.r_squared) {
+            assert!((weights[0] - 0.6).abs() < 1e-6);
+            assert!((weights[1] - 0.4).abs() < 1e-6);
+            assert!(weights[2].abs() < 1e-6);
+            assert!((r_squared - 1.0).abs() < 1e-9);
+        }
+
+        // Test case with an exposure outside the simplex
+        let returns = growth
+            .iter()
+            .zip(&value)
+            .map(|(g, v)| 1.3 * g - 0.3 * v)
+            .collect::<Vec<f64>>();
+        let result = calculate_style_analysis(&returns, &[&growth, &value], 8).unwrap();
+        let weights = &result.weights[0];
+        assert!(weights.iter().all(|w| *w >= 0.0));
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((weights[0] - 1.0).abs() < 1e-6);
+
+        // Test case with not enough data
+        let result = calculate_style_analysis(&returns, &[&growth, &value], 9);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+}
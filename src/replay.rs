@@ -0,0 +1,190 @@
+//! Playback of stored series as timed events, for demos and for testing live integrations without
+//! a market connection.
+//!
+//! A [`Replay`] yields the observations of a [`Series`] together with the wall time to wait before
+//! each of them, and [`replay_series`] waits that long, moves a [`SimulatedClock`] to the time of
+//! the observation, and hands it to a callback, e.g. one feeding a
+//! [`StreamingIndicator`](crate::streaming::StreamingIndicator) or an
+//! [`AlertMonitor`](crate::alerts::AlertMonitor).
+
+use std::time::Duration;
+
+use crate::clock::SimulatedClock;
+use crate::series::Series;
+use crate::IndicatorError;
+
+/// How fast a [`Replay`] plays back a series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Compress the time between observations by the given factor, 1 being real time and 10 ten
+    /// times faster
+    Multiple(f64),
+    /// Play back every observation without waiting
+    Max,
+}
+
+/// An observation played back by a [`Replay`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayEvent {
+    /// Unix timestamp of the observation in seconds
+    pub time: i64,
+    /// The observation
+    pub value: f64,
+    /// Wall time to wait after the previous event before this one
+    pub delay: Duration,
+}
+
+/// An iterator over the observations of a series as timed events.
+#[derive(Debug, Clone)]
+pub struct Replay<'a> {
+    series: &'a Series,
+    speed: ReplaySpeed,
+    index: usize,
+}
+
+impl<'a> Replay<'a> {
+    /// Creates a replay of `series` at `speed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if the factor of a [`ReplaySpeed::Multiple`] is
+    /// not positive and finite.
+    pub fn new(series: &'a Series, speed: ReplaySpeed) -> Result<Self, IndicatorError> {
+        if let ReplaySpeed::Multiple(factor) = speed {
+            if !factor.is_finite() || factor <= 0.0 {
+                return Err(IndicatorError::InvalidInput(
+                    "The replay speed must be positive and finite".to_string(),
+                ));
+            }
+        }
+        Ok(Replay {
+            series,
+            speed,
+            index: 0,
+        })
+    }
+}
+
+impl Iterator for Replay<'_> {
+    type Item = ReplayEvent;
+
+    fn next(&mut self) -> Option<ReplayEvent> {
+        let i = self.index;
+        let time = *self.series.timestamps().get(i)?;
+        self.index += 1;
+
+        let delay = match (self.speed, i.checked_sub(1)) {
+            (ReplaySpeed::Multiple(factor), Some(previous)) => {
+                let gap = (time - self.series.timestamps()[previous]) as f64;
+                Duration::from_secs_f64(gap / factor)
+            }
+            _ => Duration::ZERO,
+        };
+        Some(ReplayEvent {
+            time,
+            value: self.series.values()[i],
+            delay,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.series.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Plays back a series in real time, scaled by `speed`, blocking the calling thread.
+///
+/// Before every observation the thread sleeps for the delay of the event and `clock` is set to the
+/// time of the observation, so components reading `clock` see the replayed time.
+///
+/// # Arguments
+///
+/// * `series` - The series to play back.
+/// * `speed` - How fast to play back the series.
+/// * `clock` - The clock moved to the time of every observation.
+/// * `on_event` - Called with every observation.
+///
+/// # Returns
+///
+/// A Result containing the number of replayed observations or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns the errors of [`Replay::new`].
+pub fn replay_series<F>(
+    series: &Series,
+    speed: ReplaySpeed,
+    clock: &SimulatedClock,
+    mut on_event: F,
+) -> Result<usize, IndicatorError>
+where
+    F: FnMut(&ReplayEvent),
+{
+    let mut count = 0;
+    for event in Replay::new(series, speed)? {
+        if !event.delay.is_zero() {
+            std::thread::sleep(event.delay);
+        }
+        clock.set(event.time);
+        on_event(&event);
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::{AlertMonitor, Condition};
+    use crate::clock::Clock;
+
+    #[test]
+    fn test_replay() {
+        let series = Series::new(vec![0, 60, 180], vec![1.0, 2.0, 3.0]).unwrap();
+
+        // Test case with a faster than real time replay
+        let delays = Replay::new(&series, ReplaySpeed::Multiple(60.0))
+            .unwrap()
+            .map(|event| event.delay)
+            .collect::<Vec<Duration>>();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::ZERO,
+                Duration::from_secs(1),
+                Duration::from_secs(2)
+            ]
+        );
+        assert!(Replay::new(&series, ReplaySpeed::Max)
+            .unwrap()
+            .all(|event| event.delay.is_zero()));
+
+        // Test case with an invalid speed
+        let result = Replay::new(&series, ReplaySpeed::Multiple(0.0));
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_series() {
+        let series = Series::new(vec![10, 20, 30], vec![1.0, 5.0, 2.0]).unwrap();
+        let clock = SimulatedClock::new(0);
+        let mut monitor = AlertMonitor::new();
+        monitor.add_rule("high", "price", Condition::Above(4.0), |_| ());
+
+        let mut alerts = Vec::new();
+        let count = replay_series(&series, ReplaySpeed::Max, &clock, |event| {
+            alerts.extend(monitor.update_with_clock(&clock, "price", event.value));
+        })
+        .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(clock.now(), 30);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].time, 20);
+    }
+}
@@ -0,0 +1,303 @@
+//! Resampling and alignment of timestamped series with different observation frequencies.
+//!
+//! Timestamps are Unix timestamps in seconds and calendar periods are evaluated in UTC. Weeks
+//! start on Monday, and intraday periods of a fixed length are counted from the Unix epoch, so four
+//! hour periods start at 00:00, 04:00, 08:00, and so on.
+
+use crate::calendar::{civil_from_days, days_from_civil, SECONDS_PER_DAY};
+use crate::IndicatorError;
+
+/// Frequency that series can be resampled to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// One period per given number of minutes, e.g. `Minutes(15)` for fifteen minute bars
+    Minutes(u32),
+    /// One period per given number of hours, e.g. `Hours(4)` for four hour bars
+    Hours(u32),
+    /// One period per calendar day
+    Daily,
+    /// One period per week, starting on Monday
+    Weekly,
+    /// One period per calendar month
+    Monthly,
+    /// One period per calendar quarter, starting in January, April, July, and October
+    Quarterly,
+    /// One period per calendar year
+    Yearly,
+}
+
+impl Frequency {
+    /// Returns the timestamp at which the period containing `timestamp` starts.
+    ///
+    /// Intraday periods with a length of zero are treated as one second long, and are rejected by
+    /// [`resample`] and [`align`].
+    pub fn period_start(&self, timestamp: i64) -> i64 {
+        let days = timestamp.div_euclid(SECONDS_PER_DAY);
+        let start = match self {
+            Frequency::Minutes(minutes) => return fixed_period_start(timestamp, 60, *minutes),
+            Frequency::Hours(hours) => return fixed_period_start(timestamp, 3_600, *hours),
+            Frequency::Daily => days,
+            // 1970-01-01 was a Thursday, so Mondays fall three days before multiples of seven
+            Frequency::Weekly => (days + 3).div_euclid(7) * 7 - 3,
+            Frequency::Monthly => {
+                let (year, month, _) = civil_from_days(days);
+                days_from_civil(year, month, 1)
+            }
+            Frequency::Quarterly => {
+                let (year, month, _) = civil_from_days(days);
+                days_from_civil(year, (month - 1) / 3 * 3 + 1, 1)
+            }
+            Frequency::Yearly => {
+                let (year, _, _) = civil_from_days(days);
+                days_from_civil(year, 1, 1)
+            }
+        };
+        start * SECONDS_PER_DAY
+    }
+}
+
+/// Returns the start of the period containing `timestamp` among periods of `count` units of `unit`
+/// seconds counted from the Unix epoch.
+fn fixed_period_start(timestamp: i64, unit: i64, count: u32) -> i64 {
+    let length = (unit * i64::from(count)).max(1);
+    timestamp.div_euclid(length) * length
+}
+
+/// A series sampled once per period by [`resample`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResampledSeries {
+    /// Timestamp of the observation that was sampled for each period.
+    pub timestamps: Vec<i64>,
+    /// Value of the observation that was sampled for each period.
+    pub values: Vec<f64>,
+}
+
+/// Two series sampled onto a common calendar by [`align`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedSeries {
+    /// Start timestamp of every period in which both series have an observation.
+    pub periods: Vec<i64>,
+    /// End-of-period values of the first series.
+    pub left: Vec<f64>,
+    /// End-of-period values of the second series.
+    pub right: Vec<f64>,
+}
+
+/// Checks that `timestamps` and `values` are parallel and that timestamps strictly increase.
+pub(crate) fn validate(timestamps: &[i64], values: &[f64]) -> Result<(), IndicatorError> {
+    if timestamps.len() != values.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`timestamps` and `values` must have the same length".to_string(),
+        ));
+    }
+    if timestamps.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(IndicatorError::InvalidInput(
+            "`timestamps` must be strictly increasing".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that an intraday `frequency` has a positive length.
+fn validate_frequency(frequency: Frequency) -> Result<(), IndicatorError> {
+    if let Frequency::Minutes(0) | Frequency::Hours(0) = frequency {
+        return Err(IndicatorError::InvalidInput(
+            "Intraday frequencies must have a positive length".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Resamples a series to a lower frequency using end-of-period sampling.
+///
+/// Every period that contains at least one observation is represented by its last observation,
+/// keeping that observation's own timestamp. Periods without observations are omitted rather than
+/// filled.
+///
+/// # Arguments
+///
+/// * `timestamps` - Strictly increasing Unix timestamps in seconds.
+/// * `values` - The observation at each timestamp.
+/// * `frequency` - The frequency to resample to.
+///
+/// # Returns
+///
+/// A Result containing the [`ResampledSeries`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `timestamps` and `values` differ in length,
+/// and an `IndicatorError::InvalidInput` if `timestamps` is not strictly increasing or an intraday
+/// `frequency` has a length of zero.
+pub fn resample(
+    timestamps: &[i64],
+    values: &[f64],
+    frequency: Frequency,
+) -> Result<ResampledSeries, IndicatorError> {
+    validate(timestamps, values)?;
+    validate_frequency(frequency)?;
+
+    let mut result = ResampledSeries {
+        timestamps: Vec::new(),
+        values: Vec::new(),
+    };
+    for (i, (timestamp, value)) in timestamps.iter().zip(values).enumerate() {
+        let is_period_end = timestamps
+            .get(i + 1)
+            .is_none_or(|next| frequency.period_start(*next) != frequency.period_start(*timestamp));
+        if is_period_end {
+            result.timestamps.push(*timestamp);
+            result.values.push(*value);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Aligns two series of possibly different frequencies onto a common calendar.
+///
+/// Both series are resampled to `frequency` with [`resample`], so each period is represented by
+/// the last observation of each series within it. Only periods in which both series have an
+/// observation are kept.
+///
+/// # Arguments
+///
+/// * `left_timestamps` - Strictly increasing Unix timestamps in seconds of the first series.
+/// * `left_values` - The observations of the first series.
+/// * `right_timestamps` - Strictly increasing Unix timestamps in seconds of the second series.
+/// * `right_values` - The observations of the second series.
+/// * `frequency` - The common frequency to align both series to.
+///
+/// # Returns
+///
+/// A Result containing the [`AlignedSeries`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the timestamps and values of either series
+/// differ in length, and an `IndicatorError::InvalidInput` if the timestamps of either series are
+/// not strictly increasing or an intraday `frequency` has a length of zero.
+pub fn align(
+    left_timestamps: &[i64],
+    left_values: &[f64],
+    right_timestamps: &[i64],
+    right_values: &[f64],
+    frequency: Frequency,
+) -> Result<AlignedSeries, IndicatorError> {
+    let left = resample(left_timestamps, left_values, frequency)?;
+    let right = resample(right_timestamps, right_values, frequency)?;
+
+    let mut result = AlignedSeries {
+        periods: Vec::new(),
+        left: Vec::new(),
+        right: Vec::new(),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < left.timestamps.len() && j < right.timestamps.len() {
+        let left_period = frequency.period_start(left.timestamps[i]);
+        let right_period = frequency.period_start(right.timestamps[j]);
+        match left_period.cmp(&right_period) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.periods.push(left_period);
+                result.left.push(left.values[i]);
+                result.right.push(right.values[j]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    #[test]
+    fn test_period_start() {
+        // 2024-03-14 12:00 UTC, a Thursday
+        let timestamp = 1_710_417_600;
+        assert_eq!(Frequency::Daily.period_start(timestamp), 1_710_374_400);
+        assert_eq!(Frequency::Weekly.period_start(timestamp), 1_710_115_200);
+        assert_eq!(Frequency::Monthly.period_start(timestamp), 1_709_251_200);
+        assert_eq!(Frequency::Quarterly.period_start(timestamp), 1_704_067_200);
+        assert_eq!(Frequency::Yearly.period_start(timestamp), 1_704_067_200);
+        assert_eq!(
+            Frequency::Hours(4).period_start(timestamp + 3_600),
+            1_710_417_600
+        );
+        assert_eq!(
+            Frequency::Hours(4).period_start(timestamp - 1),
+            1_710_403_200
+        );
+        assert_eq!(
+            Frequency::Minutes(15).period_start(timestamp + 899),
+            timestamp
+        );
+        assert_eq!(Frequency::Minutes(15).period_start(-1), -900);
+    }
+
+    #[test]
+    fn test_resample() {
+        // Test case with daily data spanning two months, starting 2024-01-30
+        let start = 1_706_572_800;
+        let timestamps = (0..4).map(|i| start + i * DAY).collect::<Vec<i64>>();
+        let values = vec![1.0, 2.0, 3.0, 4.0];
+        let result = resample(&timestamps, &values, Frequency::Monthly).unwrap();
+        assert_eq!(result.timestamps, vec![start + DAY, start + 3 * DAY]);
+        assert_eq!(result.values, vec![2.0, 4.0]);
+
+        // Test case with unsorted timestamps
+        let result = resample(&[2, 1], &[1.0, 2.0], Frequency::Daily);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with an intraday frequency of zero length
+        let result = resample(&[1, 2], &[1.0, 2.0], Frequency::Hours(0));
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_align() {
+        // Test case with a daily fund and a month-end benchmark, starting 2024-01-30
+        let start = 1_706_572_800;
+        let fund_timestamps = (0..4).map(|i| start + i * DAY).collect::<Vec<i64>>();
+        let fund_values = vec![10.0, 10.5, 10.2, 10.4];
+        let benchmark_timestamps = vec![start - 30 * DAY, start + DAY];
+        let benchmark_values = vec![100.0, 101.0];
+        let result = align(
+            &fund_timestamps,
+            &fund_values,
+            &benchmark_timestamps,
+            &benchmark_values,
+            Frequency::Monthly,
+        )
+        .unwrap();
+        assert_eq!(result.periods, vec![1_704_067_200]);
+        assert_eq!(result.left, vec![10.5]);
+        assert_eq!(result.right, vec![101.0]);
+
+        // Test case with mismatched lengths
+        let result = align(&[1, 2], &[1.0], &[1], &[1.0], Frequency::Daily);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+}
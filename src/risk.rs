@@ -0,0 +1,919 @@
+//! Risk and performance measures computed from price, equity, or return series.
+//!
+//! Drawdowns and ratios are expressed as fractions, so a drawdown of `0.1` is a 10% decline from
+//! the running peak.
+
+use crate::special::chi_squared_sf;
+use crate::stats::{quantile, RollingCovariance, RollingVariance};
+use crate::IndicatorError;
+
+/// Calculates the drawdown from the running peak at every point of a price or equity series.
+fn drawdowns(prices: &[f64]) -> Vec<f64> {
+    let mut peak = f64::NEG_INFINITY;
+    prices
+        .iter()
+        .map(|price| {
+            peak = peak.max(*price);
+            (peak - price) / peak
+        })
+        .collect()
+}
+
+/// Checks that a drawdown based measure has at least one data point to work with.
+fn require_prices(prices: &[f64]) -> Result<(), IndicatorError> {
+    if prices.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "`prices` must have at least one item".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates the Ulcer Index, the root mean square of the drawdowns of a price series.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price or equity data.
+///
+/// # Returns
+///
+/// A Result containing the Ulcer Index or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `prices` is empty.
+pub fn calculate_ulcer_index(prices: &[f64]) -> Result<f64, IndicatorError> {
+    require_prices(prices)?;
+
+    let squares = drawdowns(prices).iter().map(|d| d * d).sum::<f64>();
+    Ok((squares / prices.len() as f64).sqrt())
+}
+
+/// Calculates the Pain Index, the mean drawdown of a price series.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price or equity data.
+///
+/// # Returns
+///
+/// A Result containing the Pain Index or an `IndicatorError` if there is not enough data.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `prices` is empty.
+pub fn calculate_pain_index(prices: &[f64]) -> Result<f64, IndicatorError> {
+    require_prices(prices)?;
+
+    Ok(drawdowns(prices).iter().sum::<f64>() / prices.len() as f64)
+}
+
+/// Calculates the Conditional Drawdown-at-Risk (CDaR) of a price series.
+///
+/// CDaR is the mean of the worst `1 - confidence` fraction of the drawdowns observed over the
+/// series, rounded up to at least one observation.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of price or equity data.
+/// * `confidence` - The confidence level, for example `0.95`.
+///
+/// # Returns
+///
+/// A Result containing the CDaR or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `prices` is empty, and an
+/// `IndicatorError::InvalidInput` if `confidence` is not within `[0, 1)`.
+pub fn calculate_conditional_drawdown_at_risk(
+    prices: &[f64],
+    confidence: f64,
+) -> Result<f64, IndicatorError> {
+    require_prices(prices)?;
+    if !(0.0..1.0).contains(&confidence) {
+        return Err(IndicatorError::InvalidInput(
+            "`confidence` must be within [0, 1)".to_string(),
+        ));
+    }
+
+    let mut drawdowns = drawdowns(prices);
+    drawdowns.sort_by(|a, b| b.total_cmp(a));
+    let tail = (((1.0 - confidence) * prices.len() as f64).ceil() as usize).max(1);
+    Ok(drawdowns[..tail].iter().sum::<f64>() / tail as f64)
+}
+
+/// Checks that a return based measure has at least one data point to work with.
+fn require_returns(returns: &[f64]) -> Result<(), IndicatorError> {
+    if returns.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "`returns` must have at least one item".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates the Omega ratio, the probability weighted ratio of gains to losses relative to a
+/// threshold return.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of periodic returns.
+/// * `threshold` - The minimum acceptable return per period.
+///
+/// # Returns
+///
+/// A Result containing the Omega ratio or an `IndicatorError` if there is not enough data. The
+/// ratio is `f64::INFINITY` if no return falls below `threshold`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `returns` is empty.
+pub fn calculate_omega_ratio(returns: &[f64], threshold: f64) -> Result<f64, IndicatorError> {
+    require_returns(returns)?;
+
+    let gains = returns
+        .iter()
+        .map(|r| (r - threshold).max(0.0))
+        .sum::<f64>();
+    let losses = returns
+        .iter()
+        .map(|r| (threshold - r).max(0.0))
+        .sum::<f64>();
+    Ok(if losses > 0.0 {
+        gains / losses
+    } else {
+        f64::INFINITY
+    })
+}
+
+/// Calculates the gain-to-pain ratio, the sum of all returns divided by the absolute sum of the
+/// negative returns.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of periodic returns.
+///
+/// # Returns
+///
+/// A Result containing the gain-to-pain ratio or an `IndicatorError` if there is not enough data.
+/// The ratio is `f64::INFINITY` if there are no negative returns.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `returns` is empty.
+pub fn calculate_gain_to_pain_ratio(returns: &[f64]) -> Result<f64, IndicatorError> {
+    require_returns(returns)?;
+
+    let total = returns.iter().sum::<f64>();
+    let pain = returns
+        .iter()
+        .filter(|r| **r < 0.0)
+        .map(|r| -r)
+        .sum::<f64>();
+    Ok(if pain > 0.0 {
+        total / pain
+    } else {
+        f64::INFINITY
+    })
+}
+
+/// Calculates the tail ratio, the absolute value of the right tail quantile of the returns divided
+/// by the absolute value of the left tail quantile.
+///
+/// With a `confidence` of `0.95` this compares the 95th and 5th percentiles of the returns.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of periodic returns.
+/// * `confidence` - The quantile of the right tail, for example `0.95`.
+///
+/// # Returns
+///
+/// A Result containing the tail ratio or an `IndicatorError`. The ratio is `f64::INFINITY` if
+/// the left tail quantile is zero.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `returns` is empty, and an
+/// `IndicatorError::InvalidInput` if `confidence` is not within `(0.5, 1]`.
+pub fn calculate_tail_ratio(returns: &[f64], confidence: f64) -> Result<f64, IndicatorError> {
+    require_returns(returns)?;
+    if !(confidence > 0.5 && confidence <= 1.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`confidence` must be within (0.5, 1]".to_string(),
+        ));
+    }
+
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let right = quantile(&sorted, confidence).abs();
+    let left = quantile(&sorted, 1.0 - confidence).abs();
+    Ok(if left > 0.0 {
+        right / left
+    } else {
+        f64::INFINITY
+    })
+}
+
+/// Checks that a rolling measure has a usable window and enough observations to fill it.
+fn require_window(returns: &[f64], window: usize) -> Result<(), IndicatorError> {
+    if window < 2 || returns.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "`returns` must have at least `window` items and `window` must be at least 2"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the ratio of `numerator` to a dispersion of zero: infinity with the sign of
+/// `numerator`, or `NaN` if `numerator` is zero as well.
+fn zero_dispersion_ratio(numerator: f64) -> f64 {
+    if numerator == 0.0 || numerator.is_nan() {
+        f64::NAN
+    } else {
+        f64::INFINITY.copysign(numerator)
+    }
+}
+
+/// Counts how many of the latest values are equal, so constant windows are recognized exactly
+/// rather than through rolling moments that may not cancel to zero.
+#[derive(Debug, Clone, Copy)]
+struct RunLength {
+    last: f64,
+    count: usize,
+}
+
+impl RunLength {
+    fn new() -> Self {
+        RunLength {
+            last: f64::NAN,
+            count: 0,
+        }
+    }
+
+    /// Records `value` and returns whether the latest `window` values are all equal.
+    fn push(&mut self, value: f64, window: usize) -> bool {
+        self.count = if value == self.last {
+            self.count + 1
+        } else {
+            1
+        };
+        self.last = value;
+        self.count >= window
+    }
+}
+
+/// Calculates the Sharpe ratio over a rolling window of returns.
+///
+/// Each value is the mean excess return of the window divided by the sample standard deviation of
+/// the excess returns. Ratios are per period; multiply by the square root of the number of periods
+/// per year to annualize them.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of periodic returns.
+/// * `risk_free` - The risk free return per period.
+/// * `window` - The number of returns in each window.
+///
+/// # Returns
+///
+/// A Result containing a vector with one Sharpe ratio per window or an `IndicatorError` if there
+/// is not enough data. The value at index `i` covers the window ending at index `i + window - 1`.
+/// A window of equal returns has no standard deviation and a ratio of `f64::INFINITY` with the
+/// sign of its excess return, or `NaN` if every return equals `risk_free`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is less than 2 or the length of
+/// `returns` is less than `window`.
+pub fn calculate_rolling_sharpe(
+    returns: &[f64],
+    risk_free: f64,
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_window(returns, window)?;
+
+    let mut stats = RollingVariance::new(window);
+    let mut run = RunLength::new();
+    Ok(returns
+        .iter()
+        .filter_map(|r| {
+            stats.push(*r);
+            let constant = run.push(*r, window);
+            if !stats.is_full() {
+                return None;
+            }
+            if constant {
+                return Some(zero_dispersion_ratio(r - risk_free));
+            }
+            Some((stats.mean()? - risk_free) / stats.std_dev()?)
+        })
+        .collect())
+}
+
+/// Calculates the Sortino ratio over a rolling window of returns.
+///
+/// Each value is the mean return of the window in excess of `target` divided by the downside
+/// deviation, the root mean square of the shortfalls below `target`. Ratios are per period;
+/// multiply by the square root of the number of periods per year to annualize them.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of periodic returns.
+/// * `target` - The minimum acceptable return per period.
+/// * `window` - The number of returns in each window.
+///
+/// # Returns
+///
+/// A Result containing a vector with one Sortino ratio per window or an `IndicatorError` if there
+/// is not enough data. The value at index `i` covers the window ending at index `i + window - 1`.
+/// A window without any return below `target` has no downside deviation and a ratio of
+/// `f64::INFINITY`, or `NaN` if every return equals `target`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is less than 2 or the length of
+/// `returns` is less than `window`.
+pub fn calculate_rolling_sortino(
+    returns: &[f64],
+    target: f64,
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    require_window(returns, window)?;
+
+    Ok(returns
+        .windows(window)
+        .map(|w| {
+            let mean = w.iter().map(|r| r - target).sum::<f64>() / window as f64;
+            let downside =
+                w.iter().map(|r| (r - target).min(0.0).powi(2)).sum::<f64>() / window as f64;
+            if downside > 0.0 {
+                mean / downside.sqrt()
+            } else {
+                zero_dispersion_ratio(mean)
+            }
+        })
+        .collect())
+}
+
+/// Calculates the beta of a series of returns against a benchmark over a rolling window.
+///
+/// # Arguments
+///
+/// * `returns` - A slice of periodic asset returns.
+/// * `benchmark` - A slice of periodic benchmark returns aligned with `returns`.
+/// * `window` - The number of returns in each window.
+///
+/// # Returns
+///
+/// A Result containing a vector with one beta per window or an `IndicatorError`. The value at
+/// index `i` covers the window ending at index `i + window - 1`. A window of equal benchmark
+/// returns has no benchmark variance and no covariance either, so its beta is `NaN`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `returns` and `benchmark` differ in length,
+/// and an `IndicatorError::NotEnoughData` if `window` is less than 2 or the length of `returns`
+/// is less than `window`.
+pub fn calculate_rolling_beta(
+    returns: &[f64],
+    benchmark: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    if returns.len() != benchmark.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`returns` and `benchmark` must have the same length".to_string(),
+        ));
+    }
+    require_window(returns, window)?;
+
+    let mut stats = RollingCovariance::new(window);
+    let mut run = RunLength::new();
+    Ok(returns
+        .iter()
+        .zip(benchmark)
+        .filter_map(|(r, b)| {
+            stats.push(*r, *b);
+            let constant = run.push(*b, window);
+            if !stats.is_full() {
+                return None;
+            }
+            if constant {
+                return Some(zero_dispersion_ratio(0.0));
+            }
+            stats.beta()
+        })
+        .collect())
+}
+
+/// How a position is unwound over its liquidation horizon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidationSchedule {
+    /// The whole position is held until the end of the horizon, scaling VaR by the square root of
+    /// the horizon
+    Hold,
+    /// The position is sold in equal daily amounts over the horizon, so less of it remains exposed
+    /// on every later day
+    Linear,
+}
+
+/// Estimates the number of days needed to liquidate a position without exceeding a participation
+/// limit.
+///
+/// # Arguments
+///
+/// * `position_size` - The size of the position in shares or contracts.
+/// * `average_daily_volume` - The average daily traded volume of the instrument.
+/// * `participation` - The largest fraction of the daily volume the position may trade.
+///
+/// # Returns
+///
+/// A Result containing the number of days to liquidate or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `average_daily_volume` is not positive or
+/// `participation` is not within `(0, 1]`.
+pub fn calculate_days_to_liquidate(
+    position_size: f64,
+    average_daily_volume: f64,
+    participation: f64,
+) -> Result<f64, IndicatorError> {
+    if average_daily_volume.is_nan() || average_daily_volume <= 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`average_daily_volume` must be positive".to_string(),
+        ));
+    }
+    if !(participation > 0.0 && participation <= 1.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`participation` must be within (0, 1]".to_string(),
+        ));
+    }
+
+    Ok(position_size.abs() / (average_daily_volume * participation))
+}
+
+/// Scales a one day Value at Risk to account for the time needed to liquidate the position.
+///
+/// With [`LiquidationSchedule::Hold`] the VaR is multiplied by `sqrt(days)`. With
+/// [`LiquidationSchedule::Linear`] the horizon is rounded up to whole days `T` and the VaR is
+/// multiplied by `sqrt((T + 1) * (2T + 1) / (6T))`, the volatility of a position that shrinks by
+/// `1 / T` every day. Horizons shorter than a day leave the VaR unchanged.
+///
+/// # Arguments
+///
+/// * `var` - The one day Value at Risk of the position.
+/// * `days_to_liquidate` - The liquidation horizon in days, for example from
+///   [`calculate_days_to_liquidate`].
+/// * `schedule` - How the position is unwound over the horizon.
+///
+/// # Returns
+///
+/// A Result containing the liquidity adjusted VaR or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `days_to_liquidate` is negative or `NaN`.
+pub fn calculate_liquidity_adjusted_var(
+    var: f64,
+    days_to_liquidate: f64,
+    schedule: LiquidationSchedule,
+) -> Result<f64, IndicatorError> {
+    if days_to_liquidate.is_nan() || days_to_liquidate < 0.0 {
+        return Err(IndicatorError::InvalidInput(
+            "`days_to_liquidate` must not be negative".to_string(),
+        ));
+    }
+    if days_to_liquidate <= 1.0 {
+        return Ok(var);
+    }
+
+    let multiplier = match schedule {
+        LiquidationSchedule::Hold => days_to_liquidate.sqrt(),
+        LiquidationSchedule::Linear => {
+            let days = days_to_liquidate.ceil();
+            ((days + 1.0) * (2.0 * days + 1.0) / (6.0 * days)).sqrt()
+        }
+    };
+    Ok(var * multiplier)
+}
+
+/// Result of backtesting a Value at Risk model, produced by [`calculate_var_backtest`].
+///
+/// Every statistic is a likelihood ratio, and a small p-value rejects the hypothesis that the
+/// model is correctly specified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarBacktest {
+    /// Number of periods tested
+    pub observations: usize,
+    /// Number of periods in which the loss exceeded the VaR
+    pub exceptions: usize,
+    /// Number of exceptions expected at the tested confidence level
+    pub expected_exceptions: f64,
+    /// Kupiec proportion of failures statistic, testing that exceptions occur at the expected rate
+    pub kupiec_statistic: f64,
+    /// P-value of `kupiec_statistic`, chi-squared with one degree of freedom
+    pub kupiec_p_value: f64,
+    /// Christoffersen statistic, testing that exceptions do not cluster in consecutive periods
+    pub independence_statistic: f64,
+    /// P-value of `independence_statistic`, chi-squared with one degree of freedom
+    pub independence_p_value: f64,
+    /// Christoffersen conditional coverage statistic, the sum of the two statistics above
+    pub conditional_coverage_statistic: f64,
+    /// P-value of `conditional_coverage_statistic`, chi-squared with two degrees of freedom
+    pub conditional_coverage_p_value: f64,
+}
+
+/// Calculates the log-likelihood of `count` events of probability `p`, taking `0 * ln(0)` as zero.
+fn log_likelihood(count: usize, p: f64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        count as f64 * p.ln()
+    }
+}
+
+/// Backtests a Value at Risk model against realized returns.
+///
+/// An exception occurs in every period whose return is below `-var`. The Kupiec test checks the
+/// number of exceptions against `1 - confidence` of the periods, the Christoffersen test checks
+/// that an exception is no more likely after an exception than after a quiet period, and the
+/// conditional coverage test combines both.
+///
+/// # Arguments
+///
+/// * `returns` - The realized return of every period.
+/// * `var` - The Value at Risk forecast for every period, as a positive loss.
+/// * `confidence` - The confidence level of the VaR forecasts, e.g. `0.99`.
+///
+/// # Returns
+///
+/// A Result containing the [`VarBacktest`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `returns` and `var` differ in length, an
+/// `IndicatorError::NotEnoughData` if there are fewer than two periods, and an
+/// `IndicatorError::InvalidInput` if `confidence` is not within `(0, 1)`.
+pub fn calculate_var_backtest(
+    returns: &[f64],
+    var: &[f64],
+    confidence: f64,
+) -> Result<VarBacktest, IndicatorError> {
+    if returns.len() != var.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`returns` and `var` must have the same length".to_string(),
+        ));
+    }
+    if returns.len() < 2 {
+        return Err(IndicatorError::NotEnoughData(
+            "`returns` must have at least two items".to_string(),
+        ));
+    }
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err(IndicatorError::InvalidInput(
+            "`confidence` must be within (0, 1)".to_string(),
+        ));
+    }
+
+    let hits = returns
+        .iter()
+        .zip(var)
+        .map(|(r, v)| *r < -v)
+        .collect::<Vec<bool>>();
+    let observations = hits.len();
+    let exceptions = hits.iter().filter(|hit| **hit).count();
+
+    // Proportion of failures
+    let p = 1.0 - confidence;
+    let observed = exceptions as f64 / observations as f64;
+    let misses = observations - exceptions;
+    let kupiec_statistic = -2.0
+        * (log_likelihood(misses, 1.0 - p) + log_likelihood(exceptions, p)
+            - log_likelihood(misses, 1.0 - observed)
+            - log_likelihood(exceptions, observed));
+
+    // Transitions between consecutive periods, indexed by [previous][current]
+    let mut transitions = [[0usize; 2]; 2];
+    for pair in hits.windows(2) {
+        transitions[pair[0] as usize][pair[1] as usize] += 1;
+    }
+    let [[n00, n01], [n10, n11]] = transitions;
+    let pi = (n01 + n11) as f64 / (observations - 1) as f64;
+    let pi0 = n01 as f64 / (n00 + n01) as f64;
+    let pi1 = n11 as f64 / (n10 + n11) as f64;
+    let independence_statistic = -2.0
+        * (log_likelihood(n00 + n10, 1.0 - pi) + log_likelihood(n01 + n11, pi)
+            - log_likelihood(n00, 1.0 - pi0)
+            - log_likelihood(n01, pi0)
+            - log_likelihood(n10, 1.0 - pi1)
+            - log_likelihood(n11, pi1));
+
+    let conditional_coverage_statistic = kupiec_statistic + independence_statistic;
+    Ok(VarBacktest {
+        observations,
+        exceptions,
+        expected_exceptions: p * observations as f64,
+        kupiec_statistic,
+        kupiec_p_value: chi_squared_sf(kupiec_statistic, 1.0),
+        independence_statistic,
+        independence_p_value: chi_squared_sf(independence_statistic, 1.0),
+        conditional_coverage_statistic,
+        conditional_coverage_p_value: chi_squared_sf(conditional_coverage_statistic, 2.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRICES: [f64; 6] = [100.0, 110.0, 99.0, 105.0, 121.0, 110.0];
+    const RETURNS: [f64; 8] = [0.02, -0.01, 0.03, -0.02, 0.01, 0.0, -0.005, 0.015];
+    const BENCHMARK: [f64; 8] = [0.01, -0.005, 0.02, -0.01, 0.004, 0.002, -0.001, 0.01];
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{} != {}", a, e);
+        }
+    }
+
+    #[test]
+    fn test_calculate_ulcer_index() {
+        // Test case with enough data
+        let result = calculate_ulcer_index(&PRICES).unwrap();
+        assert!((result - 0.05821022034029863).abs() < 1e-12);
+
+        // Test case with not enough data
+        let result = calculate_ulcer_index(&[]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_pain_index() {
+        // Test case with enough data
+        let result = calculate_pain_index(&PRICES).unwrap();
+        assert!((result - 0.03939393939393939).abs() < 1e-12);
+
+        // Test case with not enough data
+        let result = calculate_pain_index(&[]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_conditional_drawdown_at_risk() {
+        // Test case with enough data
+        let result = calculate_conditional_drawdown_at_risk(&PRICES, 0.7).unwrap();
+        assert!((result - 0.09545454545454546).abs() < 1e-12);
+
+        // Test case with an invalid confidence level
+        let result = calculate_conditional_drawdown_at_risk(&PRICES, 1.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_omega_ratio() {
+        // Test case with enough data
+        let result = calculate_omega_ratio(&RETURNS, 0.0).unwrap();
+        assert!((result - 2.142857142857143).abs() < 1e-12);
+
+        // Test case without returns below the threshold
+        let result = calculate_omega_ratio(&RETURNS, -0.05).unwrap();
+        assert_eq!(result, f64::INFINITY);
+
+        // Test case with not enough data
+        let result = calculate_omega_ratio(&[], 0.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_gain_to_pain_ratio() {
+        // Test case with enough data
+        let result = calculate_gain_to_pain_ratio(&RETURNS).unwrap();
+        assert!((result - 1.1428571428571428).abs() < 1e-12);
+
+        // Test case with not enough data
+        let result = calculate_gain_to_pain_ratio(&[]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_tail_ratio() {
+        // Test case with enough data
+        let result = calculate_tail_ratio(&RETURNS, 0.9).unwrap();
+        assert!((result - 1.7692307692307694).abs() < 1e-12);
+
+        // Test case with an invalid confidence level
+        let result = calculate_tail_ratio(&RETURNS, 0.4);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_rolling_sharpe() {
+        // Test case with enough data
+        let result = calculate_rolling_sharpe(&RETURNS, 0.0, 4).unwrap();
+        assert_close(
+            &result,
+            &[
+                0.21004201260420147,
+                0.11274690420042428,
+                0.24019223070763063,
+                -0.3,
+                0.5477225575051662,
+            ],
+        );
+
+        // Test case with constant windows
+        let result = calculate_rolling_sharpe(&[0.01, 0.03, 0.01, 0.01, 0.0, 0.0], 0.0, 2).unwrap();
+        assert!(result[0].is_finite() && result[1].is_finite() && result[3].is_finite());
+        assert_eq!(result[2], f64::INFINITY);
+        assert!(result[4].is_nan());
+        let result = calculate_rolling_sharpe(&[-0.01, -0.01], 0.0, 2).unwrap();
+        assert_eq!(result, [f64::NEG_INFINITY]);
+
+        // Test case with not enough data
+        let result = calculate_rolling_sharpe(&RETURNS, 0.0, 9);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_rolling_sortino() {
+        // Test case with enough data
+        let result = calculate_rolling_sortino(&RETURNS, 0.0, 4).unwrap();
+        assert_close(
+            &result,
+            &[
+                0.4472135954999579,
+                0.2236067977499789,
+                0.5,
+                -0.3638034375544994,
+                2.0,
+            ],
+        );
+
+        // Test case with windows without downside
+        let result = calculate_rolling_sortino(&[0.01, 0.02, 0.0, 0.0], 0.0, 2).unwrap();
+        assert_eq!(result[..2], [f64::INFINITY, f64::INFINITY]);
+        assert!(result[2].is_nan());
+
+        // Test case with not enough data
+        let result = calculate_rolling_sortino(&RETURNS, 0.0, 1);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_rolling_beta() {
+        // Test case with enough data
+        let result = calculate_rolling_beta(&RETURNS, &BENCHMARK, 4).unwrap();
+        assert_close(
+            &result,
+            &[
+                1.714285714285714,
+                1.6658665386461828,
+                1.6666666666666665,
+                1.971677559912854,
+                1.8532818532818531,
+            ],
+        );
+
+        // Test case with a constant benchmark window
+        let result =
+            calculate_rolling_beta(&[0.01, 0.02, 0.03, 0.0], &[0.01, 0.02, 0.02, 0.02], 3).unwrap();
+        assert!(result[0].is_finite());
+        assert!(result[1].is_nan());
+
+        // Test case with mismatched lengths
+        let result = calculate_rolling_beta(&RETURNS, &BENCHMARK[1..], 4);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_calculate_days_to_liquidate() {
+        // Test case with enough data
+        let result = calculate_days_to_liquidate(-50_000.0, 100_000.0, 0.1).unwrap();
+        assert!((result - 5.0).abs() < 1e-12);
+
+        // Test case with an invalid participation rate
+        let result = calculate_days_to_liquidate(50_000.0, 100_000.0, 0.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_liquidity_adjusted_var() {
+        // Test case with a multi-day horizon
+        let result = calculate_liquidity_adjusted_var(100.0, 4.0, LiquidationSchedule::Hold);
+        assert!((result.unwrap() - 200.0).abs() < 1e-12);
+        let result = calculate_liquidity_adjusted_var(100.0, 3.5, LiquidationSchedule::Linear);
+        assert!((result.unwrap() - 100.0 * 1.875_f64.sqrt()).abs() < 1e-12);
+
+        // Test case with an intraday horizon
+        let result = calculate_liquidity_adjusted_var(100.0, 0.5, LiquidationSchedule::Linear);
+        assert_eq!(result.unwrap(), 100.0);
+
+        // Test case with an invalid horizon
+        let result = calculate_liquidity_adjusted_var(100.0, -1.0, LiquidationSchedule::Hold);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_var_backtest() {
+        let returns = [
+            0.01, -0.03, -0.025, 0.004, 0.012, -0.001, 0.02, -0.04, 0.003, 0.006, -0.002, 0.015,
+            -0.01, 0.008, -0.022, 0.001, 0.007, -0.005, 0.011, 0.002,
+        ];
+        let var = [0.02; 20];
+
+        // Test case with enough data
+        let result = calculate_var_backtest(&returns, &var, 0.95).unwrap();
+        assert_eq!(result.observations, 20);
+        assert_eq!(result.exceptions, 4);
+        assert!((result.expected_exceptions - 1.0).abs() < 1e-12);
+        assert!((result.kupiec_statistic - 5.591146667306031).abs() < 1e-9);
+        assert!((result.kupiec_p_value - 0.018051475507555627).abs() < 1e-9);
+        assert!((result.independence_statistic - 0.04606642320321086).abs() < 1e-9);
+        assert!((result.independence_p_value - 0.83005510066424).abs() < 1e-9);
+        assert!((result.conditional_coverage_statistic - 5.637213090509242).abs() < 1e-9);
+        assert!((result.conditional_coverage_p_value - 0.05968905878845864).abs() < 1e-9);
+
+        // Test case without any exceptions
+        let result = calculate_var_backtest(&returns, &[0.05; 20], 0.99).unwrap();
+        assert_eq!(result.exceptions, 0);
+        assert!(result.independence_statistic.abs() < 1e-12);
+        assert!(result.kupiec_p_value > 0.5);
+
+        // Test case with mismatched lengths
+        let result = calculate_var_backtest(&returns, &var[..10], 0.95);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with an invalid confidence
+        let result = calculate_var_backtest(&returns, &var, 1.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+}
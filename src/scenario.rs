@@ -0,0 +1,251 @@
+//! Stress testing of portfolios against hypothetical and historical scenarios.
+//!
+//! Portfolios are described by the signed market value of each position, and positions are
+//! revalued linearly from the asset returns implied by a scenario.
+
+use crate::IndicatorError;
+
+/// Asset price moves applied by a [`Scenario`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shock {
+    /// The same instantaneous return applied to every asset
+    Uniform(f64),
+    /// One instantaneous return per asset
+    PerAsset(Vec<f64>),
+    /// A market gap transmitted to every asset through its beta, `market_return * beta`
+    Market {
+        /// Return of the market
+        market_return: f64,
+        /// Beta of each asset to the market
+        betas: Vec<f64>,
+    },
+    /// A volatility spike modelled as a move of `sigmas` standard deviations in every asset, using
+    /// each asset's own volatility. Negative `sigmas` move prices down.
+    Volatility {
+        /// Size of the move in standard deviations
+        sigmas: f64,
+        /// Volatility of each asset over the scenario horizon
+        volatilities: Vec<f64>,
+    },
+    /// A replay of historical periodic returns, one path per asset, compounded step by step
+    Historical(Vec<Vec<f64>>),
+}
+
+/// A named shock that can be applied to any portfolio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// Name used to identify the scenario in reports
+    pub name: String,
+    /// Price moves applied by the scenario
+    pub shock: Shock,
+}
+
+impl Scenario {
+    /// Creates a new scenario.
+    pub fn new(name: &str, shock: Shock) -> Self {
+        Scenario {
+            name: name.to_string(),
+            shock,
+        }
+    }
+}
+
+/// Profit and loss of a portfolio under a scenario, produced by [`apply_scenario`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioResult {
+    /// Name of the scenario
+    pub name: String,
+    /// Profit and loss of each position at the end of the scenario.
+    pub position_pnl: Vec<f64>,
+    /// Profit and loss of the portfolio at the end of the scenario.
+    pub total_pnl: f64,
+    /// Cumulative profit and loss of the portfolio after each step of the scenario. Instantaneous
+    /// shocks have a single step.
+    pub pnl_path: Vec<f64>,
+    /// Lowest point of `pnl_path`.
+    pub worst_pnl: f64,
+}
+
+/// Checks that a per asset shock vector covers every position.
+fn require_per_asset(values: &[f64], positions: usize) -> Result<(), IndicatorError> {
+    if values.len() != positions {
+        return Err(IndicatorError::MismatchedLength(
+            "Shocks must have one value per position".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Applies a scenario to a portfolio and reports the resulting profit and loss.
+///
+/// # Arguments
+///
+/// * `position_values` - The signed market value of each position.
+/// * `scenario` - The scenario to apply.
+///
+/// # Returns
+///
+/// A Result containing the [`ScenarioResult`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the shock does not have one value or path per
+/// position or historical paths differ in length, and an `IndicatorError::NotEnoughData` if
+/// historical paths are empty.
+pub fn apply_scenario(
+    position_values: &[f64],
+    scenario: &Scenario,
+) -> Result<ScenarioResult, IndicatorError> {
+    let positions = position_values.len();
+
+    // Cumulative return of every asset after each step of the scenario
+    let paths: Vec<Vec<f64>> = match &scenario.shock {
+        Shock::Uniform(r) => vec![vec![*r; positions]],
+        Shock::PerAsset(returns) => {
+            require_per_asset(returns, positions)?;
+            vec![returns.clone()]
+        }
+        Shock::Market {
+            market_return,
+            betas,
+        } => {
+            require_per_asset(betas, positions)?;
+            vec![betas.iter().map(|b| market_return * b).collect()]
+        }
+        Shock::Volatility {
+            sigmas,
+            volatilities,
+        } => {
+            require_per_asset(volatilities, positions)?;
+            vec![volatilities.iter().map(|v| sigmas * v).collect()]
+        }
+        Shock::Historical(returns) => {
+            if returns.len() != positions {
+                return Err(IndicatorError::MismatchedLength(
+                    "Historical scenarios must have one return path per position".to_string(),
+                ));
+            }
+            let steps = returns.first().map_or(0, |r| r.len());
+            if returns.iter().any(|r| r.len() != steps) {
+                return Err(IndicatorError::MismatchedLength(
+                    "Historical return paths must have the same length".to_string(),
+                ));
+            }
+            if steps == 0 {
+                return Err(IndicatorError::NotEnoughData(
+                    "Historical return paths must not be empty".to_string(),
+                ));
+            }
+            let mut growth = vec![1.0; positions];
+            (0..steps)
+                .map(|step| {
+                    growth
+                        .iter_mut()
+                        .zip(returns)
+                        .map(|(g, r)| {
+                            *g *= 1.0 + r[step];
+                            *g - 1.0
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+    };
+
+    let pnl = |returns: &[f64]| {
+        position_values
+            .iter()
+            .zip(returns)
+            .map(|(value, r)| value * r)
+            .collect::<Vec<f64>>()
+    };
+    let pnl_path = paths
+        .iter()
+        .map(|returns| pnl(returns).iter().sum::<f64>())
+        .collect::<Vec<f64>>();
+    let position_pnl = pnl(&paths[paths.len() - 1]);
+
+    Ok(ScenarioResult {
+        name: scenario.name.clone(),
+        total_pnl: position_pnl.iter().sum(),
+        position_pnl,
+        worst_pnl: pnl_path.iter().copied().fold(f64::INFINITY, f64::min),
+        pnl_path,
+    })
+}
+
+/// Applies every scenario to a portfolio, see [`apply_scenario`].
+///
+/// # Errors
+///
+/// Returns the first error produced by [`apply_scenario`].
+pub fn run_scenarios(
+    position_values: &[f64],
+    scenarios: &[Scenario],
+) -> Result<Vec<ScenarioResult>, IndicatorError> {
+    scenarios
+        .iter()
+        .map(|scenario| apply_scenario(position_values, scenario))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITIONS: [f64; 2] = [1_000.0, -500.0];
+
+    #[test]
+    fn test_apply_scenario() {
+        // Test case with instantaneous shocks
+        let result = apply_scenario(&POSITIONS, &Scenario::new("gap", Shock::Uniform(-0.1)));
+        let result = result.unwrap();
+        assert_eq!(result.position_pnl, vec![-100.0, 50.0]);
+        assert_eq!(result.total_pnl, -50.0);
+        assert_eq!(result.pnl_path, vec![-50.0]);
+
+        let shock = Shock::Market {
+            market_return: -0.2,
+            betas: vec![1.5, 0.5],
+        };
+        let result = apply_scenario(&POSITIONS, &Scenario::new("crash", shock)).unwrap();
+        assert!((result.total_pnl + 250.0).abs() < 1e-9);
+
+        let shock = Shock::Volatility {
+            sigmas: -3.0,
+            volatilities: vec![0.02, 0.04],
+        };
+        let result = apply_scenario(&POSITIONS, &Scenario::new("vol", shock)).unwrap();
+        assert!(result.total_pnl.abs() < 1e-9);
+
+        // Test case with a historical replay
+        let shock = Shock::Historical(vec![vec![-0.1, -0.1, 0.5], vec![0.0, 0.1, 0.0]]);
+        let result = apply_scenario(&POSITIONS, &Scenario::new("replay", shock)).unwrap();
+        let expected = [-100.0, -240.0, 165.0];
+        for (actual, expected) in result.pnl_path.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-9);
+        }
+        assert!((result.worst_pnl + 240.0).abs() < 1e-9);
+
+        // Test case with mismatched lengths
+        let shock = Shock::PerAsset(vec![0.1]);
+        let result = apply_scenario(&POSITIONS, &Scenario::new("bad", shock));
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_run_scenarios() {
+        let scenarios = [
+            Scenario::new("down", Shock::Uniform(-0.1)),
+            Scenario::new("up", Shock::PerAsset(vec![0.1, 0.2])),
+        ];
+        let results = run_scenarios(&POSITIONS, &scenarios).unwrap();
+        assert_eq!(results[0].name, "down");
+        assert_eq!(results[1].total_pnl, 0.0);
+    }
+}
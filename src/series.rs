@@ -0,0 +1,252 @@
+//! Timestamped series with fast lookups by time.
+
+use crate::resample::{resample, validate, Frequency};
+use crate::{IndicatorError, IndicatorSpec};
+
+/// A series of observations at strictly increasing Unix timestamps in seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    timestamps: Vec<i64>,
+    values: Vec<f64>,
+}
+
+/// A borrowed view of a contiguous range of a [`Series`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeriesSlice<'a> {
+    /// Timestamps of the observations in the range
+    pub timestamps: &'a [i64],
+    /// Observations in the range
+    pub values: &'a [f64],
+}
+
+impl Series {
+    /// Creates a series from parallel timestamps and values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::MismatchedLength` if `timestamps` and `values` differ in
+    /// length, and an `IndicatorError::InvalidInput` if `timestamps` are not strictly increasing.
+    pub fn new(timestamps: Vec<i64>, values: Vec<f64>) -> Result<Self, IndicatorError> {
+        validate(&timestamps, &values)?;
+        Ok(Series { timestamps, values })
+    }
+
+    /// Returns the timestamps of the series.
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Returns the observations of the series.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the number of observations.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the series has no observations.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns a view of the whole series.
+    pub fn as_slice(&self) -> SeriesSlice<'_> {
+        SeriesSlice {
+            timestamps: &self.timestamps,
+            values: &self.values,
+        }
+    }
+
+    /// Returns the observations with a timestamp in `start..end`, found by binary search without
+    /// copying any data.
+    pub fn between(&self, start: i64, end: i64) -> SeriesSlice<'_> {
+        let from = self.timestamps.partition_point(|t| *t < start);
+        let to = self.timestamps.partition_point(|t| *t < end).max(from);
+        SeriesSlice {
+            timestamps: &self.timestamps[from..to],
+            values: &self.values[from..to],
+        }
+    }
+
+    /// Returns the index of the last observation at or before `time`, if any.
+    pub fn index_at_or_before(&self, time: i64) -> Option<usize> {
+        self.timestamps
+            .partition_point(|t| *t <= time)
+            .checked_sub(1)
+    }
+
+    /// Returns the timestamp and value of the last observation at or before `time`, if any.
+    pub fn at_or_before(&self, time: i64) -> Option<(i64, f64)> {
+        self.index_at_or_before(time)
+            .map(|i| (self.timestamps[i], self.values[i]))
+    }
+
+    /// Calculates an indicator on a lower frequency and maps it back onto the observations of the
+    /// series.
+    ///
+    /// The series is resampled to `frequency` with [`resample`] and the indicator is calculated
+    /// over the end-of-period values. The value of a period becomes available from the first
+    /// observation of the following period, so every observation only sees periods that ended
+    /// before it and the result never changes as later observations arrive.
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - The frequency to calculate the indicator on.
+    /// * `spec` - The indicator to calculate.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a series with the timestamps of this series or an `IndicatorError`.
+    /// Observations before the indicator is defined on a completed period hold `NaN`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if an intraday `frequency` has a length of zero,
+    /// and otherwise the error of the indicator, e.g. an `IndicatorError::NotEnoughData` if the
+    /// series spans too few periods for its window.
+    pub fn indicator_on_timeframe(
+        &self,
+        frequency: Frequency,
+        spec: IndicatorSpec,
+    ) -> Result<Series, IndicatorError> {
+        let resampled = resample(&self.timestamps, &self.values, frequency)?;
+        let indicator = spec.calculate(&resampled.values)?;
+        let offset = resampled.values.len() - indicator.len();
+        let period_starts = resampled
+            .timestamps
+            .iter()
+            .map(|t| frequency.period_start(*t))
+            .collect::<Vec<i64>>();
+
+        let values = self
+            .timestamps
+            .iter()
+            .map(|t| {
+                let start = frequency.period_start(*t);
+                let completed = period_starts.partition_point(|s| *s < start);
+                match completed.checked_sub(1 + offset) {
+                    Some(i) => indicator[i],
+                    None => f64::NAN,
+                }
+            })
+            .collect();
+        Series::new(self.timestamps.clone(), values)
+    }
+}
+
+impl SeriesSlice<'_> {
+    /// Returns the number of observations in the view.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the view has no observations.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MovingAverageKind;
+
+    fn series() -> Series {
+        Series::new(vec![10, 20, 30, 40, 50], vec![1.0, 2.0, 3.0, 4.0, 5.0]).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(series().len(), 5);
+
+        // Test case with unsorted timestamps
+        let result = Series::new(vec![10, 10], vec![1.0, 2.0]);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = Series::new(vec![10, 20], vec![1.0]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_between() {
+        let series = series();
+        let slice = series.between(20, 50);
+        assert_eq!(slice.timestamps, &[20, 30, 40]);
+        assert_eq!(slice.values, &[2.0, 3.0, 4.0]);
+        assert_eq!(series.between(15, 35).values, &[2.0, 3.0]);
+        assert_eq!(series.between(0, 100), series.as_slice());
+        assert!(series.between(60, 70).is_empty());
+        assert!(series.between(40, 20).is_empty());
+
+        // The view borrows the series
+        assert!(std::ptr::eq(slice.values.as_ptr(), &series.values()[1]));
+    }
+
+    #[test]
+    fn test_at_or_before() {
+        let series = series();
+        assert_eq!(series.at_or_before(30), Some((30, 3.0)));
+        assert_eq!(series.at_or_before(39), Some((30, 3.0)));
+        assert_eq!(series.at_or_before(1_000), Some((50, 5.0)));
+        assert_eq!(series.at_or_before(9), None);
+    }
+
+    #[test]
+    fn test_indicator_on_timeframe() {
+        // Test case with two bars per day over four days
+        let timestamps = (0..8).map(|i| i * 43_200).collect::<Vec<i64>>();
+        let series = Series::new(timestamps, (1..=8).map(f64::from).collect()).unwrap();
+        let spec = IndicatorSpec::MovingAverage {
+            kind: MovingAverageKind::Sma,
+            window: 2,
+        };
+        let result = series
+            .indicator_on_timeframe(Frequency::Daily, spec)
+            .unwrap();
+        assert_eq!(result.timestamps(), series.timestamps());
+        assert!(result.values()[..4].iter().all(|v| v.is_nan()));
+        assert_eq!(result.values()[4..], [3.0, 3.0, 5.0, 5.0]);
+
+        // Values never change as later observations arrive
+        let partial = Series::new(
+            series.timestamps()[..7].to_vec(),
+            series.values()[..7].to_vec(),
+        )
+        .unwrap()
+        .indicator_on_timeframe(Frequency::Daily, spec)
+        .unwrap();
+        assert_eq!(partial.values()[4..], result.values()[4..7]);
+
+        // Test case with an H4 indicator on hourly bars over a day
+        let timestamps = (0..24).map(|i| i * 3_600).collect::<Vec<i64>>();
+        let hourly = Series::new(timestamps, (1..=24).map(f64::from).collect()).unwrap();
+        let result = hourly
+            .indicator_on_timeframe(Frequency::Hours(4), spec)
+            .unwrap();
+        assert!(result.values()[..8].iter().all(|v| v.is_nan()));
+        assert_eq!(result.values()[8..12], [6.0; 4]);
+        assert_eq!(result.values()[20..], [18.0; 4]);
+
+        // Test case with not enough data
+        let result = series.indicator_on_timeframe(Frequency::Weekly, spec);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+}
@@ -0,0 +1,866 @@
+//! Evaluation of trading signals against the prices that followed them.
+//!
+//! Signals are series of directions, `1` for a long entry, `-1` for a short entry, and `0` for no
+//! entry, aligned with the prices they were generated on.
+
+use crate::special::normal_cdf;
+use crate::stats::quantile;
+use crate::IndicatorError;
+
+/// Quality of a signal at one horizon, produced by [`evaluate_signals`].
+///
+/// Returns are signed by the direction of the signal, so a positive return is a profitable entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonQuality {
+    /// Number of bars between entry and evaluation
+    pub horizon: usize,
+    /// Number of entries with a complete horizon
+    pub count: usize,
+    /// Fraction of entries with a positive return
+    pub hit_rate: f64,
+    /// Mean return of the entries
+    pub mean_return: f64,
+    /// Sample standard deviation of the returns of the entries
+    pub std_dev: f64,
+    /// 5th percentile of the returns of the entries
+    pub percentile_5: f64,
+    /// First quartile of the returns of the entries
+    pub lower_quartile: f64,
+    /// Median return of the entries
+    pub median: f64,
+    /// Third quartile of the returns of the entries
+    pub upper_quartile: f64,
+    /// 95th percentile of the returns of the entries
+    pub percentile_95: f64,
+    /// Mean return expected from entering in the same directions at random bars
+    pub random_mean_return: f64,
+    /// Z statistic of `mean_return` against `random_mean_return`
+    pub z_statistic: f64,
+    /// Two-sided p-value of `z_statistic`, the probability of a difference at least this large
+    /// from random entries
+    pub p_value: f64,
+}
+
+/// Evaluates the forward returns of a signal at several horizons.
+///
+/// The return of an entry at bar `t` over a horizon `h` is `prices[t + h] / prices[t] - 1`,
+/// multiplied by the direction of the signal. Entries without `h` bars after them are left out of
+/// that horizon. Random entries take the same directions at bars drawn uniformly from all bars
+/// with a complete horizon, so their expected return is the mean direction times the mean
+/// forward return of all bars, and the z statistic compares the mean return of the signal with it
+/// using the dispersion of the forward returns of all bars. Overlapping horizons make successive
+/// entries dependent, which makes the p-value optimistic for signals that fire on consecutive
+/// bars.
+///
+/// # Arguments
+///
+/// * `signals` - The direction of the signal at every bar.
+/// * `prices` - The price at every bar.
+/// * `horizons` - The horizons to evaluate, in bars.
+///
+/// # Returns
+///
+/// A Result containing one [`HorizonQuality`] per horizon or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `signals` and `prices` differ in length, an
+/// `IndicatorError::InvalidInput` if a horizon is zero or a signal is not `-1`, `0`, or `1`, and
+/// an `IndicatorError::NotEnoughData` if a horizon has fewer than two entries or fewer than two
+/// bars with a complete horizon.
+pub fn evaluate_signals(
+    signals: &[i8],
+    prices: &[f64],
+    horizons: &[usize],
+) -> Result<Vec<HorizonQuality>, IndicatorError> {
+    if signals.len() != prices.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`signals` and `prices` must have the same length".to_string(),
+        ));
+    }
+    if horizons.contains(&0) {
+        return Err(IndicatorError::InvalidInput(
+            "Horizons must be positive".to_string(),
+        ));
+    }
+    if signals.iter().any(|s| !(-1..=1).contains(s)) {
+        return Err(IndicatorError::InvalidInput(
+            "Signals must be -1, 0, or 1".to_string(),
+        ));
+    }
+
+    horizons
+        .iter()
+        .map(|&horizon| {
+            let forward = prices
+                .iter()
+                .zip(&prices[horizon.min(prices.len())..])
+                .map(|(entry, exit)| exit / entry - 1.0)
+                .collect::<Vec<f64>>();
+            let mut returns = forward
+                .iter()
+                .zip(signals)
+                .filter(|(_, s)| **s != 0)
+                .map(|(r, s)| r * *s as f64)
+                .collect::<Vec<f64>>();
+            if returns.len() < 2 || forward.len() < 2 {
+                return Err(IndicatorError::NotEnoughData(format!(
+                    "Horizon {} needs at least two entries and two bars after them",
+                    horizon
+                )));
+            }
+
+            let count = returns.len() as f64;
+            let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+            let std_dev = |values: &[f64], mean: f64| {
+                (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64)
+                    .sqrt()
+            };
+            let mean_return = mean(&returns);
+            let direction = signals[..forward.len()]
+                .iter()
+                .map(|s| *s as f64)
+                .sum::<f64>()
+                / count;
+            let forward_mean = mean(&forward);
+            let random_mean_return = direction * forward_mean;
+            let z_statistic = (mean_return - random_mean_return)
+                / (std_dev(&forward, forward_mean) / count.sqrt());
+
+            returns.sort_by(f64::total_cmp);
+            Ok(HorizonQuality {
+                horizon,
+                count: returns.len(),
+                hit_rate: returns.iter().filter(|r| **r > 0.0).count() as f64 / count,
+                mean_return,
+                std_dev: std_dev(&returns, mean_return),
+                percentile_5: quantile(&returns, 0.05),
+                lower_quartile: quantile(&returns, 0.25),
+                median: quantile(&returns, 0.5),
+                upper_quartile: quantile(&returns, 0.75),
+                percentile_95: quantile(&returns, 0.95),
+                random_mean_return,
+                z_statistic,
+                p_value: 2.0 * normal_cdf(-z_statistic.abs()),
+            })
+        })
+        .collect()
+}
+
+/// A measure of the performance of a strategy, computed on its returns at every bar and maximized
+/// by [`search_thresholds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdObjective {
+    /// The compounded return, `prod(1 + r) - 1`
+    TotalReturn,
+    /// The mean return divided by its sample standard deviation, not annualized
+    SharpeRatio,
+    /// The growth rate of log wealth per bar when staking the Kelly fraction `mean / variance` of
+    /// capital, approximated by `mean^2 / (2 * variance)` and zero when the mean is not positive
+    KellyGrowth,
+}
+
+impl ThresholdObjective {
+    fn evaluate(&self, returns: &[f64]) -> f64 {
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        match self {
+            ThresholdObjective::TotalReturn => {
+                returns.iter().map(|r| 1.0 + r).product::<f64>() - 1.0
+            }
+            ThresholdObjective::SharpeRatio => mean / variance.sqrt(),
+            ThresholdObjective::KellyGrowth if mean > 0.0 => mean * mean / (2.0 * variance),
+            ThresholdObjective::KellyGrowth => 0.0,
+        }
+    }
+}
+
+/// A pair of thresholds and the objective they achieve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdFit {
+    /// The oscillator value below which a long position is entered
+    pub entry: f64,
+    /// The oscillator value above which the position is closed
+    pub exit: f64,
+    /// The objective achieved on the bars the thresholds were chosen on
+    pub objective: f64,
+}
+
+/// The thresholds chosen on one training segment and their performance on the following segment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdSplit {
+    /// Number of bar returns the thresholds were chosen on, starting at the first bar
+    pub train_len: usize,
+    /// Number of bar returns the thresholds were validated on, directly after the training bars
+    pub test_len: usize,
+    /// The thresholds chosen on the training bars and their in-sample objective
+    pub fit: ThresholdFit,
+    /// The objective achieved by the thresholds on the test bars
+    pub out_of_sample: f64,
+}
+
+/// The outcome of [`search_thresholds`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdSearch {
+    /// The thresholds that maximize the objective over all bars
+    pub best: ThresholdFit,
+    /// The walk-forward validation splits, in chronological order
+    pub splits: Vec<ThresholdSplit>,
+}
+
+/// Returns the return of the threshold strategy from every bar to the next.
+///
+/// The position held from bar `t` to `t + 1` is decided at the close of bar `t`, so the returns do
+/// not look ahead.
+fn threshold_returns(oscillator: &[f64], prices: &[f64], entry: f64, exit: f64) -> Vec<f64> {
+    let mut long = false;
+    oscillator
+        .iter()
+        .zip(prices.windows(2))
+        .map(|(value, pair)| {
+            if *value < entry {
+                long = true;
+            } else if *value > exit {
+                long = false;
+            }
+            if long {
+                pair[1] / pair[0] - 1.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Searches the entry and exit thresholds of an oscillator that maximize an objective.
+///
+/// The strategy enters a long position when the oscillator closes below the entry threshold and
+/// closes it when the oscillator closes above the exit threshold, as a mean reverting strategy on
+/// e.g. [`calculate_rsi`](crate::calculate_rsi) would. Every pair of candidates with `entry <=
+/// exit` is evaluated. For out-of-sample validation the bar returns are divided into `folds + 1`
+/// segments of equal length, and for every fold the thresholds are chosen on all segments before
+/// it and evaluated on the fold itself, in the manner of an anchored walk-forward test. A large
+/// gap between the in-sample and out-of-sample objectives signals that the thresholds are fitted
+/// to noise.
+///
+/// Pairs whose objective is NaN, e.g. a Sharpe ratio of a strategy that never trades, are only
+/// chosen when every pair is NaN.
+///
+/// # Arguments
+///
+/// * `oscillator` - The oscillator value at every bar.
+/// * `prices` - The price at every bar, aligned with `oscillator`.
+/// * `entries` - The candidate entry thresholds.
+/// * `exits` - The candidate exit thresholds.
+/// * `objective` - The objective to maximize.
+/// * `folds` - The number of out-of-sample validation splits, zero for none.
+///
+/// # Returns
+///
+/// A Result containing the [`ThresholdSearch`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `oscillator` and `prices` differ in length, an
+/// `IndicatorError::InvalidInput` if no pair of candidates has `entry <= exit`, and an
+/// `IndicatorError::NotEnoughData` if a segment has fewer than two bar returns.
+pub fn search_thresholds(
+    oscillator: &[f64],
+    prices: &[f64],
+    entries: &[f64],
+    exits: &[f64],
+    objective: ThresholdObjective,
+    folds: usize,
+) -> Result<ThresholdSearch, IndicatorError> {
+    if oscillator.len() != prices.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`oscillator` and `prices` must have the same length".to_string(),
+        ));
+    }
+    let candidates = entries
+        .iter()
+        .flat_map(|entry| exits.iter().map(move |exit| (*entry, *exit)))
+        .filter(|(entry, exit)| entry <= exit)
+        .map(|(entry, exit)| {
+            (
+                entry,
+                exit,
+                threshold_returns(oscillator, prices, entry, exit),
+            )
+        })
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return Err(IndicatorError::InvalidInput(
+            "At least one pair of thresholds must have `entry <= exit`".to_string(),
+        ));
+    }
+    let segment = prices.len().saturating_sub(1) / (folds + 1);
+    if segment < 2 {
+        return Err(IndicatorError::NotEnoughData(format!(
+            "{} folds need at least {} prices",
+            folds,
+            2 * (folds + 1) + 1
+        )));
+    }
+
+    let fit = |range: std::ops::Range<usize>| {
+        candidates
+            .iter()
+            .map(|(entry, exit, returns)| ThresholdFit {
+                entry: *entry,
+                exit: *exit,
+                objective: objective.evaluate(&returns[range.clone()]),
+            })
+            .reduce(|best, fit| {
+                if fit.objective > best.objective || best.objective.is_nan() {
+                    fit
+                } else {
+                    best
+                }
+            })
+            .expect("candidates is not empty")
+    };
+    let splits = (1..=folds)
+        .map(|fold| {
+            let fit = fit(0..fold * segment);
+            let returns = threshold_returns(oscillator, prices, fit.entry, fit.exit);
+            ThresholdSplit {
+                train_len: fold * segment,
+                test_len: segment,
+                fit,
+                out_of_sample: objective.evaluate(&returns[fold * segment..(fold + 1) * segment]),
+            }
+        })
+        .collect();
+
+    Ok(ThresholdSearch {
+        best: fit(0..prices.len() - 1),
+        splits,
+    })
+}
+
+/// How [`permutation_test`] rearranges the positions of a strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resampling {
+    /// Shuffle the positions of individual bars, destroying any persistence of the positions
+    Shuffle,
+    /// Shuffle blocks of consecutive positions of the given length, keeping the holding periods
+    /// of the strategy intact within every block
+    Blocks(usize),
+}
+
+/// The outcome of [`permutation_test`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PermutationTest {
+    /// The objective achieved by the positions of the strategy
+    pub observed: f64,
+    /// The mean objective achieved by the rearranged positions
+    pub null_mean: f64,
+    /// The fraction of rearrangements, counting the observed positions as one, that achieve an
+    /// objective at least as large as `observed`
+    pub p_value: f64,
+}
+
+/// A SplitMix64 pseudo random number generator, reproducible from its seed.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed integer below `bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        ((self.next() as u128 * bound as u128) >> 64) as usize
+    }
+}
+
+/// Tests whether the performance of a strategy could be explained by luck.
+///
+/// The positions of the strategy are rearranged in time `permutations` times, which keeps their
+/// exposure but breaks any relation with the returns that follow them. The p-value is the share
+/// of rearrangements that perform at least as well as the strategy, with the strategy itself
+/// counted among them so that the p-value is never zero. Block resampling keeps the holding
+/// periods of the strategy, which gives a fairer comparison for strategies that hold positions
+/// for many bars. When thresholds or parameters were chosen on the same data, the p-value only
+/// guards against data mining if the whole search is repeated on every rearrangement.
+///
+/// # Arguments
+///
+/// * `positions` - The position held over every bar, e.g. `1.0` for long and `-1.0` for short.
+/// * `returns` - The return of the asset over every bar, aligned with `positions`.
+/// * `objective` - The measure of performance.
+/// * `resampling` - How the positions are rearranged.
+/// * `permutations` - The number of rearrangements.
+/// * `seed` - The seed of the random number generator, the same seed gives the same p-value.
+///
+/// # Returns
+///
+/// A Result containing the [`PermutationTest`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `positions` and `returns` differ in length, an
+/// `IndicatorError::InvalidInput` if `permutations` or the block length is zero, and an
+/// `IndicatorError::NotEnoughData` if there are fewer than two bars.
+pub fn permutation_test(
+    positions: &[f64],
+    returns: &[f64],
+    objective: ThresholdObjective,
+    resampling: Resampling,
+    permutations: usize,
+    seed: u64,
+) -> Result<PermutationTest, IndicatorError> {
+    if positions.len() != returns.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`positions` and `returns` must have the same length".to_string(),
+        ));
+    }
+    let block = match resampling {
+        Resampling::Shuffle => 1,
+        Resampling::Blocks(length) => length,
+    };
+    if permutations == 0 || block == 0 {
+        return Err(IndicatorError::InvalidInput(
+            "`permutations` and the block length must be positive".to_string(),
+        ));
+    }
+    if positions.len() < 2 {
+        return Err(IndicatorError::NotEnoughData(
+            "At least two bars are required".to_string(),
+        ));
+    }
+
+    let performance = |positions: &[f64]| {
+        let strategy = positions
+            .iter()
+            .zip(returns)
+            .map(|(p, r)| p * r)
+            .collect::<Vec<f64>>();
+        objective.evaluate(&strategy)
+    };
+    let observed = performance(positions);
+    let mut rng = SplitMix64(seed);
+    let mut blocks = positions.chunks(block).collect::<Vec<&[f64]>>();
+    let mut shuffled = Vec::with_capacity(positions.len());
+    let mut total = 0.0;
+    let mut at_least = 1;
+    for _ in 0..permutations {
+        for i in (1..blocks.len()).rev() {
+            blocks.swap(i, rng.below(i + 1));
+        }
+        shuffled.clear();
+        blocks.iter().for_each(|b| shuffled.extend_from_slice(b));
+        let value = performance(&shuffled);
+        total += value;
+        if value >= observed {
+            at_least += 1;
+        }
+    }
+
+    Ok(PermutationTest {
+        observed,
+        null_mean: total / permutations as f64,
+        p_value: at_least as f64 / (permutations + 1) as f64,
+    })
+}
+
+/// How [`RegimeSwitch`] moves from the position of one sub-strategy to another when the regime
+/// changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// Take the position of the new sub-strategy at once
+    Immediate,
+    /// Stay flat until the new sub-strategy changes its position, so that a position it entered
+    /// in a regime it was not trading is never inherited
+    Flatten,
+    /// Move linearly from the position held at the switch to the position of the new sub-strategy
+    /// over the given number of bars
+    Blend(usize),
+}
+
+/// Follows one of several sub-strategies depending on the current regime, e.g. a trend follower
+/// while ADX is high and a mean reverter while it is low.
+///
+/// Every sub-strategy keeps being computed on all bars, whether it is followed or not, so that its
+/// indicators are warmed up and its position is valid when its regime begins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegimeSwitch {
+    transition: Transition,
+    regime: Option<usize>,
+    position: f64,
+    /// Position of the followed sub-strategy when it was switched to
+    entry_position: f64,
+    /// Position held when the regime changed, and bars since then
+    blend_from: f64,
+    blend_bars: usize,
+    waiting: bool,
+}
+
+impl RegimeSwitch {
+    /// Creates a switch that has not followed any sub-strategy yet.
+    pub fn new(transition: Transition) -> Self {
+        RegimeSwitch {
+            transition,
+            regime: None,
+            position: 0.0,
+            entry_position: 0.0,
+            blend_from: 0.0,
+            blend_bars: 0,
+            waiting: false,
+        }
+    }
+
+    /// Returns the regime followed at the latest bar, if any bar was seen.
+    pub fn regime(&self) -> Option<usize> {
+        self.regime
+    }
+
+    /// Returns the position held at the latest bar.
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Advances by one bar and returns the position to hold.
+    ///
+    /// The first bar takes the position of its sub-strategy at once, whatever the transition.
+    ///
+    /// # Arguments
+    ///
+    /// * `regime` - The index of the sub-strategy to follow at this bar.
+    /// * `positions` - The position of every sub-strategy at this bar.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the position to hold or an `IndicatorError`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `IndicatorError::InvalidInput` if `regime` is not an index of `positions` or the
+    /// transition is a blend over zero bars.
+    pub fn update(&mut self, regime: usize, positions: &[f64]) -> Result<f64, IndicatorError> {
+        let Some(&target) = positions.get(regime) else {
+            return Err(IndicatorError::InvalidInput(format!(
+                "Regime {} has no sub-strategy among {}",
+                regime,
+                positions.len()
+            )));
+        };
+        if self.transition == Transition::Blend(0) {
+            return Err(IndicatorError::InvalidInput(
+                "A blend must last at least one bar".to_string(),
+            ));
+        }
+
+        match self.regime {
+            None => {
+                self.regime = Some(regime);
+                self.position = target;
+                self.blend_bars = usize::MAX;
+                return Ok(target);
+            }
+            Some(previous) if previous != regime => {
+                self.regime = Some(regime);
+                self.entry_position = target;
+                self.blend_from = self.position;
+                self.blend_bars = 0;
+                self.waiting = self.transition == Transition::Flatten;
+            }
+            Some(_) => (),
+        }
+
+        self.position = match self.transition {
+            Transition::Immediate => target,
+            Transition::Flatten => {
+                if self.waiting && target != self.entry_position {
+                    self.waiting = false;
+                }
+                if self.waiting {
+                    0.0
+                } else {
+                    target
+                }
+            }
+            Transition::Blend(bars) => {
+                self.blend_bars = self.blend_bars.saturating_add(1).min(bars);
+                let weight = self.blend_bars as f64 / bars as f64;
+                self.blend_from * (1.0 - weight) + target * weight
+            }
+        };
+        Ok(self.position)
+    }
+}
+
+/// Combines the positions of several sub-strategies according to a regime signal.
+///
+/// Every bar follows the sub-strategy selected by `regimes` through a [`RegimeSwitch`].
+///
+/// # Arguments
+///
+/// * `regimes` - The index of the sub-strategy to follow at every bar.
+/// * `positions` - One slice of positions per sub-strategy, aligned with `regimes`.
+/// * `transition` - How positions move from one sub-strategy to the next.
+///
+/// # Returns
+///
+/// A Result containing the position to hold at every bar or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if no sub-strategy is provided, an
+/// `IndicatorError::MismatchedLength` if a sub-strategy and `regimes` differ in length, and an
+/// `IndicatorError::InvalidInput` if a regime has no sub-strategy or the transition is a blend
+/// over zero bars.
+pub fn switch_regimes(
+    regimes: &[usize],
+    positions: &[&[f64]],
+    transition: Transition,
+) -> Result<Vec<f64>, IndicatorError> {
+    if positions.is_empty() {
+        return Err(IndicatorError::NotEnoughData(
+            "At least one sub-strategy is required".to_string(),
+        ));
+    }
+    if positions.iter().any(|p| p.len() != regimes.len()) {
+        return Err(IndicatorError::MismatchedLength(
+            "Every sub-strategy must have a position for every regime".to_string(),
+        ));
+    }
+
+    let mut switch = RegimeSwitch::new(transition);
+    let mut bar = Vec::with_capacity(positions.len());
+    regimes
+        .iter()
+        .enumerate()
+        .map(|(i, regime)| {
+            bar.clear();
+            bar.extend(positions.iter().map(|p| p[i]));
+            switch.update(*regime, &bar)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_signals() {
+        let prices = [
+            100.0, 102.0, 101.0, 105.0, 104.0, 108.0, 107.0, 110.0, 109.0, 112.0,
+        ];
+        let signals = [1, 0, 1, 0, 1, 0, 1, 0, -1, 0];
+
+        // Test case with enough data
+        let result = evaluate_signals(&signals, &prices, &[1, 2]).unwrap();
+        assert_eq!(result.len(), 2);
+        let one = &result[0];
+        assert_eq!(one.horizon, 1);
+        assert_eq!(one.count, 5);
+        assert!((one.hit_rate - 0.8).abs() < 1e-12);
+        assert!((one.mean_return - 0.019_715_989_251_066_324).abs() < 1e-12);
+        assert!((one.median - 0.028_037_383_177_569_986).abs() < 1e-12);
+        assert!((one.random_mean_return - 0.007_729_861_224_823_968).abs() < 1e-12);
+        assert!((one.z_statistic - 1.221_295_279_577_454).abs() < 1e-9);
+        assert!((one.p_value - 0.221_974_240_032_256_6).abs() < 1e-9);
+        assert_eq!(result[1].count, 4);
+
+        // Test case with not enough data
+        let result = evaluate_signals(&signals, &prices, &[9]);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = evaluate_signals(&signals[1..], &prices, &[1]);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_search_thresholds() {
+        let prices = [
+            100.0, 98.0, 96.0, 99.0, 102.0, 100.0, 97.0, 95.0, 98.0, 101.0, 99.0, 96.0, 99.0,
+        ];
+        let oscillator = [
+            50.0, 35.0, 20.0, 45.0, 70.0, 55.0, 30.0, 15.0, 40.0, 75.0, 60.0, 25.0, 50.0,
+        ];
+
+        // Test case with enough data
+        let result = search_thresholds(
+            &oscillator,
+            &prices,
+            &[20.0, 30.0, 40.0],
+            &[40.0, 60.0, 80.0],
+            ThresholdObjective::TotalReturn,
+            1,
+        )
+        .unwrap();
+        assert_eq!(result.best.entry, 30.0);
+        assert_eq!(result.best.exit, 60.0);
+        assert!((result.best.objective - 0.164_905_427_631_578_9).abs() < 1e-12);
+        assert_eq!(result.splits.len(), 1);
+        let split = result.splits[0];
+        assert_eq!((split.train_len, split.test_len), (6, 6));
+        assert_eq!((split.fit.entry, split.fit.exit), (30.0, 60.0));
+        assert!((split.out_of_sample - 0.096_381_578_947_368_32).abs() < 1e-12);
+
+        // Test case with no valid pair of thresholds
+        let result = search_thresholds(
+            &oscillator,
+            &prices,
+            &[70.0],
+            &[30.0],
+            ThresholdObjective::SharpeRatio,
+            0,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with not enough data
+        let result = search_thresholds(
+            &oscillator,
+            &prices,
+            &[30.0],
+            &[60.0],
+            ThresholdObjective::KellyGrowth,
+            6,
+        );
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_permutation_test() {
+        let returns = (0..60)
+            .map(|i| ((i * 37 % 11) as f64 - 5.0) / 100.0)
+            .collect::<Vec<f64>>();
+        let foresight = returns.iter().map(|r| r.signum()).collect::<Vec<f64>>();
+
+        // Test case with enough data
+        let result = permutation_test(
+            &foresight,
+            &returns,
+            ThresholdObjective::SharpeRatio,
+            Resampling::Shuffle,
+            199,
+            7,
+        )
+        .unwrap();
+        assert!((result.p_value - 0.005).abs() < 1e-12);
+        assert!(result.null_mean < result.observed);
+        let again = permutation_test(
+            &foresight,
+            &returns,
+            ThresholdObjective::SharpeRatio,
+            Resampling::Shuffle,
+            199,
+            7,
+        )
+        .unwrap();
+        assert_eq!(result, again);
+
+        // A constant position is unchanged by any rearrangement
+        let result = permutation_test(
+            &[1.0; 60],
+            &returns,
+            ThresholdObjective::TotalReturn,
+            Resampling::Blocks(5),
+            99,
+            7,
+        )
+        .unwrap();
+        assert_eq!(result.p_value, 1.0);
+        assert!((result.null_mean - result.observed).abs() < 1e-12);
+
+        // Test case with mismatched lengths
+        let result = permutation_test(
+            &foresight[1..],
+            &returns,
+            ThresholdObjective::TotalReturn,
+            Resampling::Shuffle,
+            99,
+            7,
+        );
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+
+        // Test case with invalid input
+        let result = permutation_test(
+            &foresight,
+            &returns,
+            ThresholdObjective::TotalReturn,
+            Resampling::Blocks(0),
+            99,
+            7,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_switch_regimes() {
+        let regimes = [0, 0, 1, 1, 1, 0];
+        let trend = [1.0, 1.0, 1.0, 1.0, -1.0, -1.0];
+        let reversion = [-1.0, -1.0, -1.0, -1.0, 1.0, 1.0];
+
+        // Test case with enough data
+        let result =
+            switch_regimes(&regimes, &[&trend, &reversion], Transition::Immediate).unwrap();
+        assert_eq!(result, vec![1.0, 1.0, -1.0, -1.0, 1.0, -1.0]);
+        let result = switch_regimes(&regimes, &[&trend, &reversion], Transition::Flatten).unwrap();
+        assert_eq!(result, vec![1.0, 1.0, 0.0, 0.0, 1.0, 0.0]);
+        let result = switch_regimes(&regimes, &[&trend, &reversion], Transition::Blend(2)).unwrap();
+        assert_eq!(result, vec![1.0, 1.0, 0.0, -1.0, 1.0, 0.0]);
+
+        // Test case with a regime without sub-strategy
+        let result = switch_regimes(
+            &[0, 2],
+            &[&trend[..2], &reversion[..2]],
+            Transition::Immediate,
+        );
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = switch_regimes(&regimes, &[&trend, &reversion[1..]], Transition::Immediate);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Special functions and probability distributions used by the statistical routines.
+
+/// Relative accuracy targeted by the iterative evaluations.
+const EPSILON: f64 = 1e-15;
+
+/// Smallest magnitude allowed in the continued fraction evaluation, to avoid division by zero.
+const TINY: f64 = 1e-300;
+
+/// Maximum number of iterations of the series and continued fraction evaluations.
+const MAX_ITERATIONS: usize = 1_000;
+
+/// Coefficients of the Lanczos approximation with `g = 7`.
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Calculates the natural logarithm of the gamma function for positive `x`.
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = LANCZOS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Calculates the regularized upper incomplete gamma function `Q(a, x)`.
+pub(crate) fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        // Series expansion of the lower function P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..MAX_ITERATIONS {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        1.0 - sum * prefactor
+    } else {
+        // Continued fraction for Q(a, x) evaluated with the modified Lentz method
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / TINY;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        prefactor * h
+    }
+}
+
+/// Calculates the complementary error function.
+pub(crate) fn erfc(x: f64) -> f64 {
+    if x >= 0.0 {
+        gamma_q(0.5, x * x)
+    } else {
+        2.0 - gamma_q(0.5, x * x)
+    }
+}
+
+/// Calculates the cumulative distribution function of the standard normal distribution.
+pub(crate) fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Calculates the probability that a chi-squared variable with `df` degrees of freedom exceeds
+/// `x`.
+pub(crate) fn chi_squared_sf(x: f64, df: f64) -> f64 {
+    gamma_q(df / 2.0, x / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ln_gamma() {
+        assert!(ln_gamma(1.0).abs() < 1e-14);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-14);
+        assert!((ln_gamma(10.0) - 362_880.0_f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((erfc(0.5) - 0.479_500_122_186_953_5).abs() < 1e-14);
+        assert!((erfc(-1.0) - 1.842_700_792_949_715).abs() < 1e-14);
+        assert!((normal_cdf(1.96) - 0.975_002_104_851_779_5).abs() < 1e-14);
+        assert!((normal_cdf(-3.0) - 0.001_349_898_031_630_095_7).abs() < 1e-16);
+    }
+
+    #[test]
+    fn test_gamma_q() {
+        assert!((gamma_q(0.5, 0.25) - 0.479_500_122_186_953_5).abs() < 1e-14);
+        assert!((gamma_q(0.5, 9.0) - 2.209_049_699_858_544e-5).abs() < 1e-18);
+        assert!((chi_squared_sf(3.841_458_820_694_124, 1.0) - 0.05).abs() < 1e-12);
+        assert!((chi_squared_sf(2.0, 2.0) - (-1.0_f64).exp()).abs() < 1e-14);
+    }
+}
@@ -0,0 +1,1008 @@
+//! Rolling statistics updated incrementally as observations enter and leave a window.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::IndicatorError;
+
+/// Calculates the `q` quantile of an ascending sorted, non-empty slice using linear interpolation
+/// between the closest ranks.
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = (sorted.len() - 1) as f64 * q;
+    let lower = rank.floor() as usize;
+    let upper = (lower + 1).min(sorted.len() - 1);
+    sorted[lower] + (rank - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+/// Mean and variance of the most recent observations of a series, updated in constant time.
+///
+/// Observations are added and removed with Welford's algorithm, which avoids the cancellation of
+/// the naive sum of squares and keeps the statistics stable over long series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollingVariance {
+    window: usize,
+    values: VecDeque<f64>,
+    mean: f64,
+    m2: f64,
+}
+
+impl RollingVariance {
+    /// Creates empty statistics over the latest `window` observations.
+    pub fn new(window: usize) -> Self {
+        RollingVariance {
+            window,
+            values: VecDeque::with_capacity(window + 1),
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    /// Adds an observation, dropping the oldest one once the window is full.
+    pub fn push(&mut self, value: f64) {
+        self.values.push_back(value);
+        let n = self.values.len() as f64;
+        let delta = value - self.mean;
+        self.mean += delta / n;
+        self.m2 += delta * (value - self.mean);
+
+        if self.values.len() > self.window {
+            if let Some(old) = self.values.pop_front() {
+                let n = self.values.len() as f64;
+                if n == 0.0 {
+                    self.mean = 0.0;
+                    self.m2 = 0.0;
+                } else {
+                    let mean = self.mean - (old - self.mean) / n;
+                    self.m2 -= (old - mean) * (old - self.mean);
+                    self.mean = mean;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of observations in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no observation was added.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` once the window holds `window` observations.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.window
+    }
+
+    /// Returns the mean of the window, if it holds any observation.
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then_some(self.mean)
+    }
+
+    /// Returns the sample variance of the window, if it holds at least two observations.
+    pub fn variance(&self) -> Option<f64> {
+        (self.len() > 1).then(|| self.m2.max(0.0) / (self.len() - 1) as f64)
+    }
+
+    /// Returns the sample standard deviation of the window, if it holds at least two observations.
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+}
+
+/// Means, variances, and covariance of the most recent observations of two series, updated in
+/// constant time.
+///
+/// This is the two series counterpart of [`RollingVariance`], used for rolling betas and
+/// correlations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollingCovariance {
+    window: usize,
+    values: VecDeque<(f64, f64)>,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    comoment: f64,
+}
+
+impl RollingCovariance {
+    /// Creates empty statistics over the latest `window` pairs of observations.
+    pub fn new(window: usize) -> Self {
+        RollingCovariance {
+            window,
+            values: VecDeque::with_capacity(window + 1),
+            mean_x: 0.0,
+            mean_y: 0.0,
+            m2_x: 0.0,
+            m2_y: 0.0,
+            comoment: 0.0,
+        }
+    }
+
+    /// Adds a pair of observations, dropping the oldest pair once the window is full.
+    pub fn push(&mut self, x: f64, y: f64) {
+        self.values.push_back((x, y));
+        let n = self.values.len() as f64;
+        let delta_x = x - self.mean_x;
+        let delta_y = y - self.mean_y;
+        self.mean_x += delta_x / n;
+        self.mean_y += delta_y / n;
+        self.m2_x += delta_x * (x - self.mean_x);
+        self.m2_y += delta_y * (y - self.mean_y);
+        self.comoment += delta_x * (y - self.mean_y);
+
+        if self.values.len() > self.window {
+            if let Some((old_x, old_y)) = self.values.pop_front() {
+                let n = self.values.len() as f64;
+                if n == 0.0 {
+                    *self = RollingCovariance::new(self.window);
+                } else {
+                    let mean_x = self.mean_x - (old_x - self.mean_x) / n;
+                    let mean_y = self.mean_y - (old_y - self.mean_y) / n;
+                    self.m2_x -= (old_x - mean_x) * (old_x - self.mean_x);
+                    self.m2_y -= (old_y - mean_y) * (old_y - self.mean_y);
+                    self.comoment -= (old_x - mean_x) * (old_y - self.mean_y);
+                    self.mean_x = mean_x;
+                    self.mean_y = mean_y;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of pairs in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no pair was added.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` once the window holds `window` pairs.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.window
+    }
+
+    /// Returns the means of `x` and `y` over the window, if it holds any pair.
+    pub fn means(&self) -> Option<(f64, f64)> {
+        (!self.is_empty()).then_some((self.mean_x, self.mean_y))
+    }
+
+    /// Returns the sample variances of `x` and `y`, if the window holds at least two pairs.
+    pub fn variances(&self) -> Option<(f64, f64)> {
+        (self.len() > 1).then(|| {
+            let n = (self.len() - 1) as f64;
+            (self.m2_x.max(0.0) / n, self.m2_y.max(0.0) / n)
+        })
+    }
+
+    /// Returns the sample covariance of `x` and `y`, if the window holds at least two pairs.
+    pub fn covariance(&self) -> Option<f64> {
+        (self.len() > 1).then(|| self.comoment / (self.len() - 1) as f64)
+    }
+
+    /// Returns the Pearson correlation of `x` and `y`, if the window holds at least two pairs.
+    /// The correlation is `NaN` if either series is constant over the window.
+    pub fn correlation(&self) -> Option<f64> {
+        (self.len() > 1).then(|| {
+            let denominator = (self.m2_x.max(0.0) * self.m2_y.max(0.0)).sqrt();
+            if denominator == 0.0 {
+                f64::NAN
+            } else {
+                self.comoment / denominator
+            }
+        })
+    }
+
+    /// Returns the beta of `x` against `y`, the covariance divided by the variance of `y`, if the
+    /// window holds at least two pairs.
+    pub fn beta(&self) -> Option<f64> {
+        (self.len() > 1).then(|| self.comoment / self.m2_y)
+    }
+}
+
+/// Rolling correlations of every pair of series, produced by [`calculate_rolling_correlations`].
+///
+/// The values form a time by pair matrix ready to be drawn as a heatmap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollingCorrelations {
+    /// Indices of the two series in each pair, `(i, j)` with `i < j` in row-major order
+    pub pairs: Vec<(usize, usize)>,
+    /// Correlation of every pair for each window, one row per window and one column per pair
+    pub values: Vec<Vec<f64>>,
+}
+
+/// Welford moments over a window of several series and of every pair of them.
+struct WindowMoments {
+    count: usize,
+    means: Vec<f64>,
+    m2s: Vec<f64>,
+    comoments: Vec<f64>,
+}
+
+impl WindowMoments {
+    /// Adds the observations at index `t` of every series.
+    fn add(&mut self, series: &[&[f64]], pairs: &[(usize, usize)], t: usize) {
+        self.count += 1;
+        let n = self.count as f64;
+        let deltas = series
+            .iter()
+            .zip(&self.means)
+            .map(|(s, mean)| s[t] - mean)
+            .collect::<Vec<f64>>();
+        for (k, s) in series.iter().enumerate() {
+            self.means[k] += deltas[k] / n;
+            self.m2s[k] += deltas[k] * (s[t] - self.means[k]);
+        }
+        for (comoment, (i, j)) in self.comoments.iter_mut().zip(pairs) {
+            *comoment += deltas[*i] * (series[*j][t] - self.means[*j]);
+        }
+    }
+
+    /// Removes the observations at index `t` of every series.
+    fn remove(&mut self, series: &[&[f64]], pairs: &[(usize, usize)], t: usize) {
+        self.count -= 1;
+        let n = self.count as f64;
+        let means = series
+            .iter()
+            .zip(&self.means)
+            .map(|(s, mean)| mean - (s[t] - mean) / n)
+            .collect::<Vec<f64>>();
+        for (k, s) in series.iter().enumerate() {
+            self.m2s[k] -= (s[t] - means[k]) * (s[t] - self.means[k]);
+        }
+        for (comoment, (i, j)) in self.comoments.iter_mut().zip(pairs) {
+            *comoment -= (series[*i][t] - means[*i]) * (series[*j][t] - self.means[*j]);
+        }
+        self.means = means;
+    }
+}
+
+/// Calculates the rolling Pearson correlation of every pair of series.
+///
+/// The moments of every series and the co-moments of every pair are updated incrementally with
+/// Welford's algorithm as the window slides, so the cost is proportional to the number of pairs
+/// times the length of the series, independent of the window size. The first row corresponds to
+/// the window ending at index `window - 1`. Pairs involving a series that is constant over a
+/// window have a correlation of `NaN` for that window.
+///
+/// # Arguments
+///
+/// * `series` - The series to correlate, for example the returns of each asset.
+/// * `window` - The number of observations in each window.
+///
+/// # Returns
+///
+/// A Result containing the [`RollingCorrelations`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if the series differ in length, an
+/// `IndicatorError::InvalidInput` if `window` is less than 2, and an
+/// `IndicatorError::NotEnoughData` if there are fewer than two series or fewer than `window`
+/// observations.
+pub fn calculate_rolling_correlations(
+    series: &[&[f64]],
+    window: usize,
+) -> Result<RollingCorrelations, IndicatorError> {
+    if series.len() < 2 {
+        return Err(IndicatorError::NotEnoughData(
+            "At least two series are required".to_string(),
+        ));
+    }
+    let len = series[0].len();
+    if series.iter().any(|s| s.len() != len) {
+        return Err(IndicatorError::MismatchedLength(
+            "All series must have the same length".to_string(),
+        ));
+    }
+    if window < 2 {
+        return Err(IndicatorError::InvalidInput(
+            "`window` must be at least 2".to_string(),
+        ));
+    }
+    if len < window {
+        return Err(IndicatorError::NotEnoughData(format!(
+            "Series must have at least {} items",
+            window
+        )));
+    }
+
+    let count = series.len();
+    let pairs = (0..count)
+        .flat_map(|i| (i + 1..count).map(move |j| (i, j)))
+        .collect::<Vec<(usize, usize)>>();
+
+    let mut moments = WindowMoments {
+        count: 0,
+        means: vec![0.0; count],
+        m2s: vec![0.0; count],
+        comoments: vec![0.0; pairs.len()],
+    };
+
+    let mut values = Vec::with_capacity(len - window + 1);
+    for t in 0..len {
+        if t >= window {
+            moments.remove(series, &pairs, t - window);
+        }
+        moments.add(series, &pairs, t);
+        if t + 1 < window {
+            continue;
+        }
+        values.push(
+            pairs
+                .iter()
+                .zip(&moments.comoments)
+                .map(|((i, j), comoment)| {
+                    let denominator = (moments.m2s[*i].max(0.0) * moments.m2s[*j].max(0.0)).sqrt();
+                    if denominator == 0.0 {
+                        f64::NAN
+                    } else {
+                        comoment / denominator
+                    }
+                })
+                .collect(),
+        );
+    }
+
+    Ok(RollingCorrelations { pairs, values })
+}
+
+/// How a rolling sum is maintained as the window slides.
+///
+/// Adding the newest value and subtracting the oldest one rounds on every step, and over long
+/// series with large values the rounding errors accumulate into a drift of the sum. The modes
+/// trade speed for resistance to this drift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SumMode {
+    /// A plain running sum, the fastest option
+    Fast,
+    /// A running sum with Neumaier compensation, which carries the rounding error of every update
+    /// and keeps the drift close to a single rounding
+    Compensated,
+    /// A plain running sum recomputed from scratch with compensated summation every
+    /// `recompute_every` windows, bounding the drift to the updates since the last
+    /// recomputation. A value of 1 recomputes every window.
+    Exact {
+        /// Number of windows between two recomputations
+        recompute_every: usize,
+    },
+}
+
+/// Sums `values` with Neumaier compensation.
+fn compensated_sum(values: &[f64]) -> f64 {
+    let mut sum = RunningSum {
+        mode: SumMode::Compensated,
+        sum: 0.0,
+        compensation: 0.0,
+        steps: 0,
+    };
+    values.iter().for_each(|value| sum.add(*value));
+    sum.value()
+}
+
+/// A rolling sum maintained according to a [`SumMode`].
+struct RunningSum {
+    mode: SumMode,
+    sum: f64,
+    compensation: f64,
+    steps: usize,
+}
+
+impl RunningSum {
+    /// Adds `value` to the sum.
+    fn add(&mut self, value: f64) {
+        let sum = self.sum + value;
+        if self.mode == SumMode::Compensated {
+            self.compensation += if self.sum.abs() >= value.abs() {
+                (self.sum - sum) + value
+            } else {
+                (value - sum) + self.sum
+            };
+        }
+        self.sum = sum;
+    }
+
+    /// Slides the sum from the previous window to `window`, given the value that left it.
+    fn slide(&mut self, window: &[f64], removed: f64) {
+        self.steps += 1;
+        match self.mode {
+            SumMode::Exact { recompute_every } if self.steps.is_multiple_of(recompute_every) => {
+                self.sum = compensated_sum(window);
+                self.compensation = 0.0;
+            }
+            _ => {
+                self.add(-removed);
+                self.add(window[window.len() - 1]);
+            }
+        }
+    }
+
+    /// Returns the current value of the sum.
+    fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Calculates the mean of every window of `window` consecutive values.
+///
+/// The window sum is updated in constant time per value, using `mode` to control the rounding
+/// drift of long series. Every mode agrees with a direct computation to within a few rounding
+/// errors on short series; the modes only differ on long series of large values, such as tick
+/// prices averaged over hundreds of ticks.
+///
+/// # Arguments
+///
+/// * `values` - A slice of values to average.
+/// * `window` - The number of values in each window.
+/// * `mode` - How the window sum is maintained.
+///
+/// # Returns
+///
+/// A Result containing a vector with one mean per window or an `IndicatorError`. The value at
+/// index `i` covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `window` is zero or `mode` recomputes every zero
+/// windows, and an `IndicatorError::NotEnoughData` if the length of `values` is less than
+/// `window`.
+pub fn calculate_rolling_mean(
+    values: &[f64],
+    window: usize,
+    mode: SumMode,
+) -> Result<Vec<f64>, IndicatorError> {
+    if window == 0 || mode == (SumMode::Exact { recompute_every: 0 }) {
+        return Err(IndicatorError::InvalidInput(
+            "`window` and `recompute_every` must be positive".to_string(),
+        ));
+    }
+    if values.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "`values` must have at least `window` items".to_string(),
+        ));
+    }
+
+    let mut sum = RunningSum {
+        mode,
+        sum: 0.0,
+        compensation: 0.0,
+        steps: 0,
+    };
+    values[..window].iter().for_each(|value| sum.add(*value));
+
+    let mut means = Vec::with_capacity(values.len() - window + 1);
+    means.push(sum.value() / window as f64);
+    for end in window..values.len() {
+        sum.slide(&values[end + 1 - window..=end], values[end - window]);
+        means.push(sum.value() / window as f64);
+    }
+
+    Ok(means)
+}
+
+/// An `f64` ordered with `total_cmp`, so it can be stored in a heap.
+#[derive(Debug, Clone, Copy)]
+struct Key(f64);
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// A quantile of the most recent observations of a series, updated in logarithmic time.
+///
+/// The window is split between a max-heap holding the observations up to the quantile and a
+/// min-heap holding the rest, so the two order statistics around the quantile are always at the
+/// top of the heaps. Observations leaving the window are deleted lazily when they reach the top
+/// of their heap, and the heaps are rebuilt whenever stale observations make up more than half
+/// of them. Quantiles are linearly interpolated between order statistics, matching
+/// `numpy.quantile` with its default method. Observations must not be `NaN`.
+#[derive(Debug, Clone)]
+pub struct RollingQuantile {
+    window: usize,
+    q: f64,
+    values: VecDeque<f64>,
+    lower: BinaryHeap<Key>,
+    upper: BinaryHeap<Reverse<Key>>,
+    lower_len: usize,
+    upper_len: usize,
+    delayed: HashMap<u64, usize>,
+}
+
+impl RollingQuantile {
+    /// Creates an empty rolling `q` quantile over the latest `window` observations, where `q` is
+    /// within `[0, 1]`.
+    pub fn new(window: usize, q: f64) -> Self {
+        RollingQuantile {
+            window,
+            q,
+            values: VecDeque::with_capacity(window + 1),
+            lower: BinaryHeap::new(),
+            upper: BinaryHeap::new(),
+            lower_len: 0,
+            upper_len: 0,
+            delayed: HashMap::new(),
+        }
+    }
+
+    /// Adds an observation, dropping the oldest one once the window is full.
+    pub fn push(&mut self, value: f64) {
+        self.values.push_back(value);
+        if self.lower.peek().is_none_or(|top| Key(value) <= *top) {
+            self.lower.push(Key(value));
+            self.lower_len += 1;
+        } else {
+            self.upper.push(Reverse(Key(value)));
+            self.upper_len += 1;
+        }
+
+        if self.values.len() > self.window {
+            if let Some(old) = self.values.pop_front() {
+                self.erase(old);
+            }
+        }
+        if self.lower.len() + self.upper.len() > 2 * self.values.len().max(1) {
+            self.rebuild();
+        } else {
+            self.rebalance();
+        }
+    }
+
+    /// Returns the number of observations in the window.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no observation was added.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns `true` once the window holds `window` observations.
+    pub fn is_full(&self) -> bool {
+        self.values.len() == self.window
+    }
+
+    /// Returns the quantile of the window, if it holds any observation.
+    pub fn quantile(&self) -> Option<f64> {
+        let below = self.lower.peek()?.0;
+        let rank = (self.len() - 1) as f64 * self.q;
+        let fraction = rank - rank.floor();
+        if fraction == 0.0 {
+            return Some(below);
+        }
+        let above = self.upper.peek()?.0 .0;
+        Some(below + fraction * (above - below))
+    }
+
+    /// Number of observations the lower heap must hold for the current window.
+    fn lower_target(&self) -> usize {
+        ((self.len().max(1) - 1) as f64 * self.q).floor() as usize + 1
+    }
+
+    /// Marks an observation that left the window for deletion.
+    fn erase(&mut self, value: f64) {
+        *self.delayed.entry(value.to_bits()).or_insert(0) += 1;
+        match self.lower.peek() {
+            Some(top) if Key(value) <= *top => {
+                self.lower_len -= 1;
+                if Key(value) == *top {
+                    self.prune_lower();
+                }
+            }
+            _ => {
+                self.upper_len -= 1;
+                if self.upper.peek().is_some_and(|top| top.0 == Key(value)) {
+                    self.prune_upper();
+                }
+            }
+        }
+    }
+
+    /// Moves observations between the heaps until the lower heap holds the quantile.
+    fn rebalance(&mut self) {
+        let target = self.lower_target();
+        while self.lower_len > target {
+            if let Some(top) = self.lower.pop() {
+                self.upper.push(Reverse(top));
+                self.lower_len -= 1;
+                self.upper_len += 1;
+                self.prune_lower();
+            }
+        }
+        while self.lower_len < target && self.upper_len > 0 {
+            if let Some(Reverse(top)) = self.upper.pop() {
+                self.lower.push(top);
+                self.upper_len -= 1;
+                self.lower_len += 1;
+                self.prune_upper();
+            }
+        }
+    }
+
+    /// Rebuilds both heaps from the observations in the window, dropping stale observations.
+    fn rebuild(&mut self) {
+        let mut sorted = self.values.iter().copied().map(Key).collect::<Vec<Key>>();
+        sorted.sort();
+        let upper = sorted.split_off(self.lower_target().min(sorted.len()));
+        self.lower_len = sorted.len();
+        self.upper_len = upper.len();
+        self.lower = sorted.into();
+        self.upper = upper.into_iter().map(Reverse).collect();
+        self.delayed.clear();
+    }
+
+    /// Consumes a pending deletion of `key`, returning `true` if there was one.
+    fn take_delayed(&mut self, key: Key) -> bool {
+        match self.delayed.get_mut(&key.0.to_bits()) {
+            Some(count) => {
+                *count -= 1;
+                if *count == 0 {
+                    self.delayed.remove(&key.0.to_bits());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pops deleted observations off the top of the lower heap.
+    fn prune_lower(&mut self) {
+        while let Some(top) = self.lower.peek().copied() {
+            if !self.take_delayed(top) {
+                break;
+            }
+            self.lower.pop();
+        }
+    }
+
+    /// Pops deleted observations off the top of the upper heap.
+    fn prune_upper(&mut self) {
+        while let Some(Reverse(top)) = self.upper.peek().copied() {
+            if !self.take_delayed(top) {
+                break;
+            }
+            self.upper.pop();
+        }
+    }
+}
+
+/// Calculates the `q` quantile of every window of `window` consecutive values.
+///
+/// Each window is updated in `O(log window)` time with a [`RollingQuantile`], and quantiles are
+/// linearly interpolated between order statistics.
+///
+/// # Arguments
+///
+/// * `values` - A slice of values.
+/// * `window` - The number of values in each window.
+/// * `q` - The quantile to calculate, within `[0, 1]`, e.g. `0.5` for the median.
+///
+/// # Returns
+///
+/// A Result containing a vector with one quantile per window or an `IndicatorError`. The value at
+/// index `i` covers the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::InvalidInput` if `window` is zero, `q` is not within `[0, 1]`, or
+/// `values` contains `NaN`, and an `IndicatorError::NotEnoughData` if the length of `values` is
+/// less than `window`.
+pub fn calculate_rolling_quantile(
+    values: &[f64],
+    window: usize,
+    q: f64,
+) -> Result<Vec<f64>, IndicatorError> {
+    if window == 0 {
+        return Err(IndicatorError::InvalidInput(
+            "`window` must be positive".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&q) {
+        return Err(IndicatorError::InvalidInput(
+            "`q` must be within [0, 1]".to_string(),
+        ));
+    }
+    if values.iter().any(|value| value.is_nan()) {
+        return Err(IndicatorError::InvalidInput(
+            "`values` must not contain NaN".to_string(),
+        ));
+    }
+    if values.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "`values` must have at least `window` items".to_string(),
+        ));
+    }
+
+    let mut quantile = RollingQuantile::new(window, q);
+    Ok(values
+        .iter()
+        .filter_map(|value| {
+            quantile.push(*value);
+            if !quantile.is_full() {
+                return None;
+            }
+            quantile.quantile()
+        })
+        .collect())
+}
+
+/// Finds the position of the extreme of every window with a monotonic deque, in linear time.
+///
+/// `replaces(new, old)` returns `true` if `new` displaces `old` as the extreme. The deque holds
+/// the positions of candidates in the order they arrived, each displacing none of the later ones.
+fn rolling_arg_extreme(
+    values: &[f64],
+    window: usize,
+    replaces: impl Fn(f64, f64) -> bool,
+) -> Vec<usize> {
+    let mut candidates = VecDeque::with_capacity(window);
+    let mut positions = Vec::with_capacity((values.len() + 1).saturating_sub(window));
+    for (i, value) in values.iter().enumerate() {
+        while candidates
+            .back()
+            .is_some_and(|back: &usize| replaces(*value, values[*back]))
+        {
+            candidates.pop_back();
+        }
+        candidates.push_back(i);
+        if candidates[0] + window <= i {
+            candidates.pop_front();
+        }
+        if i + 1 >= window {
+            positions.push(candidates[0]);
+        }
+    }
+    positions
+}
+
+/// Finds the position of the largest value of every window, the latest one on ties.
+///
+/// The value at index `i` covers the window ending at index `i + window - 1`, and `window` must
+/// be positive.
+pub(crate) fn rolling_argmax(values: &[f64], window: usize) -> Vec<usize> {
+    rolling_arg_extreme(values, window, |new, old| new >= old)
+}
+
+/// Finds the position of the smallest value of every window, the latest one on ties.
+///
+/// The value at index `i` covers the window ending at index `i + window - 1`, and `window` must
+/// be positive.
+pub(crate) fn rolling_argmin(values: &[f64], window: usize) -> Vec<usize> {
+    rolling_arg_extreme(values, window, |new, old| new <= old)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_rolling_correlations() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let b = [2.0, 4.0, 6.0, 8.0, 10.0, 12.0];
+        let c = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0];
+        let d = [5.0, 5.0, 5.0, 5.0, 5.0, 5.0];
+
+        // Test case with enough data
+        let result = calculate_rolling_correlations(&[&a, &b, &c], 4).unwrap();
+        assert_eq!(result.pairs, vec![(0, 1), (0, 2), (1, 2)]);
+        assert_eq!(result.values.len(), 3);
+        let expected = [
+            [1.0, -0.25819888974716115, -0.25819888974716115],
+            [1.0, 0.5636018619766345, 0.5636018619766345],
+            [1.0, 0.7423914319063354, 0.7423914319063354],
+        ];
+        for (row, expected) in result.values.iter().zip(expected) {
+            for (actual, expected) in row.iter().zip(expected) {
+                assert!((actual - expected).abs() < 1e-9);
+            }
+        }
+
+        // Test case with a constant series
+        let result = calculate_rolling_correlations(&[&a, &d], 3).unwrap();
+        assert!(result.values.iter().all(|row| row[0].is_nan()));
+
+        // Test case with not enough data
+        let result = calculate_rolling_correlations(&[&a, &b], 7);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_rolling_correlations(&[&a, &b[..5]], 3);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+
+    #[test]
+    fn test_rolling_variance() {
+        let mut stats = RollingVariance::new(3);
+        assert_eq!(stats.mean(), None);
+        stats.push(1.0);
+        assert_eq!(stats.variance(), None);
+        for value in [2.0, 4.0, 8.0, 16.0] {
+            stats.push(value);
+        }
+
+        // Only the latest three observations are kept
+        assert!(stats.is_full());
+        assert!((stats.mean().unwrap() - 28.0 / 3.0).abs() < 1e-12);
+        assert!((stats.variance().unwrap() - 37.333333333333336).abs() < 1e-9);
+
+        // A large offset does not degrade the variance
+        let mut stats = RollingVariance::new(4);
+        for i in 0..10_000 {
+            stats.push(1e9 + (i % 4) as f64);
+        }
+        assert!((stats.variance().unwrap() - 5.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rolling_covariance() {
+        let mut stats = RollingCovariance::new(4);
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let y = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0];
+        for (x, y) in x.iter().zip(y) {
+            stats.push(*x, y);
+        }
+        assert_eq!(stats.len(), 4);
+        let (mean_x, mean_y) = stats.means().unwrap();
+        assert!((mean_x - 4.5).abs() < 1e-12);
+        assert!((mean_y - 4.75).abs() < 1e-12);
+        assert!((stats.variances().unwrap().1 - 10.916666666666666).abs() < 1e-9);
+        assert!((stats.covariance().unwrap() - 3.1666666666666665).abs() < 1e-9);
+        assert!((stats.correlation().unwrap() - 0.7423914319063354).abs() < 1e-9);
+        assert!((stats.beta().unwrap() - 3.1666666666666665 / 10.916666666666666).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calculate_rolling_mean() {
+        // Test case with enough data
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        for mode in [
+            SumMode::Fast,
+            SumMode::Compensated,
+            SumMode::Exact { recompute_every: 2 },
+        ] {
+            let result = calculate_rolling_mean(&values, 3, mode).unwrap();
+            assert_eq!(result, vec![2.0, 3.0, 4.0, 5.0]);
+        }
+
+        // Fast sums drift on long series of large values. Compensated sums stay within a few
+        // rounding errors of a sum computed relative to the price level, and periodic
+        // recomputation bounds the drift to the updates since the last recomputation.
+        let mut seed = 1_u64;
+        let ticks = (0..200_000)
+            .map(|_| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                1e9 + (seed >> 11) as f64 / (1_u64 << 53) as f64
+            })
+            .collect::<Vec<f64>>();
+        let window = 500;
+        let exact = ticks
+            .windows(window)
+            .map(|w| 1e9 + w.iter().map(|x| x - 1e9).sum::<f64>() / window as f64)
+            .collect::<Vec<f64>>();
+        let drift = |mode| {
+            calculate_rolling_mean(&ticks, window, mode)
+                .unwrap()
+                .iter()
+                .zip(&exact)
+                .fold(0.0_f64, |max, (a, e)| max.max((a - e).abs()))
+        };
+        let fast = drift(SumMode::Fast);
+        let compensated = drift(SumMode::Compensated);
+        let recomputed = drift(SumMode::Exact {
+            recompute_every: 10,
+        });
+        assert!(fast > 1e-6);
+        assert!(compensated < 5e-7);
+        assert!(recomputed < fast / 2.0);
+
+        // Test case with not enough data
+        let result = calculate_rolling_mean(&values, 7, SumMode::Fast);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with an invalid recomputation interval
+        let result = calculate_rolling_mean(&values, 3, SumMode::Exact { recompute_every: 0 });
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_rolling_quantile() {
+        // Test case with enough data
+        let values = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let result = calculate_rolling_quantile(&values, 4, 0.5).unwrap();
+        assert_eq!(result, vec![2.0, 2.5, 4.5, 3.5, 5.5]);
+        let result = calculate_rolling_quantile(&values, 4, 0.9).unwrap();
+        let expected = [3.7, 4.7, 7.8, 7.8, 8.1];
+        for (actual, expected) in result.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+
+        // Agreement with sorting every window, on long series with many duplicates
+        let mut seed = 7_u64;
+        let series = (0..2_000)
+            .map(|i| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                ((seed >> 33) % 50) as f64 + i as f64 * 0.01
+            })
+            .collect::<Vec<f64>>();
+        for (window, q) in [(1, 0.5), (7, 0.0), (20, 0.25), (33, 0.95), (50, 1.0)] {
+            let result = calculate_rolling_quantile(&series, window, q).unwrap();
+            assert_eq!(result.len(), series.len() - window + 1);
+            for (actual, w) in result.iter().zip(series.windows(window)) {
+                let mut sorted = w.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let rank = (window - 1) as f64 * q;
+                let below = sorted[rank.floor() as usize];
+                let above = sorted[rank.ceil() as usize];
+                let expected = below + (rank - rank.floor()) * (above - below);
+                assert!((actual - expected).abs() < 1e-9);
+            }
+        }
+
+        // Test case with not enough data
+        let result = calculate_rolling_quantile(&values, 9, 0.5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with an invalid quantile
+        let result = calculate_rolling_quantile(&values, 4, 1.5);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_argmax() {
+        let values = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0, 6.0, 1.0];
+        assert_eq!(rolling_argmax(&values, 3), [2, 2, 4, 5, 5, 5, 8, 8]);
+        assert_eq!(rolling_argmin(&values, 3), [1, 3, 3, 3, 6, 6, 6, 9]);
+        assert_eq!(rolling_argmax(&values, 1), (0..10).collect::<Vec<usize>>());
+        assert!(rolling_argmin(&values, 11).is_empty());
+    }
+}
@@ -0,0 +1,429 @@
+//! Compact in-memory representations of [`Series`] for holding large universes of symbols.
+//!
+//! Compact series trade some precision or access speed for memory. They are converted back to
+//! `f64` before any computation, so indicators always run in full precision on the stored values.
+
+use crate::series::Series;
+use crate::IndicatorError;
+
+/// Converts values to `f32`, rounding each to the nearest representable value.
+///
+/// `f32` holds about 7 significant digits, so prices keep a relative error below `6e-8`. Values
+/// beyond the range of `f32` become infinite.
+pub fn to_f32(values: &[f64]) -> Vec<f32> {
+    values.iter().map(|v| *v as f32).collect()
+}
+
+/// Converts values to `f64`, which is exact.
+pub fn to_f64(values: &[f32]) -> Vec<f64> {
+    values.iter().map(|v| f64::from(*v)).collect()
+}
+
+/// A [`Series`] storing its observations as `f32`, which halves the memory of the values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct F32Series {
+    timestamps: Vec<i64>,
+    values: Vec<f32>,
+}
+
+impl F32Series {
+    /// Returns the timestamps of the series.
+    pub fn timestamps(&self) -> &[i64] {
+        &self.timestamps
+    }
+
+    /// Returns the stored observations.
+    pub fn values_f32(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Returns the observations promoted to `f64`.
+    pub fn values(&self) -> Vec<f64> {
+        to_f64(&self.values)
+    }
+
+    /// Returns the number of observations.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the series has no observations.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the timestamp and promoted value of every observation.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.timestamps
+            .iter()
+            .zip(&self.values)
+            .map(|(t, v)| (*t, f64::from(*v)))
+    }
+
+    /// Returns the series with its observations promoted to `f64`.
+    pub fn to_series(&self) -> Series {
+        Series::new(self.timestamps.clone(), self.values())
+            .expect("timestamps were validated by the original series")
+    }
+
+    /// Computes an indicator in `f64` on the promoted observations, e.g.
+    /// `series.apply(|prices| calculate_rsi(prices, 14))`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `indicator`.
+    pub fn apply<F, T>(&self, indicator: F) -> Result<T, IndicatorError>
+    where
+        F: FnOnce(&[f64]) -> Result<T, IndicatorError>,
+    {
+        indicator(&self.values())
+    }
+}
+
+impl From<&Series> for F32Series {
+    fn from(series: &Series) -> Self {
+        F32Series {
+            timestamps: series.timestamps().to_vec(),
+            values: to_f32(series.values()),
+        }
+    }
+}
+
+/// A growable sequence of bits, written most significant bit first.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BitWriter {
+    words: Vec<u64>,
+    bits: usize,
+}
+
+impl BitWriter {
+    /// Appends the lowest `count` bits of `value`.
+    fn write(&mut self, value: u64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let value = if count == 64 {
+            value
+        } else {
+            value & ((1 << count) - 1)
+        };
+        let offset = (self.bits % 64) as u32;
+        if offset == 0 {
+            self.words.push(0);
+        }
+        let free = 64 - offset;
+        let last = self.words.len() - 1;
+        if count <= free {
+            self.words[last] |= value << (free - count);
+        } else {
+            self.words[last] |= value >> (count - free);
+            self.words.push(value << (64 - (count - free)));
+        }
+        self.bits += count as usize;
+    }
+}
+
+/// Reads the bits of a [`BitWriter`] back in order.
+struct BitReader<'a> {
+    words: &'a [u64],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    /// Reads the next `count` bits as the lowest bits of the result.
+    fn read(&mut self, count: u32) -> u64 {
+        if count == 0 {
+            return 0;
+        }
+        let word = self.position / 64;
+        let offset = (self.position % 64) as u32;
+        self.position += count as usize;
+        let available = 64 - offset;
+        if count <= available {
+            (self.words[word] << offset) >> (64 - count)
+        } else {
+            let high = (self.words[word] << offset) >> offset;
+            (high << (count - available)) | (self.words[word + 1] >> (64 - (count - available)))
+        }
+    }
+
+    fn bit(&mut self) -> bool {
+        self.read(1) == 1
+    }
+}
+
+/// Maps signed integers to unsigned ones so that small magnitudes get small codes.
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Bucket sizes of the delta-of-delta timestamp encoding, selected by a prefix of ones ended by a
+/// zero, except for the last bucket which has no terminating zero.
+const TIMESTAMP_BUCKETS: [u32; 4] = [7, 9, 12, 64];
+
+/// A [`Series`] compressed in the manner of Facebook's Gorilla time series database.
+///
+/// Timestamps are stored as the change of the difference between successive timestamps, which is
+/// zero and takes a single bit for bars at a fixed interval. Values are stored as the XOR of their
+/// bits with the previous value, keeping only the bits between the leading and trailing zeros, so
+/// a repeated value takes a single bit and slowly moving prices take far fewer than 64 bits.
+/// Compression is lossless, including for `NaN` payloads and signed zeros.
+///
+/// The series is read sequentially with [`CompressedSeries::iter`] or decompressed with
+/// [`CompressedSeries::to_series`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedSeries {
+    len: usize,
+    bits: BitWriter,
+}
+
+impl CompressedSeries {
+    /// Returns the number of observations.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the series has no observations.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes holding the compressed observations.
+    pub fn compressed_bytes(&self) -> usize {
+        self.bits.words.len() * std::mem::size_of::<u64>()
+    }
+
+    /// Returns the timestamp and value of every observation, decompressed in order.
+    pub fn iter(&self) -> CompressedIter<'_> {
+        CompressedIter {
+            reader: BitReader {
+                words: &self.bits.words,
+                position: 0,
+            },
+            remaining: self.len,
+            index: 0,
+            time: 0,
+            delta: 0,
+            value: 0,
+            leading: 0,
+            trailing: 0,
+        }
+    }
+
+    /// Returns the decompressed series.
+    pub fn to_series(&self) -> Series {
+        let (timestamps, values) = self.iter().unzip();
+        Series::new(timestamps, values).expect("timestamps were validated by the original series")
+    }
+}
+
+impl From<&Series> for CompressedSeries {
+    fn from(series: &Series) -> Self {
+        let mut bits = BitWriter::default();
+        let (mut time, mut delta, mut value) = (0_i64, 0_i64, 0_u64);
+        let (mut leading, mut trailing) = (u32::MAX, 0);
+        for (i, (t, v)) in series.timestamps().iter().zip(series.values()).enumerate() {
+            let v = v.to_bits();
+            if i == 0 {
+                bits.write(*t as u64, 64);
+                bits.write(v, 64);
+                (time, value) = (*t, v);
+                continue;
+            }
+
+            // Timestamp
+            let new_delta = t.wrapping_sub(time);
+            let code = zigzag(new_delta.wrapping_sub(delta));
+            if code == 0 {
+                bits.write(0, 1);
+            } else {
+                let bucket = TIMESTAMP_BUCKETS
+                    .iter()
+                    .position(|b| *b == 64 || code < 1 << b)
+                    .unwrap_or(TIMESTAMP_BUCKETS.len() - 1);
+                let prefix = if bucket == TIMESTAMP_BUCKETS.len() - 1 {
+                    (bucket as u32 + 1, (1 << (bucket + 1)) - 1)
+                } else {
+                    (bucket as u32 + 2, (1 << (bucket + 2)) - 2)
+                };
+                bits.write(prefix.1, prefix.0);
+                bits.write(code, TIMESTAMP_BUCKETS[bucket]);
+            }
+            (time, delta) = (*t, new_delta);
+
+            // Value
+            let xor = v ^ value;
+            if xor == 0 {
+                bits.write(0, 1);
+            } else {
+                let new_leading = xor.leading_zeros().min(31);
+                let new_trailing = xor.trailing_zeros();
+                let meaningful = 64 - new_leading - new_trailing;
+                // The previous window is reused while that is cheaper than describing a new one
+                if leading != u32::MAX
+                    && new_leading >= leading
+                    && new_trailing >= trailing
+                    && 64 - leading - trailing <= meaningful + 11
+                {
+                    bits.write(0b10, 2);
+                    bits.write(xor >> trailing, 64 - leading - trailing);
+                } else {
+                    bits.write(0b11, 2);
+                    bits.write(u64::from(new_leading), 5);
+                    bits.write(u64::from(meaningful - 1), 6);
+                    bits.write(xor >> new_trailing, meaningful);
+                    (leading, trailing) = (new_leading, new_trailing);
+                }
+            }
+            value = v;
+        }
+
+        CompressedSeries {
+            len: series.len(),
+            bits,
+        }
+    }
+}
+
+/// An iterator decompressing a [`CompressedSeries`], created by [`CompressedSeries::iter`].
+pub struct CompressedIter<'a> {
+    reader: BitReader<'a>,
+    remaining: usize,
+    index: usize,
+    time: i64,
+    delta: i64,
+    value: u64,
+    leading: u32,
+    trailing: u32,
+}
+
+impl Iterator for CompressedIter<'_> {
+    type Item = (i64, f64);
+
+    fn next(&mut self) -> Option<(i64, f64)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.index += 1;
+        let reader = &mut self.reader;
+        if self.index == 1 {
+            self.time = reader.read(64) as i64;
+            self.value = reader.read(64);
+            return Some((self.time, f64::from_bits(self.value)));
+        }
+
+        let mut bucket = 0;
+        while bucket < TIMESTAMP_BUCKETS.len() && reader.bit() {
+            bucket += 1;
+        }
+        let dod = match bucket {
+            0 => 0,
+            _ => unzigzag(reader.read(TIMESTAMP_BUCKETS[bucket - 1])),
+        };
+        self.delta = self.delta.wrapping_add(dod);
+        self.time = self.time.wrapping_add(self.delta);
+
+        if reader.bit() {
+            if reader.bit() {
+                self.leading = reader.read(5) as u32;
+                let meaningful = reader.read(6) as u32 + 1;
+                self.trailing = 64 - self.leading - meaningful;
+            }
+            let meaningful = 64 - self.leading - self.trailing;
+            self.value ^= reader.read(meaningful) << self.trailing;
+        }
+        Some((self.time, f64::from_bits(self.value)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_ema, calculate_rsi};
+
+    #[test]
+    fn test_f32_series() {
+        // A random walk of prices around 100
+        let mut state = 12_345_u64;
+        let mut price = 100.0;
+        let prices = (0..1_000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1);
+                price *= 1.0 + ((state >> 33) as f64 / (1u64 << 31) as f64 - 0.5) / 50.0;
+                price
+            })
+            .collect::<Vec<f64>>();
+        let series = Series::new((0..1_000).collect(), prices.clone()).unwrap();
+        let compact = F32Series::from(&series);
+        assert_eq!(compact.len(), 1_000);
+        assert_eq!(compact.to_series().timestamps(), series.timestamps());
+
+        // Stored values are within the rounding of f32
+        for ((_, value), price) in compact.iter().zip(&prices) {
+            assert!(((value - price) / price).abs() < 6e-8);
+        }
+
+        // Indicators computed on the stored values stay close to full precision
+        let full = calculate_ema(&prices, 20).unwrap();
+        let stored = compact.apply(|p| calculate_ema(p, 20)).unwrap();
+        for (a, b) in full.iter().zip(&stored) {
+            assert!(((a - b) / a).abs() < 1e-7);
+        }
+        let full = calculate_rsi(&prices, 14).unwrap();
+        let stored = compact.apply(|p| calculate_rsi(p, 14)).unwrap();
+        for (a, b) in full.iter().zip(&stored) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_compressed_series() {
+        // Minute bars with a gap, repeated closes, and special values
+        let mut timestamps = (0..500)
+            .map(|i| 1_700_000_000 + 60 * i)
+            .collect::<Vec<i64>>();
+        timestamps[250..].iter_mut().for_each(|t| *t += 3_600);
+        let mut values = (0..500)
+            .map(|i| 100.0 + ((i * 7 % 13) / 4) as f64 * 0.25)
+            .collect::<Vec<f64>>();
+        values[10] = f64::NAN;
+        values[11] = -0.0;
+        values[12] = f64::INFINITY;
+        values[13] = 1e-300;
+        let series = Series::new(timestamps, values).unwrap();
+
+        let compressed = CompressedSeries::from(&series);
+        assert_eq!(compressed.len(), 500);
+        let decompressed = compressed.to_series();
+        assert_eq!(decompressed.timestamps(), series.timestamps());
+        for (a, b) in decompressed.values().iter().zip(series.values()) {
+            assert_eq!(a.to_bits(), b.to_bits());
+        }
+
+        // Regular bars of slowly moving prices compress several-fold
+        let raw = series.len() * (std::mem::size_of::<i64>() + std::mem::size_of::<f64>());
+        assert!(compressed.compressed_bytes() * 4 < raw);
+
+        // Irregular timestamps and arbitrary values still round trip
+        let series = Series::new(
+            vec![-5, 0, 1, 1_000, 1_000_000_000, i64::MAX],
+            vec![1.5, -2.25, 1e300, 3.0, 3.0, f64::MIN_POSITIVE],
+        )
+        .unwrap();
+        let compressed = CompressedSeries::from(&series);
+        assert_eq!(compressed.iter().collect::<Vec<_>>().len(), 6);
+        assert_eq!(compressed.to_series(), series);
+        assert!(CompressedSeries::from(&Series::new(vec![], vec![]).unwrap()).is_empty());
+    }
+}
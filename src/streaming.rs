@@ -0,0 +1,461 @@
+//! Indicators updated one bar at a time, with provisional values for the bar that is still
+//! forming.
+//!
+//! A [`StreamingIndicator`] consumes the closing price of every bar. Wrapping it in a
+//! [`DevelopingBar`] also yields a [`BarValue::Provisional`] value for every tick of the forming
+//! bar, computed as if the bar closed at that tick, without changing the state of the indicator.
+//! Only the closing price of the bar updates the state and yields a [`BarValue::Final`] value, so
+//! the final values match the batch functions of this crate and provisional values never leak into
+//! later bars.
+
+use std::collections::VecDeque;
+
+use crate::kama_step;
+
+/// An indicator that is updated with the closing price of one bar at a time.
+///
+/// Implementations must be cheap to clone, as the provided [`StreamingIndicator::preview`]
+/// updates a clone.
+pub trait StreamingIndicator: Clone {
+    /// The value produced for every bar
+    type Output;
+
+    /// Adds the closing price of a bar and returns the value of the indicator, once it has seen
+    /// enough bars.
+    fn update(&mut self, price: f64) -> Option<Self::Output>;
+
+    /// Returns the value the indicator would have if the next bar closed at `price`, leaving the
+    /// indicator unchanged.
+    fn preview(&self, price: f64) -> Option<Self::Output> {
+        self.clone().update(price)
+    }
+}
+
+/// A value of an indicator for a bar that is either still forming or closed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarValue<T> {
+    /// The value computed from the latest tick of a forming bar, replaced by later ticks and by
+    /// the final value
+    Provisional(T),
+    /// The value computed from the closing price of a bar, which never changes
+    Final(T),
+}
+
+impl<T> BarValue<T> {
+    /// Returns the value, whether provisional or final.
+    pub fn value(&self) -> &T {
+        match self {
+            BarValue::Provisional(value) | BarValue::Final(value) => value,
+        }
+    }
+
+    /// Returns the value, whether provisional or final.
+    pub fn into_value(self) -> T {
+        match self {
+            BarValue::Provisional(value) | BarValue::Final(value) => value,
+        }
+    }
+
+    /// Returns `true` if the value belongs to a closed bar.
+    pub fn is_final(&self) -> bool {
+        matches!(self, BarValue::Final(_))
+    }
+}
+
+/// Feeds the ticks of forming bars and the closes of finished bars to an indicator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DevelopingBar<I> {
+    indicator: I,
+}
+
+impl<I: StreamingIndicator> DevelopingBar<I> {
+    /// Wraps `indicator`.
+    pub fn new(indicator: I) -> Self {
+        DevelopingBar { indicator }
+    }
+
+    /// Returns the indicator, updated with the closes of all finished bars.
+    pub fn indicator(&self) -> &I {
+        &self.indicator
+    }
+
+    /// Returns the provisional value of the forming bar given its latest price.
+    pub fn tick(&self, price: f64) -> Option<BarValue<I::Output>> {
+        self.indicator.preview(price).map(BarValue::Provisional)
+    }
+
+    /// Closes the forming bar at `price` and returns its final value.
+    pub fn close(&mut self, price: f64) -> Option<BarValue<I::Output>> {
+        self.indicator.update(price).map(BarValue::Final)
+    }
+}
+
+/// The streaming counterpart of [`calculate_sma`](crate::calculate_sma).
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingSma {
+    window: usize,
+    prices: VecDeque<f64>,
+    sum: f64,
+}
+
+impl StreamingSma {
+    /// Creates an SMA over the latest `window` prices.
+    pub fn new(window: usize) -> Self {
+        StreamingSma {
+            window,
+            prices: VecDeque::with_capacity(window + 1),
+            sum: 0.0,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingSma {
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        self.prices.push_back(price);
+        if self.prices.len() > self.window {
+            self.sum -= self.prices.pop_front().unwrap_or(0.0);
+        }
+        self.sum += price;
+        (self.window > 0 && self.prices.len() == self.window).then(|| self.sum / self.window as f64)
+    }
+
+    fn preview(&self, price: f64) -> Option<f64> {
+        if self.window == 0 || self.prices.len() + 1 < self.window {
+            return None;
+        }
+        let removed = if self.prices.len() == self.window {
+            self.prices[0]
+        } else {
+            0.0
+        };
+        Some((self.sum - removed + price) / self.window as f64)
+    }
+}
+
+/// The streaming counterpart of [`calculate_ema`](crate::calculate_ema), seeded with the SMA of
+/// the first `window` prices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingEma {
+    window: usize,
+    seen: usize,
+    value: f64,
+}
+
+impl StreamingEma {
+    /// Creates an EMA over `window` prices.
+    pub fn new(window: usize) -> Self {
+        StreamingEma {
+            window,
+            seen: 0,
+            value: 0.0,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingEma {
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        if self.window == 0 {
+            return None;
+        }
+        self.seen += 1;
+        if self.seen <= self.window {
+            self.value += price / self.window as f64;
+            return (self.seen == self.window).then_some(self.value);
+        }
+        let smoothing = 2.0 / (self.window as f64 + 1.0);
+        self.value += (price - self.value) * smoothing;
+        Some(self.value)
+    }
+}
+
+/// The streaming counterpart of [`calculate_rsi`](crate::calculate_rsi), producing the same
+/// values once `window + 1` prices were seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingRsi {
+    window: usize,
+    previous: Option<f64>,
+    changes: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl StreamingRsi {
+    /// Creates an RSI over `window` price changes.
+    pub fn new(window: usize) -> Self {
+        StreamingRsi {
+            window,
+            previous: None,
+            changes: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingRsi {
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        let previous = self.previous.replace(price)?;
+        if self.window == 0 {
+            return None;
+        }
+        let change = price - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let window = self.window as f64;
+
+        self.changes += 1;
+        if self.changes <= self.window {
+            self.avg_gain += gain / window;
+            self.avg_loss += loss / window;
+            if self.changes < self.window {
+                return None;
+            }
+        }
+        // The batch RSI smooths the last change of the first window into its average once more,
+        // which is kept here so both produce the same values
+        self.avg_gain = (self.avg_gain * (window - 1.0) + gain) / window;
+        self.avg_loss = (self.avg_loss * (window - 1.0) + loss) / window;
+
+        let rs = if self.avg_loss > 0.0 {
+            self.avg_gain / self.avg_loss
+        } else {
+            f64::INFINITY
+        };
+        Some(100.0 - (100.0 / (1.0 + rs)))
+    }
+}
+
+/// The streaming counterpart of [`calculate_kama`](crate::calculate_kama), producing the same
+/// values once `window + 1` prices were seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingKama {
+    window: usize,
+    fast: usize,
+    slow: usize,
+    prices: VecDeque<f64>,
+    value: f64,
+}
+
+impl StreamingKama {
+    /// Creates a KAMA with an efficiency ratio over `window` price changes, smoothing between
+    /// EMAs over `fast` and `slow` prices.
+    pub fn new(window: usize, fast: usize, slow: usize) -> Self {
+        StreamingKama {
+            window,
+            fast,
+            slow,
+            prices: VecDeque::with_capacity(window + 2),
+            value: 0.0,
+        }
+    }
+}
+
+impl StreamingIndicator for StreamingKama {
+    type Output = f64;
+
+    fn update(&mut self, price: f64) -> Option<f64> {
+        if self.window == 0 {
+            return None;
+        }
+        self.prices.push_back(price);
+        if self.prices.len() == self.window {
+            self.value = price;
+        }
+        if self.prices.len() <= self.window {
+            return None;
+        }
+        if self.prices.len() > self.window + 1 {
+            self.prices.pop_front();
+        }
+        self.value = kama_step(
+            self.value,
+            self.prices.make_contiguous(),
+            self.fast,
+            self.slow,
+        );
+        Some(self.value)
+    }
+}
+
+/// The streaming counterpart of [`calculate_psar`](crate::calculate_psar), updated with the high
+/// and low of one bar at a time and producing the same values from the second bar on.
+///
+/// It does not implement [`StreamingIndicator`], which takes a single closing price per bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingPsar {
+    af_start: f64,
+    af_step: f64,
+    af_max: f64,
+    /// High and low of the previous two bars, the latest last
+    bars: VecDeque<(f64, f64)>,
+    long: bool,
+    sar: f64,
+    extreme: f64,
+    factor: f64,
+}
+
+impl StreamingPsar {
+    /// Creates a Parabolic SAR whose acceleration factor starts at `af_start` and grows by
+    /// `af_step` up to `af_max`.
+    pub fn new(af_start: f64, af_step: f64, af_max: f64) -> Self {
+        StreamingPsar {
+            af_start,
+            af_step,
+            af_max,
+            bars: VecDeque::with_capacity(3),
+            long: true,
+            sar: 0.0,
+            extreme: 0.0,
+            factor: af_start,
+        }
+    }
+
+    /// Adds the high and low of a bar and returns the SAR and the direction of the trend, `1` for
+    /// long and `-1` for short, once two bars were seen.
+    pub fn update(&mut self, high: f64, low: f64) -> Option<(f64, i8)> {
+        match self.bars.len() {
+            0 => {
+                self.bars.push_back((high, low));
+                return None;
+            }
+            1 => {
+                let (first_high, first_low) = self.bars[0];
+                self.long = high + low >= first_high + first_low;
+                (self.sar, self.extreme) = if self.long {
+                    (first_low, first_high.max(high))
+                } else {
+                    (first_high, first_low.min(low))
+                };
+                self.factor = self.af_start;
+            }
+            _ => {
+                let ((older_high, older_low), (newer_high, newer_low)) =
+                    (self.bars[0], self.bars[1]);
+                self.sar += self.factor * (self.extreme - self.sar);
+                if self.long {
+                    self.sar = self.sar.min(newer_low).min(older_low);
+                    if low < self.sar {
+                        (self.long, self.sar, self.extreme, self.factor) =
+                            (false, self.extreme, low, self.af_start);
+                    } else if high > self.extreme {
+                        self.extreme = high;
+                        self.factor = (self.factor + self.af_step).min(self.af_max);
+                    }
+                } else {
+                    self.sar = self.sar.max(newer_high).max(older_high);
+                    if high > self.sar {
+                        (self.long, self.sar, self.extreme, self.factor) =
+                            (true, self.extreme, high, self.af_start);
+                    } else if low < self.extreme {
+                        self.extreme = low;
+                        self.factor = (self.factor + self.af_step).min(self.af_max);
+                    }
+                }
+                self.bars.pop_front();
+            }
+        }
+        self.bars.push_back((high, low));
+        Some((self.sar, if self.long { 1 } else { -1 }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_ema, calculate_kama, calculate_psar, calculate_rsi, calculate_sma};
+
+    #[test]
+    fn test_developing_bar() {
+        let closes = [1.0, 2.0, 3.0, 4.0, 6.0, 5.0];
+        let mut sma = DevelopingBar::new(StreamingSma::new(3));
+        let mut ema = DevelopingBar::new(StreamingEma::new(3));
+
+        let mut sma_values = Vec::new();
+        let mut ema_values = Vec::new();
+        for close in closes {
+            // Ticks of the forming bar do not change the final values
+            for tick in [close - 1.0, close + 1.0] {
+                if let Some(value) = sma.tick(tick) {
+                    assert!(!value.is_final());
+                }
+                ema.tick(tick);
+            }
+            sma_values.extend(sma.close(close).map(BarValue::into_value));
+            ema_values.extend(ema.close(close).map(BarValue::into_value));
+        }
+
+        let expected = calculate_sma(&closes, 3).unwrap();
+        assert_eq!(sma_values.len(), expected.len());
+        for (value, expected) in sma_values.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        let expected = calculate_ema(&closes, 3).unwrap();
+        assert_eq!(ema_values.len(), expected.len());
+        for (value, expected) in ema_values.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+
+        // A provisional value is the final value of a bar closing at the tick
+        let provisional = sma.tick(9.0).unwrap();
+        assert_eq!(provisional, BarValue::Provisional(20.0 / 3.0));
+        assert_eq!(
+            *ema.tick(9.0).unwrap().value(),
+            ema.indicator().clone().update(9.0).unwrap()
+        );
+        assert!(sma.close(9.0).unwrap().is_final());
+    }
+
+    #[test]
+    fn test_streaming_rsi() {
+        let closes = [44.0, 44.5, 43.8, 44.2, 45.1, 45.0, 46.2, 45.7, 46.5, 46.0];
+        let mut rsi = StreamingRsi::new(4);
+        let values = closes
+            .iter()
+            .filter_map(|close| rsi.update(*close))
+            .collect::<Vec<f64>>();
+        let expected = calculate_rsi(&closes, 4).unwrap();
+        assert_eq!(values.len(), expected.len());
+        for (value, expected) in values.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_streaming_kama() {
+        let closes = [10.0, 10.5, 10.2, 11.0, 11.4, 11.1, 12.0, 12.5, 12.2, 13.0];
+        let mut kama = StreamingKama::new(3, 2, 30);
+        let values = closes
+            .iter()
+            .filter_map(|close| kama.update(*close))
+            .collect::<Vec<f64>>();
+        let expected = calculate_kama(&closes, 3, 2, 30).unwrap();
+        assert_eq!(values.len(), expected.len());
+        for (value, expected) in values.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_streaming_psar() {
+        let high = [10.0, 11.0, 12.0, 12.5, 12.2, 11.0, 10.5, 10.8, 11.6, 12.1];
+        let low = [9.0, 10.0, 11.0, 11.5, 11.0, 9.5, 9.2, 9.6, 10.5, 11.2];
+        let mut psar = StreamingPsar::new(0.03, 0.02, 0.2);
+        let mut sar = Vec::new();
+        let mut direction = Vec::new();
+        for (high, low) in high.iter().zip(&low) {
+            if let Some((value, trend)) = psar.update(*high, *low) {
+                sar.push(value);
+                direction.push(trend);
+            }
+        }
+        let expected = calculate_psar(&high, &low, 0.03, 0.02, 0.2).unwrap();
+        assert_eq!(sar, expected.sar);
+        assert_eq!(direction, expected.direction);
+        assert!(direction.contains(&1) && direction.contains(&-1));
+    }
+}
@@ -0,0 +1,268 @@
+//! Helpers for checking the correctness guarantees of indicators.
+//!
+//! # No repainting
+//!
+//! Every indicator in this crate emits its values in input order, with the first value
+//! corresponding to the first input that completes the indicator's window. Appending new data to
+//! the input therefore only appends new values to the output: values that were already emitted
+//! are never changed. [`verify_no_repaint`] checks this contract for any indicator, including
+//! ones composed by users from the functions of this crate.
+//!
+//! The one exception is the Chikou Span of [`crate::Ichimoku`], a display-only line that plots
+//! every close behind its own bar. Its values are `NaN` until the later bar they show arrives, so
+//! it repaints and must not feed signals.
+//!
+//! # No lookahead
+//!
+//! A signal evaluated at a bar may only depend on that bar and the bars before it. A
+//! [`LookaheadGuard`] wraps the input data of a signal and records every read of a later bar, and
+//! [`check_lookahead`] evaluates a signal at every bar through such a guard.
+
+use std::cell::{Cell, RefCell};
+
+use crate::IndicatorError;
+
+/// An emitted indicator value that changed once more input data was appended, as reported by
+/// [`verify_no_repaint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repaint {
+    /// Length of the input at which the previously emitted value changed.
+    pub input_len: usize,
+    /// Index of the changed value in the indicator output.
+    pub index: usize,
+    /// Value emitted for `index` with one input item less.
+    pub previous: f64,
+    /// Value emitted for `index` with `input_len` input items, or `None` if the output no longer
+    /// contains `index`.
+    pub current: Option<f64>,
+}
+
+/// Compares two indicator values, treating `NaN` as equal to itself.
+fn same_value(a: f64, b: f64) -> bool {
+    a == b || (a.is_nan() && b.is_nan())
+}
+
+/// Verifies that an indicator never changes a previously emitted value when new data is
+/// appended to its input.
+///
+/// The indicator is evaluated on every prefix of `prices`, and the output for each prefix is
+/// compared with the output for the prefix one item shorter. Prefixes for which the indicator
+/// returns an error, such as `IndicatorError::NotEnoughData`, are treated as producing no output.
+///
+/// # Arguments
+///
+/// * `prices` - The input data to evaluate the indicator on.
+/// * `indicator` - The indicator to verify, mapping an input slice to its output values.
+///
+/// # Returns
+///
+/// `Ok(())` if no value was repainted, or the first [`Repaint`] found.
+pub fn verify_no_repaint<F>(prices: &[f64], indicator: F) -> Result<(), Repaint>
+where
+    F: Fn(&[f64]) -> Result<Vec<f64>, IndicatorError>,
+{
+    let mut previous: Vec<f64> = Vec::new();
+    for input_len in 1..=prices.len() {
+        let current = indicator(&prices[..input_len]).unwrap_or_default();
+        for (index, value) in previous.iter().enumerate() {
+            let emitted = current.get(index).copied();
+            if !emitted.is_some_and(|c| same_value(c, *value)) {
+                return Err(Repaint {
+                    input_len,
+                    index,
+                    previous: *value,
+                    current: emitted,
+                });
+            }
+        }
+        previous = current;
+    }
+
+    Ok(())
+}
+
+/// A read of a future bar recorded by a [`LookaheadGuard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookaheadAccess {
+    /// Bar the signal was being evaluated at.
+    pub bar: usize,
+    /// Index of the future bar that was read.
+    pub index: usize,
+}
+
+/// A read-only view of a data slice that records reads of bars after the bar being evaluated.
+#[derive(Debug)]
+pub struct LookaheadGuard<'a> {
+    data: &'a [f64],
+    bar: Cell<usize>,
+    violations: RefCell<Vec<LookaheadAccess>>,
+}
+
+impl<'a> LookaheadGuard<'a> {
+    /// Creates a guard over `data`, evaluating at the first bar.
+    pub fn new(data: &'a [f64]) -> Self {
+        LookaheadGuard {
+            data,
+            bar: Cell::new(0),
+            violations: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the bar currently being evaluated.
+    pub fn bar(&self) -> usize {
+        self.bar.get()
+    }
+
+    /// Moves evaluation to `bar`.
+    pub fn set_bar(&self, bar: usize) {
+        self.bar.set(bar);
+    }
+
+    /// Returns the number of bars in the underlying data.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the underlying data has no bars.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the value at `index`, recording a violation if it lies after the current bar.
+    pub fn get(&self, index: usize) -> Option<f64> {
+        self.record(index);
+        self.data.get(index).copied()
+    }
+
+    /// Returns the values in `start..end`, recording a violation if the range extends past the
+    /// current bar.
+    pub fn range(&self, start: usize, end: usize) -> &'a [f64] {
+        if end > start {
+            self.record(end - 1);
+        }
+        &self.data[start..end]
+    }
+
+    /// Returns every value up to and including the current bar. Reading the history never
+    /// records a violation.
+    pub fn history(&self) -> &'a [f64] {
+        &self.data[..(self.bar() + 1).min(self.data.len())]
+    }
+
+    /// Returns the recorded reads of future bars, in the order they happened.
+    pub fn violations(&self) -> Vec<LookaheadAccess> {
+        self.violations.borrow().clone()
+    }
+
+    /// Records a read of `index` if it lies after the current bar.
+    fn record(&self, index: usize) {
+        let bar = self.bar();
+        if index > bar {
+            self.violations
+                .borrow_mut()
+                .push(LookaheadAccess { bar, index });
+        }
+    }
+}
+
+/// Outputs and lookahead violations of a signal evaluated by [`check_lookahead`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookaheadReport<T> {
+    /// Output of the signal at every bar.
+    pub outputs: Vec<T>,
+    /// Every read of a future bar made while computing `outputs`.
+    pub violations: Vec<LookaheadAccess>,
+}
+
+/// Evaluates a signal at every bar of `data` and records any reads of future bars.
+///
+/// # Arguments
+///
+/// * `data` - The input data of the signal.
+/// * `signal` - The signal to check, called with a guard over `data` and the bar to evaluate. It
+///   should read `data` exclusively through the guard.
+///
+/// # Returns
+///
+/// A [`LookaheadReport`] holding the signal outputs and the recorded violations. The signal is
+/// free of lookahead bias if `violations` is empty.
+pub fn check_lookahead<F, T>(data: &[f64], mut signal: F) -> LookaheadReport<T>
+where
+    F: FnMut(&LookaheadGuard, usize) -> T,
+{
+    let guard = LookaheadGuard::new(data);
+    let outputs = (0..data.len())
+        .map(|bar| {
+            guard.set_bar(bar);
+            signal(&guard, bar)
+        })
+        .collect();
+
+    LookaheadReport {
+        outputs,
+        violations: guard.violations(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calculate_ema, calculate_macd, calculate_rsi};
+
+    const PRICES: [f64; 12] = [
+        44.0, 44.3, 44.1, 43.6, 44.3, 44.8, 45.1, 45.4, 45.8, 46.1, 45.9, 46.3,
+    ];
+
+    #[test]
+    fn test_verify_no_repaint() {
+        // The indicators of this crate never repaint
+        assert!(verify_no_repaint(&PRICES, |p| calculate_rsi(p, 3)).is_ok());
+        assert!(verify_no_repaint(&PRICES, |p| calculate_ema(p, 4)).is_ok());
+        for line in 0..3 {
+            let result = verify_no_repaint(&PRICES, |p| {
+                calculate_macd(p, 2, 4, 2).map(|macd| [macd.0, macd.1, macd.2][line].clone())
+            });
+            assert!(result.is_ok());
+        }
+
+        // Normalizing by the maximum of the whole input repaints once a new high arrives
+        let normalized = |p: &[f64]| {
+            let max = p.iter().fold(f64::MIN, |m, x| m.max(*x));
+            Ok(p.iter().map(|x| x / max).collect())
+        };
+        let result = verify_no_repaint(&PRICES, normalized).unwrap_err();
+        assert_eq!(result.input_len, 2);
+        assert_eq!(result.index, 0);
+        assert_eq!(result.previous, 1.0);
+        assert_eq!(result.current, Some(44.0 / 44.3));
+    }
+
+    #[test]
+    fn test_check_lookahead() {
+        // A momentum signal only reads the current and past bars
+        let report = check_lookahead(&PRICES, |data, bar| {
+            bar.checked_sub(2)
+                .and_then(|past| Some(data.get(bar)? - data.get(past)?))
+        });
+        assert!(report.violations.is_empty());
+        assert_eq!(report.outputs.len(), PRICES.len());
+        assert_eq!(report.outputs[0], None);
+        assert!((report.outputs[2].unwrap() - 0.1).abs() < 1e-9);
+
+        // Reading the history never records a violation
+        let report = check_lookahead(&PRICES, |data, _| data.history().len());
+        assert!(report.violations.is_empty());
+
+        // Comparing against the next bar is lookahead bias
+        let report = check_lookahead(&PRICES[..3], |data, bar| {
+            data.range(bar, (bar + 2).min(data.len())).len()
+        });
+        assert_eq!(
+            report.violations,
+            vec![
+                LookaheadAccess { bar: 0, index: 1 },
+                LookaheadAccess { bar: 1, index: 2 }
+            ]
+        );
+    }
+}
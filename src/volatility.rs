@@ -0,0 +1,563 @@
+//! Implied volatility smiles, fitting the SVI parameterization to quoted volatilities and
+//! checking it for static arbitrage, and comparisons of implied with historical volatility.
+
+use crate::linalg::solve;
+use crate::stats::{self, RollingVariance};
+use crate::{calculate_percent_rank, IndicatorError};
+
+/// Parameters of the raw SVI (stochastic volatility inspired) parameterization of a smile.
+///
+/// The total implied variance `w = iv^2 * years` at log-moneyness `k = ln(strike / forward)` is
+/// `a + b * (rho * (k - m) + sqrt((k - m)^2 + sigma^2))`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SviParams {
+    /// Level of the total variance
+    pub a: f64,
+    /// Slope of the wings
+    pub b: f64,
+    /// Skew, the rotation of the smile between -1 and 1
+    pub rho: f64,
+    /// Log-moneyness of the vertex of the smile
+    pub m: f64,
+    /// Curvature at the vertex, larger values giving a rounder smile
+    pub sigma: f64,
+}
+
+impl SviParams {
+    /// Returns the total implied variance at log-moneyness `k`.
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        self.a + self.b * (self.rho * x + (x * x + self.sigma * self.sigma).sqrt())
+    }
+
+    /// Returns the implied volatility at log-moneyness `k` of options expiring in `years`.
+    pub fn implied_volatility(&self, k: f64, years: f64) -> f64 {
+        (self.total_variance(k) / years).sqrt()
+    }
+
+    /// Evaluates Durrleman's condition at log-moneyness `k`, which is negative where the smile
+    /// implies a negative density of the terminal price and hence a butterfly arbitrage.
+    pub fn durrleman(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        let root = (x * x + self.sigma * self.sigma).sqrt();
+        let w = self.total_variance(k);
+        let slope = self.b * (self.rho + x / root);
+        let curvature = self.b * self.sigma * self.sigma / root.powi(3);
+        (1.0 - k * slope / (2.0 * w)).powi(2) - slope * slope / 4.0 * (1.0 / w + 0.25)
+            + curvature / 2.0
+    }
+
+    /// Returns `true` if the wings grow no faster than Roger Lee's moment formula allows, a total
+    /// variance slope of at most 2 on either side.
+    pub fn satisfies_wing_bound(&self) -> bool {
+        self.b * (1.0 + self.rho.abs()) <= 2.0
+    }
+
+    /// Returns the log-moneyness values among `log_moneyness` where Durrleman's condition is
+    /// violated or the total variance is not positive.
+    pub fn butterfly_violations(&self, log_moneyness: &[f64]) -> Vec<f64> {
+        log_moneyness
+            .iter()
+            .copied()
+            .filter(|k| self.total_variance(*k) <= 0.0 || self.durrleman(*k) < 0.0)
+            .collect()
+    }
+}
+
+/// An SVI smile fitted by [`fit_svi`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SviFit {
+    /// Fitted parameters
+    pub params: SviParams,
+    /// Root mean squared error of the fitted total variances
+    pub rmse: f64,
+    /// Whether the smile satisfies the wing bound and Durrleman's condition from one unit of
+    /// log-moneyness below the lowest quoted strike to one unit above the highest
+    pub arbitrage_free: bool,
+}
+
+/// Fits `a`, `rho`, and `b` by least squares for a fixed vertex `m` and curvature `sigma`, where
+/// the total variance is linear in `a`, `b * rho`, and `b`.
+///
+/// Returns the parameters and the sum of squared errors, or `None` if the system is singular or
+/// the parameters have no valid smile.
+fn fit_linear(k: &[f64], w: &[f64], m: f64, sigma: f64) -> Option<(SviParams, f64)> {
+    let rows = k
+        .iter()
+        .map(|k| [1.0, k - m, ((k - m).powi(2) + sigma * sigma).sqrt()])
+        .collect::<Vec<[f64; 3]>>();
+    let normal = (0..3)
+        .map(|i| {
+            (0..3)
+                .map(|j| rows.iter().map(|r| r[i] * r[j]).sum())
+                .collect()
+        })
+        .collect();
+    let rhs = (0..3)
+        .map(|i| rows.iter().zip(w).map(|(r, w)| r[i] * w).sum())
+        .collect();
+    let x = solve(normal, rhs)?;
+    let (a, c, b) = (x[0], x[1], x[2]);
+    // The smile needs growing wings, a skew within (-1, 1), and a non-negative minimum variance
+    if b <= 0.0 || c.abs() >= b || a + sigma * (b * b - c * c).sqrt() < 0.0 {
+        return None;
+    }
+
+    let params = SviParams {
+        a,
+        b,
+        rho: c / b,
+        m,
+        sigma,
+    };
+    let error = k
+        .iter()
+        .zip(w)
+        .map(|(k, w)| (params.total_variance(*k) - w).powi(2))
+        .sum();
+    Some((params, error))
+}
+
+/// Minimizes a function of two variables with the Nelder-Mead simplex method.
+fn nelder_mead(f: impl Fn([f64; 2]) -> f64, start: [f64; 2], step: f64) -> [f64; 2] {
+    let mut simplex = [
+        start,
+        [start[0] + step, start[1]],
+        [start[0], start[1] + step],
+    ]
+    .map(|p| (p, f(p)));
+    for _ in 0..500 {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if (simplex[2].1 - simplex[0].1).abs() <= 1e-16 * (1.0 + simplex[0].1.abs()) {
+            break;
+        }
+        let centroid = [0, 1].map(|i| (simplex[0].0[i] + simplex[1].0[i]) / 2.0);
+        let toward = |t: f64| {
+            let p = [0, 1].map(|i| centroid[i] + t * (simplex[2].0[i] - centroid[i]));
+            (p, f(p))
+        };
+        let reflected = toward(-1.0);
+        if reflected.1 < simplex[0].1 {
+            let expanded = toward(-2.0);
+            simplex[2] = if expanded.1 < reflected.1 {
+                expanded
+            } else {
+                reflected
+            };
+        } else if reflected.1 < simplex[1].1 {
+            simplex[2] = reflected;
+        } else {
+            let contracted = toward(0.5);
+            if contracted.1 < simplex[2].1 {
+                simplex[2] = contracted;
+            } else {
+                let best = simplex[0].0;
+                for vertex in simplex.iter_mut().skip(1) {
+                    let p = [0, 1].map(|i| (best[i] + vertex.0[i]) / 2.0);
+                    *vertex = (p, f(p));
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex[0].0
+}
+
+/// Fits the raw SVI parameterization to the implied volatilities of one expiry.
+///
+/// For a fixed vertex `m` and curvature `sigma` the remaining parameters follow from linear least
+/// squares, so only `m` and `sigma` are searched: first on a grid, then with a Nelder-Mead simplex
+/// from the best grid point. Parameters without a valid smile, with `b <= 0`, `|rho| >= 1`, or a
+/// negative minimum variance, are excluded from the search. The fitted smile is then checked for
+/// butterfly arbitrage.
+///
+/// # Arguments
+///
+/// * `strikes` - The strikes of the quotes.
+/// * `implied_vols` - The implied volatility of every strike.
+/// * `forward` - The forward price of the underlying for the expiry.
+/// * `years` - The time to expiry in years.
+///
+/// # Returns
+///
+/// A Result containing the [`SviFit`] or an `IndicatorError`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `strikes` and `implied_vols` differ in
+/// length, an `IndicatorError::NotEnoughData` if there are fewer than five quotes, and an
+/// `IndicatorError::InvalidInput` if a strike, volatility, the forward, or the expiry is not
+/// positive, or no valid smile fits the quotes.
+pub fn fit_svi(
+    strikes: &[f64],
+    implied_vols: &[f64],
+    forward: f64,
+    years: f64,
+) -> Result<SviFit, IndicatorError> {
+    if strikes.len() != implied_vols.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`strikes` and `implied_vols` must have the same length".to_string(),
+        ));
+    }
+    if strikes.len() < 5 {
+        return Err(IndicatorError::NotEnoughData(
+            "SVI needs at least five quotes".to_string(),
+        ));
+    }
+    if !(forward > 0.0 && years > 0.0)
+        || strikes
+            .iter()
+            .chain(implied_vols)
+            .any(|x| !(*x > 0.0 && x.is_finite()))
+    {
+        return Err(IndicatorError::InvalidInput(
+            "Strikes, volatilities, `forward`, and `years` must be positive".to_string(),
+        ));
+    }
+
+    let k = strikes
+        .iter()
+        .map(|strike| (strike / forward).ln())
+        .collect::<Vec<f64>>();
+    let w = implied_vols
+        .iter()
+        .map(|iv| iv * iv * years)
+        .collect::<Vec<f64>>();
+    let low = k.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = k.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = (high - low).max(1e-4);
+
+    // The simplex searches `ln(sigma)` so that `sigma` stays positive
+    let objective = |p: [f64; 2]| {
+        fit_linear(&k, &w, p[0], p[1].exp()).map_or(f64::INFINITY, |(_, error)| error)
+    };
+    let mut start = None;
+    for i in 0..=20 {
+        let m = low - width / 2.0 + 2.0 * width * i as f64 / 20.0;
+        for j in 0..=20 {
+            let log_sigma = (width * 1e-3).ln() + (1e3_f64).ln() * j as f64 / 20.0;
+            let error = objective([m, log_sigma]);
+            if start.is_none_or(|(_, best)| error < best) && error.is_finite() {
+                start = Some(([m, log_sigma], error));
+            }
+        }
+    }
+    let Some((start, _)) = start else {
+        return Err(IndicatorError::InvalidInput(
+            "No valid SVI smile fits the quotes".to_string(),
+        ));
+    };
+    let best = nelder_mead(objective, start, width / 20.0);
+    let Some((params, error)) = fit_linear(&k, &w, best[0], best[1].exp()) else {
+        return Err(IndicatorError::InvalidInput(
+            "No valid SVI smile fits the quotes".to_string(),
+        ));
+    };
+
+    let grid = (0..=200)
+        .map(|i| low - 1.0 + (width + 2.0) * i as f64 / 200.0)
+        .collect::<Vec<f64>>();
+    Ok(SviFit {
+        params,
+        rmse: (error / k.len() as f64).sqrt(),
+        arbitrage_free: params.satisfies_wing_bound()
+            && params.butterfly_violations(&grid).is_empty(),
+    })
+}
+
+/// Calculates the close-to-close historical volatility, the annualized sample standard deviation
+/// of the log returns of every window.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of positive prices.
+/// * `window` - The number of returns in each window, at least 2.
+/// * `periods_per_year` - The number of returns per year used to annualize, e.g. 252 for daily
+///   prices.
+///
+/// # Returns
+///
+/// A Result containing a vector of annualized volatilities or an `IndicatorError`. Each window
+/// holds the returns into `window` prices, so the value at index `i` covers the returns ending at
+/// index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is less than 2 or the length of `prices`
+/// is not greater than `window`.
+pub fn calculate_historical_volatility(
+    prices: &[f64],
+    window: usize,
+    periods_per_year: f64,
+) -> Result<Vec<f64>, IndicatorError> {
+    if window < 2 || prices.len() <= window {
+        return Err(IndicatorError::NotEnoughData(
+            "`prices` must have more than `window` items and `window` must be at least 2"
+                .to_string(),
+        ));
+    }
+
+    let mut variance = RollingVariance::new(window);
+    Ok(prices
+        .windows(2)
+        .filter_map(|w| {
+            variance.push((w[1] / w[0]).ln());
+            variance
+                .is_full()
+                .then(|| (variance.variance().unwrap_or(0.0) * periods_per_year).sqrt())
+        })
+        .collect())
+}
+
+/// Calculates the IV rank of every implied volatility, its position between the lowest and
+/// highest implied volatility of the window, from 0 at the low to 100 at the high.
+///
+/// Windows of equal implied volatilities have an IV rank of `NaN`. See [`calculate_iv_percentile`]
+/// for a measure that is not dominated by a single extreme value.
+///
+/// # Arguments
+///
+/// * `implied_vols` - A slice of implied volatilities, e.g. of the 30 day at-the-money option.
+/// * `window` - The number of values in each window, including the current one, typically 252.
+///
+/// # Returns
+///
+/// A Result containing a vector of IV ranks or an `IndicatorError`. The value at index `i` covers
+/// the window ending at index `i + window - 1`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of
+/// `implied_vols` is less than `window`.
+pub fn calculate_iv_rank(implied_vols: &[f64], window: usize) -> Result<Vec<f64>, IndicatorError> {
+    if window == 0 || implied_vols.len() < window {
+        return Err(IndicatorError::NotEnoughData(
+            "`implied_vols` must have at least `window` items and `window` must be positive"
+                .to_string(),
+        ));
+    }
+
+    let highs = stats::rolling_argmax(implied_vols, window);
+    let lows = stats::rolling_argmin(implied_vols, window);
+    Ok(highs
+        .iter()
+        .zip(&lows)
+        .zip(&implied_vols[window - 1..])
+        .map(|((high, low), iv)| {
+            let (high, low) = (implied_vols[*high], implied_vols[*low]);
+            if high > low {
+                100.0 * (iv - low) / (high - low)
+            } else {
+                f64::NAN
+            }
+        })
+        .collect())
+}
+
+/// Calculates the IV percentile of every implied volatility, the percentage of the previous
+/// `window` implied volatilities that are less than or equal to it.
+///
+/// This is the percent rank of [`crate::calculate_percent_rank`] applied to implied volatilities.
+///
+/// # Arguments
+///
+/// * `implied_vols` - A slice of implied volatilities.
+/// * `window` - The number of previous values each value is ranked against, typically 252.
+///
+/// # Returns
+///
+/// A Result containing a vector of IV percentiles between 0 and 100 or an `IndicatorError`. The
+/// value at index `i` ranks the implied volatility at index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::NotEnoughData` if `window` is zero or the length of
+/// `implied_vols` is less than or equal to `window`.
+pub fn calculate_iv_percentile(
+    implied_vols: &[f64],
+    window: usize,
+) -> Result<Vec<f64>, IndicatorError> {
+    calculate_percent_rank(implied_vols, window)
+}
+
+/// Calculates the spread of implied over historical volatility, positive when options price more
+/// volatility than the underlying has recently realized.
+///
+/// # Arguments
+///
+/// * `prices` - A slice of positive prices of the underlying.
+/// * `implied_vols` - The annualized implied volatility at every price.
+/// * `window` - The number of returns of the historical volatility, at least 2.
+/// * `periods_per_year` - The number of returns per year used to annualize the historical
+///   volatility.
+///
+/// # Returns
+///
+/// A Result containing a vector of implied minus historical volatilities or an `IndicatorError`.
+/// The value at index `i` belongs to the price at index `i + window`.
+///
+/// # Errors
+///
+/// Returns an `IndicatorError::MismatchedLength` if `prices` and `implied_vols` differ in length,
+/// and an `IndicatorError::NotEnoughData` if `window` is less than 2 or the length of `prices` is
+/// not greater than `window`.
+pub fn calculate_iv_hv_spread(
+    prices: &[f64],
+    implied_vols: &[f64],
+    window: usize,
+    periods_per_year: f64,
+) -> Result<Vec<f64>, IndicatorError> {
+    if prices.len() != implied_vols.len() {
+        return Err(IndicatorError::MismatchedLength(
+            "`prices` and `implied_vols` must have the same length".to_string(),
+        ));
+    }
+    let historical = calculate_historical_volatility(prices, window, periods_per_year)?;
+    Ok(implied_vols[window..]
+        .iter()
+        .zip(&historical)
+        .map(|(iv, hv)| iv - hv)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_svi() {
+        // Test case with quotes generated from known parameters
+        let truth = SviParams {
+            a: 0.01,
+            b: 0.1,
+            rho: -0.4,
+            m: 0.05,
+            sigma: 0.2,
+        };
+        let strikes = (0..15).map(|i| 70.0 + 5.0 * i as f64).collect::<Vec<f64>>();
+        let vols = strikes
+            .iter()
+            .map(|s| truth.implied_volatility((s / 100.0).ln(), 0.5))
+            .collect::<Vec<f64>>();
+        let fit = fit_svi(&strikes, &vols, 100.0, 0.5).unwrap();
+        assert!(fit.rmse < 1e-8);
+        assert!((fit.params.rho - truth.rho).abs() < 1e-4);
+        assert!((fit.params.m - truth.m).abs() < 1e-4);
+        assert!((fit.params.sigma - truth.sigma).abs() < 1e-4);
+        assert!(fit.arbitrage_free);
+
+        // Test case with not enough data
+        let result = fit_svi(&strikes[..4], &vols[..4], 100.0, 0.5);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+
+        // Test case with invalid input
+        let result = fit_svi(&strikes, &vols, 100.0, 0.0);
+        match result {
+            Err(IndicatorError::InvalidInput(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::InvalidInput`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_butterfly_violations() {
+        // Axel Vogt's parameters, a well known SVI smile with butterfly arbitrage
+        let vogt = SviParams {
+            a: -0.041,
+            b: 0.1331,
+            rho: 0.306,
+            m: 0.3586,
+            sigma: 0.4153,
+        };
+        let grid = (0..=300)
+            .map(|i| -1.5 + 0.01 * i as f64)
+            .collect::<Vec<f64>>();
+        assert!(vogt.satisfies_wing_bound());
+        assert!(!vogt.butterfly_violations(&grid).is_empty());
+        assert!(vogt.durrleman(0.0) > 0.0);
+
+        let wide = SviParams { b: 1.8, ..vogt };
+        assert!(!wide.satisfies_wing_bound());
+    }
+
+    #[test]
+    fn test_calculate_historical_volatility() {
+        // Test case with enough data
+        let prices = [100.0, 101.0, 99.0, 102.0, 104.0, 103.0];
+        let result = calculate_historical_volatility(&prices, 3, 252.0).unwrap();
+        let expected = [
+            0.398_371_159_039_622_45,
+            0.417_397_392_614_351_27,
+            0.325_069_595_975_409_35,
+        ];
+        assert_eq!(result.len(), 3);
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with not enough data
+        let result = calculate_historical_volatility(&prices, 1, 252.0);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_iv_rank() {
+        let implied_vols = [0.2, 0.25, 0.22, 0.3, 0.28, 0.26, 0.26, 0.26];
+        let result = calculate_iv_rank(&implied_vols, 3).unwrap();
+        assert_eq!(result.len(), 6);
+        assert!((result[0] - 40.0).abs() < 1e-9);
+        assert!((result[1] - 100.0).abs() < 1e-9);
+        assert!((result[2] - 75.0).abs() < 1e-9);
+        assert!(result[3].abs() < 1e-9);
+        assert!(result[5].is_nan());
+        assert_eq!(
+            calculate_iv_percentile(&implied_vols, 3).unwrap(),
+            calculate_percent_rank(&implied_vols, 3).unwrap()
+        );
+
+        // Test case with not enough data
+        let result = calculate_iv_rank(&implied_vols, 9);
+        match result {
+            Err(IndicatorError::NotEnoughData(_)) => (),
+            _ => {
+                panic!("Expected `IndicatorError::NotEnoughData`, found different `IndicatorError`")
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_iv_hv_spread() {
+        // Test case with enough data
+        let prices = [100.0, 101.0, 99.0, 102.0, 104.0, 103.0];
+        let implied_vols = [0.2, 0.25, 0.22, 0.3, 0.28, 0.26];
+        let result = calculate_iv_hv_spread(&prices, &implied_vols, 3, 252.0).unwrap();
+        let expected = [
+            -0.098_371_159_039_622_46,
+            -0.137_397_392_614_351_24,
+            -0.065_069_595_975_409_34,
+        ];
+        for (value, expected) in result.iter().zip(&expected) {
+            assert!((value - expected).abs() < 1e-9);
+        }
+
+        // Test case with mismatched lengths
+        let result = calculate_iv_hv_spread(&prices, &implied_vols[1..], 3, 252.0);
+        match result {
+            Err(IndicatorError::MismatchedLength(_)) => (),
+            _ => panic!(
+                "Expected `IndicatorError::MismatchedLength`, found different `IndicatorError`"
+            ),
+        }
+    }
+}